@@ -7,6 +7,8 @@ use serde_json::Value;
 use sqlx::Row;
 
 use crate::db::models::*;
+use crate::proxy::circuit::CircuitStatus;
+use crate::router::{BalanceStrategy, ModelPattern};
 use crate::Gateway;
 
 const MODELS_DEV_SNAPSHOT: &str = include_str!("../../assets/models.dev.json");
@@ -28,8 +30,26 @@ impl AdminService {
     // ── Providers ──
 
     pub async fn list_providers(&self) -> anyhow::Result<Vec<Provider>> {
+        Ok(self
+            .fetch_providers()
+            .await?
+            .into_iter()
+            .map(Provider::masked)
+            .collect())
+    }
+
+    async fn load_providers(&self) -> anyhow::Result<Vec<Provider>> {
+        self.fetch_providers()
+            .await?
+            .into_iter()
+            .map(Provider::decrypted)
+            .collect()
+    }
+
+    /// Provider rows as stored, with `api_key` still encrypted.
+    async fn fetch_providers(&self) -> anyhow::Result<Vec<Provider>> {
         let rows = sqlx::query_as::<_, Provider>(
-            "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, last_test_success, last_test_at, is_active, created_at, updated_at FROM providers ORDER BY created_at DESC",
+            "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, max_concurrency, rpm, tpm, retry_max_attempts, retry_base_delay_ms, retry_max_delay_ms, api_version, access_key_id, cloud_region, api_dialect, last_test_success, last_test_at, is_active, created_at, updated_at FROM providers ORDER BY created_at DESC",
         )
         .fetch_all(&self.gw.db)
         .await?;
@@ -40,9 +60,29 @@ impl AdminService {
         parse_provider_presets_snapshot()
     }
 
+    /// Provider with its `api_key` masked, for admin API responses.
     pub async fn get_provider(&self, id: &str) -> anyhow::Result<Provider> {
+        Ok(self.fetch_provider(id).await?.masked())
+    }
+
+    /// Return the plaintext upstream key and record the access in `audit_logs`.
+    pub async fn reveal_provider_key(&self, id: &str, actor: &str) -> anyhow::Result<String> {
+        let provider = self.load_provider(id).await?;
+        self.record_audit("provider.reveal_key", Some(id), actor, Some(&provider.name))
+            .await?;
+        tracing::warn!("provider api key revealed: provider={} actor={actor}", provider.name);
+        Ok(provider.api_key)
+    }
+
+    /// Provider with its decrypted `api_key`, for upstream calls made by the admin service.
+    async fn load_provider(&self, id: &str) -> anyhow::Result<Provider> {
+        self.fetch_provider(id).await?.decrypted()
+    }
+
+    /// Provider row as stored, with `api_key` still encrypted.
+    async fn fetch_provider(&self, id: &str) -> anyhow::Result<Provider> {
         let row = sqlx::query_as::<_, Provider>(
-            "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, last_test_success, last_test_at, is_active, created_at, updated_at FROM providers WHERE id = ?",
+            "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, max_concurrency, rpm, tpm, retry_max_attempts, retry_base_delay_ms, retry_max_delay_ms, api_version, access_key_id, cloud_region, api_dialect, last_test_success, last_test_at, is_active, created_at, updated_at FROM providers WHERE id = ?",
         )
         .bind(id)
         .fetch_one(&self.gw.db)
@@ -59,7 +99,7 @@ impl AdminService {
             .effective_models_source()
             .map(ToString::to_string);
         sqlx::query(
-            "INSERT INTO providers (id, name, vendor, protocol, base_url, preset_key, channel, models_endpoint, models_source, capabilities_source, static_models, api_key) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO providers (id, name, vendor, protocol, base_url, preset_key, channel, models_endpoint, models_source, capabilities_source, static_models, api_key, max_concurrency, rpm, tpm, retry_max_attempts, retry_base_delay_ms, retry_max_delay_ms, api_version, access_key_id, cloud_region, api_dialect) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&id)
         .bind(&name)
@@ -72,7 +112,17 @@ impl AdminService {
         .bind(&models_source)
         .bind(&input.capabilities_source)
         .bind(&input.static_models)
-        .bind(&input.api_key)
+        .bind(crate::crypto::encrypt(&input.api_key))
+        .bind(input.max_concurrency)
+        .bind(input.rpm)
+        .bind(input.tpm)
+        .bind(input.retry_max_attempts)
+        .bind(input.retry_base_delay_ms)
+        .bind(input.retry_max_delay_ms)
+        .bind(normalize_optional(input.api_version.clone()))
+        .bind(normalize_optional(input.access_key_id.clone()))
+        .bind(normalize_optional(input.cloud_region.clone()))
+        .bind(normalize_optional(input.api_dialect.clone()))
         .execute(&self.gw.db)
         .await?;
 
@@ -84,8 +134,9 @@ impl AdminService {
         id: &str,
         input: UpdateProvider,
     ) -> anyhow::Result<Provider> {
-        let current = self.get_provider(id).await?;
+        let current = self.fetch_provider(id).await?;
         let current_base_url = current.base_url.clone();
+        let current_masked = current.clone().masked().api_key;
         let models_source_input = input
             .effective_models_source()
             .map(ToString::to_string);
@@ -113,12 +164,39 @@ impl AdminService {
             .capabilities_source
             .or(current.capabilities_source);
         let static_models = input.static_models.or(current.static_models);
-        let api_key = input.api_key.unwrap_or(current.api_key);
+        // The admin API only ever hands out masked keys; echoing one back means
+        // "unchanged", in which case the stored value is kept without decrypting it.
+        let api_key = match input.api_key {
+            Some(v) if v != current_masked => crate::crypto::encrypt(&v),
+            _ => current.api_key,
+        };
+        let max_concurrency = input.max_concurrency.or(current.max_concurrency);
+        let rpm = input.rpm.or(current.rpm);
+        let tpm = input.tpm.or(current.tpm);
+        let retry_max_attempts = input.retry_max_attempts.or(current.retry_max_attempts);
+        let retry_base_delay_ms = input.retry_base_delay_ms.or(current.retry_base_delay_ms);
+        let retry_max_delay_ms = input.retry_max_delay_ms.or(current.retry_max_delay_ms);
+        let api_version = match input.api_version {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.api_version,
+        };
+        let access_key_id = match input.access_key_id {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.access_key_id,
+        };
+        let cloud_region = match input.cloud_region {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.cloud_region,
+        };
+        let api_dialect = match input.api_dialect {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.api_dialect,
+        };
         let is_active = input.is_active.unwrap_or(current.is_active);
         let base_url_changed = base_url != current_base_url;
 
         sqlx::query(
-            "UPDATE providers SET name=?, vendor=?, protocol=?, base_url=?, preset_key=?, channel=?, models_endpoint=?, models_source=?, capabilities_source=?, static_models=?, api_key=?, is_active=?, updated_at=datetime('now') WHERE id=?",
+            "UPDATE providers SET name=?, vendor=?, protocol=?, base_url=?, preset_key=?, channel=?, models_endpoint=?, models_source=?, capabilities_source=?, static_models=?, api_key=?, max_concurrency=?, rpm=?, tpm=?, retry_max_attempts=?, retry_base_delay_ms=?, retry_max_delay_ms=?, api_version=?, access_key_id=?, cloud_region=?, api_dialect=?, is_active=?, updated_at=datetime('now') WHERE id=?",
         )
         .bind(&name)
         .bind(&vendor)
@@ -131,6 +209,16 @@ impl AdminService {
         .bind(&capabilities_source)
         .bind(&static_models)
         .bind(&api_key)
+        .bind(max_concurrency)
+        .bind(rpm)
+        .bind(tpm)
+        .bind(retry_max_attempts)
+        .bind(retry_base_delay_ms)
+        .bind(retry_max_delay_ms)
+        .bind(&api_version)
+        .bind(&access_key_id)
+        .bind(&cloud_region)
+        .bind(&api_dialect)
         .bind(is_active)
         .bind(id)
         .execute(&self.gw.db)
@@ -139,15 +227,21 @@ impl AdminService {
         if base_url_changed {
             self.gw.clear_ollama_capability_cache_for_provider(id).await;
         }
+        self.gw.clear_model_capability_cache_for_provider(id).await;
+        // Config changed: give the provider a fresh chance instead of waiting out the cooldown.
+        self.gw.circuit_breakers.reset(id);
 
         self.get_provider(id).await
     }
 
     pub async fn delete_provider(&self, id: &str) -> anyhow::Result<()> {
         let route_ref_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(1) FROM routes WHERE target_provider = ?",
+            "SELECT (SELECT COUNT(1) FROM routes WHERE target_provider = ? OR fallback_provider = ?) \
+                  + (SELECT COUNT(DISTINCT route_id) FROM route_targets WHERE provider_id = ?)",
         )
         .bind(id)
+        .bind(id)
+        .bind(id)
         .fetch_one(&self.gw.db)
         .await
         .unwrap_or(0);
@@ -176,11 +270,14 @@ impl AdminService {
             return Err(e.into());
         }
         self.gw.clear_ollama_capability_cache_for_provider(id).await;
+        self.gw.clear_model_capability_cache_for_provider(id).await;
+        self.gw.circuit_breakers.reset(id);
+        self.gw.provider_limiter.forget(id);
         Ok(())
     }
 
     pub async fn test_provider(&self, id: &str) -> anyhow::Result<TestResult> {
-        let provider = self.get_provider(id).await?;
+        let provider = self.load_provider(id).await?;
         self.gw
             .clear_ollama_capability_cache_for_provider(&provider.id)
             .await;
@@ -246,7 +343,7 @@ impl AdminService {
     }
 
     pub async fn test_provider_models(&self, id: &str) -> anyhow::Result<Vec<String>> {
-        let provider = self.get_provider(id).await?;
+        let provider = self.load_provider(id).await?;
         let endpoint = provider
             .effective_models_source()
             .map(str::trim)
@@ -304,7 +401,7 @@ impl AdminService {
     }
 
     pub async fn get_provider_models(&self, id: &str) -> anyhow::Result<Vec<String>> {
-        let provider = self.get_provider(id).await?;
+        let provider = self.load_provider(id).await?;
 
         if let Some(endpoint) = resolve_models_endpoint(&provider) {
             if let Some(models) = lookup_models_dev_models(&self.gw.config.data_dir, &endpoint)? {
@@ -359,7 +456,7 @@ impl AdminService {
         provider_id: &str,
         model: &str,
     ) -> anyhow::Result<ModelCapabilities> {
-        let provider = self.get_provider(provider_id).await?;
+        let provider = self.load_provider(provider_id).await?;
         let trimmed_model = model.trim();
         if trimmed_model.is_empty() {
             anyhow::bail!("model cannot be empty");
@@ -464,11 +561,39 @@ impl AdminService {
         Ok(parse_ollama_capability(&json, model))
     }
 
+    // ── Provider Health ──
+
+    pub async fn list_provider_health(&self) -> anyhow::Result<Vec<CircuitStatus>> {
+        let names: HashMap<String, String> =
+            sqlx::query_as::<_, (String, String)>("SELECT id, name FROM providers")
+                .fetch_all(&self.gw.db)
+                .await?
+                .into_iter()
+                .collect();
+
+        Ok(self
+            .gw
+            .circuit_breakers
+            .snapshot()
+            .into_iter()
+            .map(|mut status| {
+                status.provider_name = names.get(&status.provider_id).cloned();
+                status
+            })
+            .collect())
+    }
+
+    pub async fn reset_provider_health(&self, id: &str) -> anyhow::Result<()> {
+        self.ensure_provider_exists(id).await?;
+        self.gw.circuit_breakers.reset(id);
+        Ok(())
+    }
+
     // ── Routes ──
 
     pub async fn list_routes(&self) -> anyhow::Result<Vec<Route>> {
         let rows = sqlx::query_as::<_, Route>(
-            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes ORDER BY created_at DESC",
+            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, fallback_provider, fallback_model, COALESCE(strategy, 'round_robin') AS strategy, COALESCE(priority, 0) AS priority, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes ORDER BY COALESCE(priority, 0) ASC, created_at DESC",
         )
         .fetch_all(&self.gw.db)
         .await?;
@@ -483,10 +608,14 @@ impl AdminService {
         self.ensure_route_unique(None, &input.ingress_protocol, &input.virtual_model)
             .await?;
 
+        let fallback_provider = normalize_optional(input.fallback_provider);
+        let fallback_model = normalize_optional(input.fallback_model);
+        let strategy = normalize_strategy(input.strategy.as_deref())?;
+
         let id = uuid::Uuid::new_v4().to_string();
 
         sqlx::query(
-            "INSERT INTO routes (id, name, ingress_protocol, virtual_model, match_pattern, target_provider, target_model, access_control) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO routes (id, name, ingress_protocol, virtual_model, match_pattern, target_provider, target_model, fallback_provider, fallback_model, strategy, priority, access_control) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&id)
         .bind(&name)
@@ -495,12 +624,16 @@ impl AdminService {
         .bind(input.virtual_model.trim())
         .bind(&input.target_provider)
         .bind(&input.target_model)
+        .bind(&fallback_provider)
+        .bind(&fallback_model)
+        .bind(&strategy)
+        .bind(input.priority.unwrap_or(0))
         .bind(input.access_control.unwrap_or(false))
         .execute(&self.gw.db)
         .await?;
 
         let route = sqlx::query_as::<_, Route>(
-            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
+            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, fallback_provider, fallback_model, COALESCE(strategy, 'round_robin') AS strategy, COALESCE(priority, 0) AS priority, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
         )
         .bind(&id)
         .fetch_one(&self.gw.db)
@@ -512,7 +645,7 @@ impl AdminService {
 
     pub async fn update_route(&self, id: &str, input: UpdateRoute) -> anyhow::Result<Route> {
         let current = sqlx::query_as::<_, Route>(
-            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
+            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, fallback_provider, fallback_model, COALESCE(strategy, 'round_robin') AS strategy, COALESCE(priority, 0) AS priority, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
         )
         .bind(id)
         .fetch_one(&self.gw.db)
@@ -524,6 +657,17 @@ impl AdminService {
         let virtual_model = input.virtual_model.unwrap_or(current.virtual_model);
         let target_provider = input.target_provider.unwrap_or(current.target_provider);
         let target_model = input.target_model.unwrap_or(current.target_model);
+        // An empty string clears the fallback; an omitted field keeps it.
+        let fallback_provider = match input.fallback_provider {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.fallback_provider,
+        };
+        let fallback_model = match input.fallback_model {
+            Some(v) => normalize_optional(Some(v)),
+            None => current.fallback_model,
+        };
+        let strategy = normalize_strategy(Some(input.strategy.as_deref().unwrap_or(&current.strategy)))?;
+        let priority = input.priority.unwrap_or(current.priority);
         let access_control = input.access_control.unwrap_or(current.access_control);
         let is_active = input.is_active.unwrap_or(current.is_active);
         ensure_protocol(&ingress_protocol)?;
@@ -532,7 +676,7 @@ impl AdminService {
             .await?;
 
         sqlx::query(
-            "UPDATE routes SET name=?, ingress_protocol=?, virtual_model=?, match_pattern=?, target_provider=?, target_model=?, access_control=?, is_active=? WHERE id=?",
+            "UPDATE routes SET name=?, ingress_protocol=?, virtual_model=?, match_pattern=?, target_provider=?, target_model=?, fallback_provider=?, fallback_model=?, strategy=?, priority=?, access_control=?, is_active=? WHERE id=?",
         )
         .bind(&name)
         .bind(ingress_protocol.trim().to_lowercase())
@@ -540,6 +684,10 @@ impl AdminService {
         .bind(virtual_model.trim())
         .bind(&target_provider)
         .bind(&target_model)
+        .bind(&fallback_provider)
+        .bind(&fallback_model)
+        .bind(&strategy)
+        .bind(priority)
         .bind(access_control)
         .bind(is_active)
         .bind(id)
@@ -549,7 +697,7 @@ impl AdminService {
         self.gw.route_cache.write().await.reload(&self.gw.db).await?;
 
         sqlx::query_as::<_, Route>(
-            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
+            "SELECT id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol, COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model, target_provider, target_model, fallback_provider, fallback_model, COALESCE(strategy, 'round_robin') AS strategy, COALESCE(priority, 0) AS priority, COALESCE(access_control, 0) AS access_control, is_active, created_at FROM routes WHERE id = ?",
         )
         .bind(id)
         .fetch_one(&self.gw.db)
@@ -558,6 +706,10 @@ impl AdminService {
     }
 
     pub async fn delete_route(&self, id: &str) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM route_targets WHERE route_id = ?")
+            .bind(id)
+            .execute(&self.gw.db)
+            .await?;
         sqlx::query("DELETE FROM routes WHERE id = ?")
             .bind(id)
             .execute(&self.gw.db)
@@ -566,6 +718,100 @@ impl AdminService {
         Ok(())
     }
 
+    // ── Route Targets ──
+
+    pub async fn list_route_targets(&self, route_id: &str) -> anyhow::Result<Vec<RouteTarget>> {
+        let rows = sqlx::query_as::<_, RouteTarget>(
+            "SELECT id, route_id, provider_id, model, weight, priority, is_active, created_at FROM route_targets WHERE route_id = ? ORDER BY priority ASC, created_at ASC",
+        )
+        .bind(route_id)
+        .fetch_all(&self.gw.db)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn create_route_target(
+        &self,
+        route_id: &str,
+        input: CreateRouteTarget,
+    ) -> anyhow::Result<RouteTarget> {
+        self.ensure_route_exists(route_id).await?;
+        self.ensure_provider_exists(&input.provider_id).await?;
+        let model = normalize_name(&input.model, "target model")?;
+        let weight = input.weight.unwrap_or(1);
+        ensure_weight(weight)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO route_targets (id, route_id, provider_id, model, weight, priority) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(route_id)
+        .bind(&input.provider_id)
+        .bind(&model)
+        .bind(weight)
+        .bind(input.priority.unwrap_or(0))
+        .execute(&self.gw.db)
+        .await?;
+
+        self.gw.route_cache.write().await.reload(&self.gw.db).await?;
+        self.get_route_target(route_id, &id).await
+    }
+
+    pub async fn update_route_target(
+        &self,
+        route_id: &str,
+        id: &str,
+        input: UpdateRouteTarget,
+    ) -> anyhow::Result<RouteTarget> {
+        let current = self.get_route_target(route_id, id).await?;
+
+        let provider_id = input.provider_id.unwrap_or(current.provider_id);
+        self.ensure_provider_exists(&provider_id).await?;
+        let model = normalize_name(&input.model.unwrap_or(current.model), "target model")?;
+        let weight = input.weight.unwrap_or(current.weight);
+        ensure_weight(weight)?;
+        let priority = input.priority.unwrap_or(current.priority);
+        let is_active = input.is_active.unwrap_or(current.is_active);
+
+        sqlx::query(
+            "UPDATE route_targets SET provider_id=?, model=?, weight=?, priority=?, is_active=? WHERE id=? AND route_id=?",
+        )
+        .bind(&provider_id)
+        .bind(&model)
+        .bind(weight)
+        .bind(priority)
+        .bind(is_active)
+        .bind(id)
+        .bind(route_id)
+        .execute(&self.gw.db)
+        .await?;
+
+        self.gw.route_cache.write().await.reload(&self.gw.db).await?;
+        self.get_route_target(route_id, id).await
+    }
+
+    pub async fn delete_route_target(&self, route_id: &str, id: &str) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM route_targets WHERE id = ? AND route_id = ?")
+            .bind(id)
+            .bind(route_id)
+            .execute(&self.gw.db)
+            .await?;
+        self.gw.route_cache.write().await.reload(&self.gw.db).await?;
+        Ok(())
+    }
+
+    async fn get_route_target(&self, route_id: &str, id: &str) -> anyhow::Result<RouteTarget> {
+        sqlx::query_as::<_, RouteTarget>(
+            "SELECT id, route_id, provider_id, model, weight, priority, is_active, created_at FROM route_targets WHERE id = ? AND route_id = ?",
+        )
+        .bind(id)
+        .bind(route_id)
+        .fetch_optional(&self.gw.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("route target not found: {id}"))
+    }
+
     // ── API Keys ──
 
     pub async fn list_api_keys(&self) -> anyhow::Result<Vec<ApiKeyWithBindings>> {
@@ -691,6 +937,7 @@ impl AdminService {
             .bind(id)
             .execute(&self.gw.db)
             .await?;
+        self.gw.rate_limiter.forget(id);
         Ok(())
     }
 
@@ -723,7 +970,7 @@ impl AdminService {
             .unwrap_or(0);
 
         let data_sql = format!(
-            "SELECT id, created_at, api_key_id, ingress_protocol, egress_protocol, request_model, actual_model, provider_name, status_code, duration_ms, input_tokens, output_tokens, is_stream, is_tool_call, error_message, request_preview, response_preview FROM request_logs WHERE {where_sql} ORDER BY created_at DESC LIMIT {limit} OFFSET {offset}"
+            "SELECT id, created_at, api_key_id, ingress_protocol, egress_protocol, request_model, actual_model, provider_name, status_code, duration_ms, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens, is_stream, is_tool_call, error_message, request_preview, response_preview FROM request_logs WHERE {where_sql} ORDER BY created_at DESC LIMIT {limit} OFFSET {offset}"
         );
         let items = sqlx::query_as::<_, RequestLog>(&data_sql)
             .fetch_all(&self.gw.db)
@@ -745,6 +992,8 @@ impl AdminService {
                     COUNT(*) as total_requests,
                     COALESCE(SUM(input_tokens), 0) as total_input_tokens,
                     COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                    COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens,
+                    COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                     COALESCE(AVG(duration_ms), 0) as avg_duration_ms,
                     COALESCE(SUM(CASE WHEN status_code >= 400 THEN 1 ELSE 0 END), 0) as error_count
                 FROM request_logs
@@ -759,6 +1008,8 @@ impl AdminService {
                     COUNT(*) as total_requests,
                     COALESCE(SUM(input_tokens), 0) as total_input_tokens,
                     COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                    COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens,
+                    COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                     COALESCE(AVG(duration_ms), 0) as avg_duration_ms,
                     COALESCE(SUM(CASE WHEN status_code >= 400 THEN 1 ELSE 0 END), 0) as error_count
                 FROM request_logs"#,
@@ -778,6 +1029,8 @@ impl AdminService {
                 SUM(CASE WHEN status_code >= 400 THEN 1 ELSE 0 END) as error_count,
                 COALESCE(SUM(input_tokens), 0) as total_input_tokens,
                 COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens,
+                COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                 COALESCE(AVG(duration_ms), 0) as avg_duration_ms
             FROM request_logs
             WHERE created_at >= datetime('now', ? || ' hours')
@@ -798,6 +1051,8 @@ impl AdminService {
                     COUNT(*) as request_count,
                     COALESCE(SUM(input_tokens), 0) as total_input_tokens,
                     COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                    COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens,
+                    COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                     COALESCE(AVG(duration_ms), 0) as avg_duration_ms
                 FROM request_logs
                 WHERE created_at >= datetime('now', ? || ' hours')
@@ -814,6 +1069,8 @@ impl AdminService {
                     COUNT(*) as request_count,
                     COALESCE(SUM(input_tokens), 0) as total_input_tokens,
                     COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                    COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens,
+                    COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                     COALESCE(AVG(duration_ms), 0) as avg_duration_ms
                 FROM request_logs
                 GROUP BY actual_model
@@ -882,11 +1139,91 @@ impl AdminService {
         Ok(())
     }
 
+    // ── Audit ──
+
+    pub async fn list_audit_logs(&self, limit: Option<i64>) -> anyhow::Result<Vec<AuditLog>> {
+        let rows = sqlx::query_as::<_, AuditLog>(
+            "SELECT id, created_at, action, target_id, actor, detail FROM audit_logs ORDER BY created_at DESC, rowid DESC LIMIT ?",
+        )
+        .bind(limit.unwrap_or(100).clamp(1, 1000))
+        .fetch_all(&self.gw.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn record_audit(
+        &self,
+        action: &str,
+        target_id: Option<&str>,
+        actor: &str,
+        detail: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO audit_logs (id, action, target_id, actor, detail) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(action)
+        .bind(target_id)
+        .bind(actor)
+        .bind(detail)
+        .execute(&self.gw.db)
+        .await?;
+        Ok(())
+    }
+
     // ── Config Import/Export ──
 
-    pub async fn export_config(&self) -> anyhow::Result<ExportData> {
-        let providers = self.list_providers().await?;
-        let routes = self.list_routes().await?;
+    /// Export providers, routes and settings. Provider keys are masked unless
+    /// `reveal_keys` is set, which is audited like a per-provider reveal.
+    pub async fn export_config(&self, reveal_keys: bool, actor: &str) -> anyhow::Result<ExportData> {
+        let providers = if reveal_keys {
+            let providers = self.load_providers().await?;
+            self.record_audit("config.export_reveal_keys", None, actor, None)
+                .await?;
+            tracing::warn!("config exported with plaintext provider keys: actor={actor}");
+            providers
+        } else {
+            self.fetch_providers()
+                .await?
+                .into_iter()
+                .map(Provider::masked)
+                .collect()
+        };
+        self.record_audit("config.export", None, actor, None).await?;
+        let provider_names: HashMap<String, String> = providers
+            .iter()
+            .map(|p| (p.id.clone(), p.name.clone()))
+            .collect();
+        let provider_name = |id: &str| provider_names.get(id).cloned().unwrap_or_default();
+        let mut routes = Vec::new();
+        for r in self.list_routes().await? {
+            let targets = self
+                .list_route_targets(&r.id)
+                .await?
+                .into_iter()
+                .map(|t| ExportRouteTarget {
+                    provider_name: provider_name(&t.provider_id),
+                    model: t.model,
+                    weight: t.weight,
+                    priority: t.priority,
+                    is_active: t.is_active,
+                })
+                .collect();
+            routes.push(ExportRoute {
+                name: r.name,
+                ingress_protocol: r.ingress_protocol,
+                virtual_model: r.virtual_model,
+                target_provider_name: provider_name(&r.target_provider),
+                target_model: r.target_model,
+                fallback_provider_name: r.fallback_provider.as_deref().map(provider_name),
+                fallback_model: r.fallback_model,
+                strategy: Some(r.strategy),
+                targets,
+                priority: r.priority,
+                access_control: r.access_control,
+                is_active: r.is_active,
+            });
+        }
         let settings: Vec<(String, String)> =
             sqlx::query_as("SELECT key, value FROM settings")
                 .fetch_all(&self.gw.db)
@@ -894,6 +1231,7 @@ impl AdminService {
 
         Ok(ExportData {
             version: 1,
+            keys_masked: !reveal_keys,
             providers: providers
                 .into_iter()
                 .map(|p| ExportProvider {
@@ -908,21 +1246,20 @@ impl AdminService {
                     capabilities_source: p.capabilities_source,
                     static_models: p.static_models,
                     api_key: p.api_key,
+                    max_concurrency: p.max_concurrency,
+                    rpm: p.rpm,
+                    tpm: p.tpm,
+                    retry_max_attempts: p.retry_max_attempts,
+                    retry_base_delay_ms: p.retry_base_delay_ms,
+                    retry_max_delay_ms: p.retry_max_delay_ms,
+                    api_version: p.api_version,
+                    access_key_id: p.access_key_id,
+                    cloud_region: p.cloud_region,
+                    api_dialect: p.api_dialect,
                     is_active: p.is_active,
                 })
                 .collect(),
-            routes: routes
-                .into_iter()
-                .map(|r| ExportRoute {
-                    name: r.name,
-                    ingress_protocol: r.ingress_protocol,
-                    virtual_model: r.virtual_model,
-                    target_provider_name: String::new(),
-                    target_model: r.target_model,
-                    access_control: r.access_control,
-                    is_active: r.is_active,
-                })
-                .collect(),
+            routes,
             settings: settings.into_iter().collect(),
         })
     }
@@ -931,6 +1268,7 @@ impl AdminService {
         let mut providers_imported = 0u32;
         let mut routes_imported = 0u32;
         let mut settings_imported = 0u32;
+        let mut targets_skipped = 0u32;
 
         for p in &data.providers {
             let exists = sqlx::query_scalar::<_, i64>(
@@ -954,7 +1292,21 @@ impl AdminService {
                         models_source: p.models_source.clone(),
                         capabilities_source: p.capabilities_source.clone(),
                         static_models: p.static_models.clone(),
-                        api_key: p.api_key.clone(),
+                        api_key: if data.keys_masked {
+                            String::new()
+                        } else {
+                            p.api_key.clone()
+                        },
+                        max_concurrency: p.max_concurrency,
+                        rpm: p.rpm,
+                        tpm: p.tpm,
+                        retry_max_attempts: p.retry_max_attempts,
+                        retry_base_delay_ms: p.retry_base_delay_ms,
+                        retry_max_delay_ms: p.retry_max_delay_ms,
+                        api_version: p.api_version.clone(),
+                        access_key_id: p.access_key_id.clone(),
+                        cloud_region: p.cloud_region.clone(),
+                        api_dialect: p.api_dialect.clone(),
                     })
                     .await
                     .is_ok()
@@ -975,25 +1327,54 @@ impl AdminService {
                     .unwrap_or(0);
 
             if exists == 0 {
-                let provider_id = sqlx::query_scalar::<_, String>(
-                    "SELECT id FROM providers LIMIT 1",
-                )
-                .fetch_optional(&self.gw.db)
-                .await?;
+                // Older exports carry no provider name; they fall back to any provider.
+                let provider_id = match self.provider_id_by_name(&r.target_provider_name).await? {
+                    Some(id) => Some(id),
+                    None => sqlx::query_scalar::<_, String>("SELECT id FROM providers LIMIT 1")
+                        .fetch_optional(&self.gw.db)
+                        .await?,
+                };
+
+                let fallback_provider = match r.fallback_provider_name.as_deref() {
+                    Some(name) => {
+                        let id = self.provider_id_by_name(name).await?;
+                        if id.is_none() {
+                            tracing::warn!(
+                                "import: route {} fallback provider {name} not found, dropping fallback",
+                                r.name
+                            );
+                        }
+                        id
+                    }
+                    None => None,
+                };
+                // A fallback model alone retries on the primary provider; keep it
+                // unless it belonged to a provider that could not be resolved.
+                let fallback_model = if r.fallback_provider_name.is_some() && fallback_provider.is_none() {
+                    None
+                } else {
+                    r.fallback_model.clone()
+                };
 
                 if let Some(pid) = provider_id {
-                    if self
+                    if let Ok(route) = self
                         .create_route(CreateRoute {
                             name: r.name.clone(),
                             ingress_protocol: r.ingress_protocol.clone(),
                             virtual_model: r.virtual_model.clone(),
                             target_provider: pid,
                             target_model: r.target_model.clone(),
+                            fallback_provider,
+                            fallback_model,
+                            strategy: r.strategy.clone(),
+                            priority: Some(r.priority),
                             access_control: Some(r.access_control),
                         })
                         .await
-                        .is_ok()
                     {
+                        targets_skipped += self
+                            .import_route_targets(&route.id, &r.name, &r.targets)
+                            .await?;
                         routes_imported += 1;
                     }
                 }
@@ -1009,9 +1390,91 @@ impl AdminService {
             providers_imported,
             routes_imported,
             settings_imported,
+            targets_skipped,
         })
     }
 
+    /// Import a route's targets, skipping (and counting) any that cannot be
+    /// created so one bad entry does not abort the rest of the import.
+    async fn import_route_targets(
+        &self,
+        route_id: &str,
+        route_name: &str,
+        targets: &[ExportRouteTarget],
+    ) -> anyhow::Result<u32> {
+        let mut skipped = 0u32;
+        for t in targets {
+            let Some(provider_id) = self.provider_id_by_name(&t.provider_name).await? else {
+                tracing::warn!(
+                    "import: route {route_name} target provider {} not found, skipping target",
+                    t.provider_name
+                );
+                skipped += 1;
+                continue;
+            };
+            let target = match self
+                .create_route_target(
+                    route_id,
+                    CreateRouteTarget {
+                        provider_id,
+                        model: t.model.clone(),
+                        weight: Some(t.weight),
+                        priority: Some(t.priority),
+                    },
+                )
+                .await
+            {
+                Ok(target) => target,
+                Err(e) => {
+                    tracing::warn!(
+                        "import: route {route_name} target {}/{} rejected, skipping target: {e}",
+                        t.provider_name,
+                        t.model
+                    );
+                    skipped += 1;
+                    continue;
+                }
+            };
+            if !t.is_active
+                && let Err(e) = self
+                    .update_route_target(
+                        route_id,
+                        &target.id,
+                        UpdateRouteTarget {
+                            provider_id: None,
+                            model: None,
+                            weight: None,
+                            priority: None,
+                            is_active: Some(false),
+                        },
+                    )
+                    .await
+            {
+                // Never leave a target live that the export had disabled.
+                tracing::warn!(
+                    "import: route {route_name} target {}/{} could not be disabled, skipping target: {e}",
+                    t.provider_name,
+                    t.model
+                );
+                self.delete_route_target(route_id, &target.id).await?;
+                skipped += 1;
+            }
+        }
+        Ok(skipped)
+    }
+
+    async fn provider_id_by_name(&self, name: &str) -> anyhow::Result<Option<String>> {
+        if name.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(sqlx::query_scalar::<_, String>(
+            "SELECT id FROM providers WHERE lower(trim(name)) = lower(trim(?)) LIMIT 1",
+        )
+        .bind(name)
+        .fetch_optional(&self.gw.db)
+        .await?)
+    }
+
     async fn ensure_route_unique(
         &self,
         exclude_id: Option<&str>,
@@ -1056,6 +1519,28 @@ impl AdminService {
         Ok(())
     }
 
+    async fn ensure_route_exists(&self, id: &str) -> anyhow::Result<()> {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(1) FROM routes WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.gw.db)
+            .await?;
+        if exists == 0 {
+            anyhow::bail!("route not found: {id}");
+        }
+        Ok(())
+    }
+
+    async fn ensure_provider_exists(&self, id: &str) -> anyhow::Result<()> {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(1) FROM providers WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.gw.db)
+            .await?;
+        if exists == 0 {
+            anyhow::bail!("provider not found: {id}");
+        }
+        Ok(())
+    }
+
     async fn ensure_provider_name_unique(
         &self,
         exclude_id: Option<&str>,
@@ -1211,7 +1696,7 @@ fn coded_error(code: &str, message: &str, params: Value) -> anyhow::Error {
 
 fn ensure_protocol(protocol: &str) -> anyhow::Result<()> {
     match protocol.trim().to_lowercase().as_str() {
-        "openai" | "anthropic" | "gemini" => Ok(()),
+        "openai" | "anthropic" | "gemini" | "ollama" => Ok(()),
         _ => anyhow::bail!("unsupported ingress protocol: {protocol}"),
     }
 }
@@ -1220,6 +1705,7 @@ fn ensure_virtual_model(model: &str) -> anyhow::Result<()> {
     if model.trim().is_empty() {
         anyhow::bail!("virtual_model cannot be empty");
     }
+    ModelPattern::parse(model)?;
     Ok(())
 }
 
@@ -1231,6 +1717,39 @@ fn normalize_name(name: &str, field: &str) -> anyhow::Result<String> {
     Ok(trimmed.to_string())
 }
 
+fn normalize_strategy(strategy: Option<&str>) -> anyhow::Result<String> {
+    let strategy: BalanceStrategy = strategy.unwrap_or_default().parse()?;
+    Ok(strategy.to_string())
+}
+
+fn ensure_weight(weight: i32) -> anyhow::Result<()> {
+    if weight < 0 {
+        anyhow::bail!("target weight cannot be negative");
+    }
+    Ok(())
+}
+
+/// Mask a secret for display, e.g. `sk-…abcd`. Short values keep no characters.
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        1..=8 => "…".to_string(),
+        9..=15 => format!("…{}", chars[chars.len() - 4..].iter().collect::<String>()),
+        _ => format!(
+            "{}…{}",
+            chars[..3].iter().collect::<String>(),
+            chars[chars.len() - 4..].iter().collect::<String>()
+        ),
+    }
+}
+
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 fn normalize_vendor(vendor: Option<&str>) -> Option<String> {
     vendor
         .map(str::trim)
@@ -1247,6 +1766,15 @@ fn resolve_models_endpoint(provider: &Provider) -> Option<String> {
     }
 
     let base = provider.base_url.trim_end_matches('/');
+    if crate::proxy::client::is_azure_provider(provider) {
+        // Routes name deployments, which the data plane only lists under this api-version.
+        let base = base.strip_suffix("/openai").unwrap_or(base);
+        return Some(format!("{base}/openai/deployments?api-version=2022-12-01"));
+    }
+    if crate::proxy::client::is_vertex_provider(provider) {
+        // Vertex publisher models are not listable with the data-plane token.
+        return None;
+    }
     match provider.protocol.as_str() {
         "openai" | "anthropic" => {
             let has_base_path = reqwest::Url::parse(base)
@@ -1263,6 +1791,10 @@ fn resolve_models_endpoint(provider: &Provider) -> Option<String> {
             }
         }
         "gemini" => Some(format!("{base}/v1beta/models")),
+        "ollama" => Some(format!(
+            "{}/api/tags",
+            crate::proxy::client::ollama_native_base(base)
+        )),
         _ => None,
     }
 }
@@ -1276,6 +1808,10 @@ fn build_model_headers(
     let is_google_vendor = vendor
         .map(str::trim)
         .is_some_and(|value| value.eq_ignore_ascii_case("google"));
+    if vendor.is_some_and(|value| value.trim().eq_ignore_ascii_case("azure")) {
+        headers.insert("api-key", HeaderValue::from_str(api_key)?);
+        return Ok(headers);
+    }
     match protocol {
         "anthropic" => {
             headers.insert("x-api-key", HeaderValue::from_str(api_key)?);
@@ -1338,6 +1874,17 @@ fn extract_models_from_response(protocol: &str, vendor: Option<&str>, json: &Val
             .collect::<Vec<_>>();
     }
 
+    if models.is_empty() && protocol == "ollama" {
+        models = json
+            .get("models")
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item.get("name").and_then(|value| value.as_str()))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+    }
+
     models.sort();
     models.dedup();
     models
@@ -1772,3 +2319,16 @@ fn to_models_dev_capability(vendor_key: &str, model: &ModelsDevModelEntry) -> Mo
         output_cost: model.cost.output,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::mask_secret;
+
+    #[test]
+    fn mask_secret_keeps_prefix_and_suffix() {
+        assert_eq!(mask_secret("sk-proj-1234567890abcd"), "sk-…abcd");
+        assert_eq!(mask_secret("short-key-abcd"), "…abcd");
+        assert_eq!(mask_secret("tiny"), "…");
+        assert_eq!(mask_secret(""), "");
+    }
+}
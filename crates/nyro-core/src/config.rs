@@ -7,6 +7,24 @@ pub struct GatewayConfig {
     pub proxy_cors_origins: Vec<String>,
     pub data_dir: PathBuf,
     pub auth_key: Option<String>,
+    /// Consecutive upstream failures before a provider's circuit opens.
+    pub circuit_failure_threshold: u32,
+    /// Seconds an open circuit rejects traffic before admitting a probe.
+    pub circuit_cooldown_secs: u64,
+    /// Seconds a request waits for a free slot on a provider at its
+    /// `max_concurrency` before being rejected with 429 (0 = reject at once).
+    pub provider_queue_timeout_secs: u64,
+    /// Download remote images for upstreams that only accept inline bytes.
+    pub fetch_remote_media: bool,
+    /// Largest remote image, in bytes, the gateway will download.
+    pub media_fetch_max_bytes: u64,
+    /// Seconds allowed for one remote image download.
+    pub media_fetch_timeout_secs: u64,
+    /// Let media downloads reach loopback, private and link-local addresses.
+    pub media_fetch_allow_private: bool,
+    /// Send the extracted text of documents (PDFs) to upstreams that cannot
+    /// take the file itself, instead of rejecting the request.
+    pub document_text_fallback: bool,
 }
 
 impl Default for GatewayConfig {
@@ -17,6 +35,14 @@ impl Default for GatewayConfig {
             proxy_cors_origins: Vec::new(),
             data_dir: default_data_dir(),
             auth_key: None,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 30,
+            provider_queue_timeout_secs: 10,
+            fetch_remote_media: false,
+            media_fetch_max_bytes: 20 * 1024 * 1024,
+            media_fetch_timeout_secs: 10,
+            media_fetch_allow_private: false,
+            document_text_fallback: false,
         }
     }
 }
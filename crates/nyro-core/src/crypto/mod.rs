@@ -1,3 +1,7 @@
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
 use aes_gcm::aead::{Aead, KeyInit, OsRng};
 use aes_gcm::{Aes256Gcm, Nonce};
 use base64::Engine;
@@ -6,31 +10,133 @@ const KEYRING_SERVICE: &str = "nyro-gateway";
 const KEYRING_USER: &str = "master-key";
 const NONCE_LEN: usize = 12;
 
-fn get_or_create_master_key() -> anyhow::Result<[u8; 32]> {
-    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+/// Base64-encoded 32-byte key that overrides the keyring and key file.
+pub const MASTER_KEY_ENV: &str = "NYRO_MASTER_KEY";
+/// Key file used when no OS secret service is available (headless Linux).
+pub const MASTER_KEY_FILE: &str = "master.key";
+
+static MASTER_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// Resolve the master key once for this process. Lookup order:
+/// `NYRO_MASTER_KEY`, an existing `<data_dir>/master.key`, the OS keyring,
+/// and finally a newly generated key persisted to the keyring or, when the
+/// keyring cannot store it, to `<data_dir>/master.key`.
+pub fn init(data_dir: &Path) -> anyhow::Result<()> {
+    if MASTER_KEY.get().is_none() {
+        let key = resolve_master_key(data_dir)?;
+        let _ = MASTER_KEY.set(key);
+    }
+    Ok(())
+}
+
+fn master_key() -> anyhow::Result<[u8; 32]> {
+    if let Some(key) = MASTER_KEY.get() {
+        return Ok(*key);
+    }
+    init(&crate::config::GatewayConfig::default().data_dir)?;
+    MASTER_KEY
+        .get()
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("master key unavailable"))
+}
+
+fn resolve_master_key(data_dir: &Path) -> anyhow::Result<[u8; 32]> {
+    if let Ok(value) = std::env::var(MASTER_KEY_ENV) {
+        return decode_key(&value).map_err(|e| anyhow::anyhow!("invalid {MASTER_KEY_ENV}: {e}"));
+    }
+
+    let key_path = data_dir.join(MASTER_KEY_FILE);
+    if key_path.exists() {
+        return decode_key(&std::fs::read_to_string(&key_path)?);
+    }
+
+    // Only a confirmed missing entry may lead to a new key: any other read
+    // failure (locked keychain, D-Bus timeout) must not replace the key that
+    // existing `enc:` rows were written with.
+    if let Some(key) = read_keyring_key()? {
+        return Ok(key);
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(Aes256Gcm::generate_key(OsRng).as_slice());
+    if store_keyring_key(&key) {
+        return Ok(key);
+    }
+
+    tracing::warn!(
+        "OS keyring unavailable, storing master key in {}",
+        key_path.display()
+    );
+    write_key_file(&key_path, &key)?;
+    Ok(key)
+}
 
+fn decode_key(b64: &str) -> anyhow::Result<[u8; 32]> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(b64.trim())?;
+    if bytes.len() != 32 {
+        anyhow::bail!("master key must be 32 bytes, got {}", bytes.len());
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+/// `Ok(None)` only when the keyring reports no entry; read failures and a
+/// malformed stored key are errors.
+fn read_keyring_key() -> anyhow::Result<Option<[u8; 32]>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| anyhow::anyhow!("failed to open keyring entry for master key: {e}"))?;
     match entry.get_password() {
-        Ok(b64) => {
-            let bytes = base64::engine::general_purpose::STANDARD.decode(&b64)?;
-            let mut key = [0u8; 32];
-            if bytes.len() >= 32 {
-                key.copy_from_slice(&bytes[..32]);
-            }
-            Ok(key)
-        }
-        Err(_) => {
-            let key = Aes256Gcm::generate_key(OsRng);
-            let b64 = base64::engine::general_purpose::STANDARD.encode(key.as_slice());
-            let _ = entry.set_password(&b64);
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(key.as_slice());
-            Ok(arr)
-        }
+        Ok(b64) => decode_key(&b64)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("malformed master key in keyring: {e}")),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("failed to read master key from keyring: {e}")),
+    }
+}
+
+/// Store the key and confirm it can be read back through a fresh entry, so
+/// non-persistent credential stores are not mistaken for a working keyring.
+/// An entry that exists by the time of writing is never overwritten.
+fn store_keyring_key(key: &[u8; 32]) -> bool {
+    let b64 = base64::engine::general_purpose::STANDARD.encode(key);
+    let stored = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .and_then(|entry| match entry.get_password() {
+            Err(keyring::Error::NoEntry) => entry.set_password(&b64),
+            Ok(_) => Err(keyring::Error::Invalid(
+                KEYRING_USER.to_string(),
+                "entry already exists".to_string(),
+            )),
+            Err(e) => Err(e),
+        })
+        .is_ok();
+    stored && matches!(read_keyring_key(), Ok(Some(read)) if read == *key)
+}
+
+fn write_key_file(path: &Path, key: &[u8; 32]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let b64 = base64::engine::general_purpose::STANDARD.encode(key);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
     }
+    let mut file = options.open(path)?;
+    file.write_all(b64.as_bytes())?;
+    Ok(())
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with("enc:")
 }
 
 pub fn encrypt(plaintext: &str) -> String {
-    let Ok(key_bytes) = get_or_create_master_key() else {
+    let Ok(key_bytes) = master_key() else {
         return plaintext.to_string();
     };
 
@@ -51,31 +157,30 @@ pub fn encrypt(plaintext: &str) -> String {
     }
 }
 
-pub fn decrypt(ciphertext: &str) -> String {
+/// Plaintext passes through unchanged; an `enc:` value that cannot be
+/// decrypted (wrong master key, corrupt data) is an error rather than being
+/// handed back, so ciphertext is never mistaken for an upstream key.
+pub fn decrypt(ciphertext: &str) -> anyhow::Result<String> {
     let Some(b64) = ciphertext.strip_prefix("enc:") else {
-        return ciphertext.to_string();
-    };
-
-    let Ok(key_bytes) = get_or_create_master_key() else {
-        return ciphertext.to_string();
-    };
-
-    let Ok(data) = base64::engine::general_purpose::STANDARD.decode(b64) else {
-        return ciphertext.to_string();
+        return Ok(ciphertext.to_string());
     };
 
+    let key_bytes = master_key()?;
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .map_err(|e| anyhow::anyhow!("encrypted value is not valid base64: {e}"))?;
     if data.len() < NONCE_LEN + 1 {
-        return ciphertext.to_string();
+        anyhow::bail!("encrypted value is truncated");
     }
 
     let (nonce_bytes, ct) = data.split_at(NONCE_LEN);
     let nonce = Nonce::from_slice(nonce_bytes);
     let cipher = Aes256Gcm::new_from_slice(&key_bytes).unwrap();
 
-    match cipher.decrypt(nonce, ct) {
-        Ok(plaintext) => String::from_utf8(plaintext).unwrap_or_else(|_| ciphertext.to_string()),
-        Err(_) => ciphertext.to_string(),
-    }
+    let plaintext = cipher
+        .decrypt(nonce, ct)
+        .map_err(|_| anyhow::anyhow!("failed to decrypt value with the current master key"))?;
+    String::from_utf8(plaintext).map_err(|_| anyhow::anyhow!("decrypted value is not UTF-8"))
 }
 
 fn rand_nonce() -> [u8; NONCE_LEN] {
@@ -91,15 +196,41 @@ mod tests {
 
     #[test]
     fn roundtrip() {
+        let dir = std::env::temp_dir().join(format!("nyro-crypto-{}", uuid::Uuid::new_v4()));
+        init(&dir).unwrap();
         let original = "sk-test-key-12345";
         let encrypted = encrypt(original);
         assert!(encrypted.starts_with("enc:"));
-        let decrypted = decrypt(&encrypted);
+        let decrypted = decrypt(&encrypted).unwrap();
         assert_eq!(decrypted, original);
     }
 
     #[test]
     fn plaintext_passthrough() {
-        assert_eq!(decrypt("sk-plain"), "sk-plain");
+        assert_eq!(decrypt("sk-plain").unwrap(), "sk-plain");
+    }
+
+    #[test]
+    fn undecryptable_value_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("nyro-crypto-{}", uuid::Uuid::new_v4()));
+        init(&dir).unwrap();
+        let encrypted = encrypt("sk-test-key-12345");
+        let mut data = base64::engine::general_purpose::STANDARD
+            .decode(encrypted.strip_prefix("enc:").unwrap())
+            .unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        let tampered = format!("enc:{}", base64::engine::general_purpose::STANDARD.encode(&data));
+        assert!(decrypt(&tampered).is_err());
+        assert!(decrypt("enc:%%%").is_err());
+        assert!(decrypt("enc:AAAA").is_err());
+    }
+
+    #[test]
+    fn key_file_is_reused() {
+        let dir = std::env::temp_dir().join(format!("nyro-crypto-{}", uuid::Uuid::new_v4()));
+        let key = [7u8; 32];
+        write_key_file(&dir.join(MASTER_KEY_FILE), &key).unwrap();
+        assert_eq!(resolve_master_key(&dir).unwrap(), key);
+        assert!(decode_key("c2hvcnQ=").is_err());
     }
 }
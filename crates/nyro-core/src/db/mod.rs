@@ -36,16 +36,53 @@ pub async fn migrate(pool: &SqlitePool) -> anyhow::Result<()> {
     ensure_provider_column(pool, "static_models", "TEXT").await?;
     ensure_provider_column(pool, "last_test_success", "INTEGER").await?;
     ensure_provider_column(pool, "last_test_at", "TEXT").await?;
+    ensure_provider_column(pool, "max_concurrency", "INTEGER").await?;
+    ensure_provider_column(pool, "rpm", "INTEGER").await?;
+    ensure_provider_column(pool, "tpm", "INTEGER").await?;
+    ensure_provider_column(pool, "retry_max_attempts", "INTEGER").await?;
+    ensure_provider_column(pool, "retry_base_delay_ms", "INTEGER").await?;
+    ensure_provider_column(pool, "retry_max_delay_ms", "INTEGER").await?;
+    ensure_provider_column(pool, "api_version", "TEXT").await?;
+    ensure_provider_column(pool, "access_key_id", "TEXT").await?;
+    ensure_provider_column(pool, "cloud_region", "TEXT").await?;
+    ensure_provider_column(pool, "api_dialect", "TEXT").await?;
     ensure_route_column(pool, "ingress_protocol", "TEXT").await?;
     ensure_route_column(pool, "virtual_model", "TEXT").await?;
     ensure_route_column(pool, "access_control", "INTEGER DEFAULT 0").await?;
+    ensure_route_column(pool, "strategy", "TEXT DEFAULT 'round_robin'").await?;
     ensure_request_log_column(pool, "api_key_id", "TEXT").await?;
+    ensure_request_log_column(pool, "cache_read_tokens", "INTEGER DEFAULT 0").await?;
+    ensure_request_log_column(pool, "cache_creation_tokens", "INTEGER DEFAULT 0").await?;
     ensure_api_key_tables(pool).await?;
     ensure_api_key_column(pool, "rpd", "INTEGER").await?;
     backfill_provider_channel(pool).await?;
     backfill_provider_vendor(pool).await?;
     backfill_provider_models_source(pool).await?;
     backfill_route_fields(pool).await?;
+    encrypt_provider_api_keys(pool).await?;
+    Ok(())
+}
+
+/// Encrypt provider keys stored in plaintext by earlier versions.
+async fn encrypt_provider_api_keys(pool: &SqlitePool) -> anyhow::Result<()> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, api_key FROM providers WHERE api_key != '' AND api_key NOT LIKE 'enc:%'",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for (id, api_key) in rows {
+        let encrypted = crate::crypto::encrypt(&api_key);
+        if !crate::crypto::is_encrypted(&encrypted) {
+            tracing::warn!("could not encrypt api key for provider {id}, leaving it unchanged");
+            continue;
+        }
+        sqlx::query("UPDATE providers SET api_key = ? WHERE id = ?")
+            .bind(&encrypted)
+            .bind(&id)
+            .execute(pool)
+            .await?;
+    }
     Ok(())
 }
 
@@ -228,6 +265,16 @@ CREATE TABLE IF NOT EXISTS providers (
     capabilities_source TEXT,
     static_models TEXT,
     api_key     TEXT NOT NULL,
+    max_concurrency INTEGER,
+    rpm         INTEGER,
+    tpm         INTEGER,
+    retry_max_attempts INTEGER,
+    retry_base_delay_ms INTEGER,
+    retry_max_delay_ms INTEGER,
+    api_version TEXT,
+    access_key_id TEXT,
+    cloud_region TEXT,
+    api_dialect TEXT,
     last_test_success INTEGER,
     last_test_at TEXT,
     is_active   INTEGER DEFAULT 1,
@@ -249,9 +296,23 @@ CREATE TABLE IF NOT EXISTS routes (
     access_control    INTEGER DEFAULT 0,
     is_active         INTEGER DEFAULT 1,
     priority          INTEGER DEFAULT 0,
+    strategy          TEXT DEFAULT 'round_robin',
     created_at        TEXT DEFAULT (datetime('now'))
 );
 
+CREATE TABLE IF NOT EXISTS route_targets (
+    id                TEXT PRIMARY KEY,
+    route_id          TEXT NOT NULL REFERENCES routes(id) ON DELETE CASCADE,
+    provider_id       TEXT NOT NULL REFERENCES providers(id),
+    model             TEXT NOT NULL,
+    weight            INTEGER NOT NULL DEFAULT 1,
+    priority          INTEGER NOT NULL DEFAULT 0,
+    is_active         INTEGER DEFAULT 1,
+    created_at        TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_route_targets_route ON route_targets(route_id);
+
 CREATE TABLE IF NOT EXISTS request_logs (
     id                TEXT PRIMARY KEY,
     created_at        TEXT DEFAULT (datetime('now')),
@@ -265,6 +326,8 @@ CREATE TABLE IF NOT EXISTS request_logs (
     duration_ms       REAL,
     input_tokens      INTEGER DEFAULT 0,
     output_tokens     INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
+    cache_creation_tokens INTEGER DEFAULT 0,
     is_stream         INTEGER DEFAULT 0,
     is_tool_call      INTEGER DEFAULT 0,
     error_message     TEXT,
@@ -304,4 +367,26 @@ CREATE TABLE IF NOT EXISTS settings (
     value      TEXT NOT NULL,
     updated_at TEXT DEFAULT (datetime('now'))
 );
+
+CREATE TABLE IF NOT EXISTS audit_logs (
+    id         TEXT PRIMARY KEY,
+    created_at TEXT DEFAULT (datetime('now')),
+    action     TEXT NOT NULL,
+    target_id  TEXT,
+    actor      TEXT NOT NULL,
+    detail     TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_created_at ON audit_logs(created_at);
+
+CREATE TABLE IF NOT EXISTS responses (
+    id         TEXT PRIMARY KEY,
+    api_key_id TEXT,
+    model      TEXT,
+    input      TEXT NOT NULL,
+    response   TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_responses_created_at ON responses(created_at);
 "#;
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::protocol::Protocol;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Provider {
     pub id: String,
@@ -19,6 +21,23 @@ pub struct Provider {
     pub capabilities_source: Option<String>,
     pub static_models: Option<String>,
     pub api_key: String,
+    /// Upstream limits enforced by the proxy; `None` or `0` means unlimited.
+    pub max_concurrency: Option<i32>,
+    pub rpm: Option<i32>,
+    pub tpm: Option<i32>,
+    /// Same-provider retry policy; `None` uses the defaults in `proxy::retry`.
+    pub retry_max_attempts: Option<i32>,
+    pub retry_base_delay_ms: Option<i32>,
+    pub retry_max_delay_ms: Option<i32>,
+    /// `api-version` query parameter for Azure OpenAI providers.
+    pub api_version: Option<String>,
+    /// AWS access key id for SigV4-signed providers; `api_key` holds the secret.
+    pub access_key_id: Option<String>,
+    /// Cloud region (AWS region, Vertex AI location) for providers that need one.
+    pub cloud_region: Option<String>,
+    /// OpenAI egress dialect: `chat` (Chat Completions, the default) or
+    /// `responses` (the Responses API).
+    pub api_dialect: Option<String>,
     pub last_test_success: Option<bool>,
     pub last_test_at: Option<String>,
     pub is_active: bool,
@@ -34,11 +53,27 @@ pub struct Route {
     pub virtual_model: String,
     pub target_provider: String,
     pub target_model: String,
+    pub fallback_provider: Option<String>,
+    pub fallback_model: Option<String>,
+    pub strategy: String,
+    pub priority: i32,
     pub access_control: bool,
     pub is_active: bool,
     pub created_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RouteTarget {
+    pub id: String,
+    pub route_id: String,
+    pub provider_id: String,
+    pub model: String,
+    pub weight: i32,
+    pub priority: i32,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ApiKey {
     pub id: String,
@@ -84,6 +119,10 @@ pub struct RequestLog {
     pub duration_ms: Option<f64>,
     pub input_tokens: i32,
     pub output_tokens: i32,
+    /// Part of `input_tokens` served from the upstream's prompt cache.
+    pub cache_read_tokens: i32,
+    /// Part of `input_tokens` written to the prompt cache.
+    pub cache_creation_tokens: i32,
     pub is_stream: bool,
     pub is_tool_call: bool,
     pub error_message: Option<String>,
@@ -108,6 +147,26 @@ pub struct CreateProvider {
     pub capabilities_source: Option<String>,
     pub static_models: Option<String>,
     pub api_key: String,
+    #[serde(default)]
+    pub max_concurrency: Option<i32>,
+    #[serde(default)]
+    pub rpm: Option<i32>,
+    #[serde(default)]
+    pub tpm: Option<i32>,
+    #[serde(default)]
+    pub retry_max_attempts: Option<i32>,
+    #[serde(default)]
+    pub retry_base_delay_ms: Option<i32>,
+    #[serde(default)]
+    pub retry_max_delay_ms: Option<i32>,
+    #[serde(default)]
+    pub api_version: Option<String>,
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub cloud_region: Option<String>,
+    #[serde(default)]
+    pub api_dialect: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +186,16 @@ pub struct UpdateProvider {
     pub capabilities_source: Option<String>,
     pub static_models: Option<String>,
     pub api_key: Option<String>,
+    pub max_concurrency: Option<i32>,
+    pub rpm: Option<i32>,
+    pub tpm: Option<i32>,
+    pub retry_max_attempts: Option<i32>,
+    pub retry_base_delay_ms: Option<i32>,
+    pub retry_max_delay_ms: Option<i32>,
+    pub api_version: Option<String>,
+    pub access_key_id: Option<String>,
+    pub cloud_region: Option<String>,
+    pub api_dialect: Option<String>,
     pub is_active: Option<bool>,
 }
 
@@ -137,6 +206,10 @@ pub struct UpdateRoute {
     pub virtual_model: Option<String>,
     pub target_provider: Option<String>,
     pub target_model: Option<String>,
+    pub fallback_provider: Option<String>,
+    pub fallback_model: Option<String>,
+    pub strategy: Option<String>,
+    pub priority: Option<i32>,
     pub access_control: Option<bool>,
     pub is_active: Option<bool>,
 }
@@ -148,9 +221,34 @@ pub struct CreateRoute {
     pub virtual_model: String,
     pub target_provider: String,
     pub target_model: String,
+    #[serde(default)]
+    pub fallback_provider: Option<String>,
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    #[serde(default)]
+    pub strategy: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i32>,
     pub access_control: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRouteTarget {
+    pub provider_id: String,
+    pub model: String,
+    pub weight: Option<i32>,
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRouteTarget {
+    pub provider_id: Option<String>,
+    pub model: Option<String>,
+    pub weight: Option<i32>,
+    pub priority: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateApiKey {
     pub name: String,
@@ -196,6 +294,8 @@ pub struct StatsOverview {
     pub total_requests: i64,
     pub total_input_tokens: i64,
     pub total_output_tokens: i64,
+    pub total_cache_read_tokens: i64,
+    pub total_cache_creation_tokens: i64,
     pub avg_duration_ms: f64,
     pub error_count: i64,
 }
@@ -207,6 +307,8 @@ pub struct StatsHourly {
     pub error_count: i64,
     pub total_input_tokens: i64,
     pub total_output_tokens: i64,
+    pub total_cache_read_tokens: i64,
+    pub total_cache_creation_tokens: i64,
     pub avg_duration_ms: f64,
 }
 
@@ -216,6 +318,8 @@ pub struct ModelStats {
     pub request_count: i64,
     pub total_input_tokens: i64,
     pub total_output_tokens: i64,
+    pub total_cache_read_tokens: i64,
+    pub total_cache_creation_tokens: i64,
     pub avg_duration_ms: f64,
 }
 
@@ -252,6 +356,10 @@ pub struct ModelCapabilities {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportData {
     pub version: u32,
+    /// Provider `api_key`s are display masks rather than usable secrets;
+    /// importing such a file leaves the keys empty to be re-entered.
+    #[serde(default)]
+    pub keys_masked: bool,
     pub providers: Vec<ExportProvider>,
     pub routes: Vec<ExportRoute>,
     pub settings: Vec<(String, String)>,
@@ -274,6 +382,26 @@ pub struct ExportProvider {
     pub capabilities_source: Option<String>,
     pub static_models: Option<String>,
     pub api_key: String,
+    #[serde(default)]
+    pub max_concurrency: Option<i32>,
+    #[serde(default)]
+    pub rpm: Option<i32>,
+    #[serde(default)]
+    pub tpm: Option<i32>,
+    #[serde(default)]
+    pub retry_max_attempts: Option<i32>,
+    #[serde(default)]
+    pub retry_base_delay_ms: Option<i32>,
+    #[serde(default)]
+    pub retry_max_delay_ms: Option<i32>,
+    #[serde(default)]
+    pub api_version: Option<String>,
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub cloud_region: Option<String>,
+    #[serde(default)]
+    pub api_dialect: Option<String>,
     pub is_active: bool,
 }
 
@@ -287,28 +415,124 @@ pub struct ExportRoute {
     pub target_provider_name: String,
     pub target_model: String,
     #[serde(default)]
+    pub fallback_provider_name: Option<String>,
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    #[serde(default)]
+    pub strategy: Option<String>,
+    #[serde(default)]
+    pub targets: Vec<ExportRouteTarget>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
     pub access_control: bool,
     pub is_active: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRouteTarget {
+    pub provider_name: String,
+    pub model: String,
+    #[serde(default = "default_target_weight")]
+    pub weight: i32,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLog {
+    pub id: String,
+    pub created_at: String,
+    pub action: String,
+    pub target_id: Option<String>,
+    pub actor: String,
+    pub detail: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportResult {
     pub providers_imported: u32,
     pub routes_imported: u32,
     pub settings_imported: u32,
+    /// Route targets left out because their provider was missing or the
+    /// target was rejected.
+    #[serde(default)]
+    pub targets_skipped: u32,
 }
 
 fn default_ingress_protocol() -> String {
     "openai".to_string()
 }
 
+fn default_target_weight() -> i32 {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl Provider {
+    /// Replace the stored (encrypted) `api_key` with its plaintext for upstream use.
+    pub fn decrypted(mut self) -> anyhow::Result<Self> {
+        self.api_key = crate::crypto::decrypt(&self.api_key)
+            .map_err(|e| anyhow::anyhow!("provider {} api key: {e}", self.name))?;
+        Ok(self)
+    }
+
+    /// Replace `api_key` with a display-safe mask such as `sk-…abcd`. A key
+    /// that cannot be decrypted is shown empty so it can be re-entered.
+    pub fn masked(mut self) -> Self {
+        self.api_key = match crate::crypto::decrypt(&self.api_key) {
+            Ok(key) => crate::admin::mask_secret(&key),
+            Err(e) => {
+                tracing::warn!("provider {} api key unreadable: {e}", self.name);
+                String::new()
+            }
+        };
+        self
+    }
+
     pub fn effective_models_source(&self) -> Option<&str> {
         self.models_source
             .as_deref()
             .filter(|v| !v.trim().is_empty())
             .or_else(|| self.models_endpoint.as_deref().filter(|v| !v.trim().is_empty()))
     }
+
+    /// Wire protocol for upstream calls: `protocol`, with OpenAI providers
+    /// switched to the Responses API when `api_dialect` is `responses`.
+    pub fn egress_protocol(&self) -> Protocol {
+        match self.protocol.parse().unwrap_or(Protocol::OpenAI) {
+            Protocol::OpenAI
+                if self
+                    .api_dialect
+                    .as_deref()
+                    .is_some_and(|d| d.trim().eq_ignore_ascii_case("responses")) =>
+            {
+                Protocol::ResponsesAPI
+            }
+            protocol => protocol,
+        }
+    }
+}
+
+impl RouteTarget {
+    /// Implicit single target for routes without `route_targets` rows.
+    pub fn primary(route: &Route) -> Self {
+        Self {
+            id: String::new(),
+            route_id: route.id.clone(),
+            provider_id: route.target_provider.clone(),
+            model: route.target_model.clone(),
+            weight: 1,
+            priority: 0,
+            is_active: true,
+            created_at: route.created_at.clone(),
+        }
+    }
 }
 
 impl CreateProvider {
@@ -23,28 +23,58 @@ pub struct CapabilityCacheEntry {
     pub cached_at: Instant,
 }
 
+/// Capabilities resolved for one provider model; `None` when the provider's
+/// source had no match.
+#[derive(Clone, Debug)]
+pub struct ModelCapabilityCacheEntry {
+    pub capabilities: Option<db::models::ModelCapabilities>,
+    pub cached_at: Instant,
+}
+
 #[derive(Clone)]
 pub struct Gateway {
     pub config: GatewayConfig,
     pub db: SqlitePool,
     pub http_client: reqwest::Client,
     pub route_cache: Arc<tokio::sync::RwLock<router::RouteCache>>,
+    pub latency_tracker: Arc<router::LatencyTracker>,
+    pub circuit_breakers: Arc<proxy::circuit::CircuitBreakers>,
+    pub rate_limiter: Arc<proxy::rate_limit::RateLimiter>,
+    pub provider_limiter: Arc<proxy::provider_limit::ProviderLimiter>,
+    /// Vertex AI access tokens minted from provider service accounts.
+    pub vertex_tokens: Arc<proxy::vertex::TokenCache>,
+    /// Local token counting for `count_tokens` when no upstream can count.
+    pub token_estimator: Arc<dyn protocol::semantic::token_count::TokenEstimator>,
     pub ollama_capability_cache: Arc<tokio::sync::RwLock<HashMap<String, CapabilityCacheEntry>>>,
+    /// Capabilities advertised by the client-facing model listings.
+    pub model_capability_cache: Arc<tokio::sync::RwLock<HashMap<String, ModelCapabilityCacheEntry>>>,
     pub log_tx: mpsc::Sender<LogEntry>,
 }
 
 impl Gateway {
     pub async fn new(config: GatewayConfig) -> anyhow::Result<(Self, mpsc::Receiver<LogEntry>)> {
+        crypto::init(&config.data_dir)?;
         let db = db::init_pool(&config.data_dir).await?;
         db::migrate(&db).await?;
 
         let http_client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(300))
+            .connect_timeout(std::time::Duration::from_secs(10))
             .build()?;
 
         let route_cache = Arc::new(tokio::sync::RwLock::new(
             router::RouteCache::load(&db).await?,
         ));
+        let latency_tracker = Arc::new(router::LatencyTracker::default());
+        let circuit_breakers = Arc::new(proxy::circuit::CircuitBreakers::new(
+            config.circuit_failure_threshold,
+            Duration::from_secs(config.circuit_cooldown_secs),
+        ));
+        let rate_limiter = Arc::new(proxy::rate_limit::RateLimiter::default());
+        rate_limiter.seed(&db).await?;
+        let provider_limiter = Arc::new(proxy::provider_limit::ProviderLimiter::new(
+            Duration::from_secs(config.provider_queue_timeout_secs),
+        ));
         let ollama_capability_cache = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
 
         let (log_tx, log_rx) = mpsc::channel(1024);
@@ -54,7 +84,14 @@ impl Gateway {
             db,
             http_client,
             route_cache,
+            latency_tracker,
+            circuit_breakers,
+            rate_limiter,
+            provider_limiter,
+            vertex_tokens: Arc::default(),
+            token_estimator: Arc::new(protocol::semantic::token_count::HeuristicEstimator),
             ollama_capability_cache,
+            model_capability_cache: Arc::default(),
             log_tx,
         };
 
@@ -121,4 +158,41 @@ impl Gateway {
         let mut cache = self.ollama_capability_cache.write().await;
         cache.retain(|k, _| !k.starts_with(&prefix));
     }
+
+    pub async fn get_model_capabilities_cached(
+        &self,
+        provider_id: &str,
+        model: &str,
+        ttl: Duration,
+    ) -> Option<Option<db::models::ModelCapabilities>> {
+        let key = format!("{provider_id}:{model}");
+        let cache = self.model_capability_cache.read().await;
+        cache
+            .get(&key)
+            .filter(|entry| entry.cached_at.elapsed() < ttl)
+            .map(|entry| entry.capabilities.clone())
+    }
+
+    pub async fn set_model_capabilities_cache(
+        &self,
+        provider_id: &str,
+        model: &str,
+        capabilities: Option<db::models::ModelCapabilities>,
+    ) {
+        let key = format!("{provider_id}:{model}");
+        let mut cache = self.model_capability_cache.write().await;
+        cache.insert(
+            key,
+            ModelCapabilityCacheEntry {
+                capabilities,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    pub async fn clear_model_capability_cache_for_provider(&self, provider_id: &str) {
+        let prefix = format!("{provider_id}:");
+        let mut cache = self.model_capability_cache.write().await;
+        cache.retain(|k, _| !k.starts_with(&prefix));
+    }
 }
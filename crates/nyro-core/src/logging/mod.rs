@@ -69,6 +69,12 @@ async fn cleanup_old_logs(db: &SqlitePool) {
             tracing::info!("cleaned up {deleted} logs older than {days} days");
         }
     }
+
+    // Stored Responses API conversations share the log retention window.
+    let _ = sqlx::query("DELETE FROM responses WHERE created_at < datetime('now', ?)")
+        .bind(&cutoff)
+        .execute(db)
+        .await;
 }
 
 async fn flush(db: &SqlitePool, buffer: &mut Vec<LogEntry>) {
@@ -78,8 +84,9 @@ async fn flush(db: &SqlitePool, buffer: &mut Vec<LogEntry>) {
             r#"INSERT INTO request_logs
                 (id, api_key_id, ingress_protocol, egress_protocol, request_model, actual_model,
                  provider_name, status_code, duration_ms, input_tokens, output_tokens,
+                 cache_read_tokens, cache_creation_tokens,
                  is_stream, is_tool_call, error_message, request_preview, response_preview)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(&id)
         .bind(&entry.api_key_id)
@@ -92,6 +99,8 @@ async fn flush(db: &SqlitePool, buffer: &mut Vec<LogEntry>) {
         .bind(entry.duration_ms)
         .bind(entry.usage.input_tokens as i32)
         .bind(entry.usage.output_tokens as i32)
+        .bind(entry.usage.cache_read_tokens as i32)
+        .bind(entry.usage.cache_creation_tokens as i32)
         .bind(entry.is_stream as i32)
         .bind(entry.is_tool_call as i32)
         .bind(&entry.error_message)
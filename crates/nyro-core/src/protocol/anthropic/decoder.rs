@@ -1,6 +1,9 @@
 use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::image_from_url;
 use crate::protocol::types::*;
 use crate::protocol::{IngressDecoder, Protocol};
 
@@ -10,6 +13,12 @@ pub struct AnthropicDecoder;
 
 impl IngressDecoder for AnthropicDecoder {
     fn decode_request(&self, body: Value) -> Result<InternalRequest> {
+        let system_breakpoint = cache_breakpoint(body.get("system"));
+        let message_breakpoints: Vec<Option<Value>> = body
+            .get("messages")
+            .and_then(Value::as_array)
+            .map(|msgs| msgs.iter().map(|m| cache_breakpoint(m.get("content"))).collect())
+            .unwrap_or_default();
         let req: AnthropicRequest = serde_json::from_value(body)?;
 
         let mut messages = Vec::new();
@@ -31,11 +40,16 @@ impl IngressDecoder for AnthropicDecoder {
                 content: MessageContent::Text(text),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: system_breakpoint,
             });
         }
 
-        for msg in req.messages {
-            messages.extend(decode_message(msg)?);
+        for (msg, breakpoint) in req.messages.into_iter().zip(message_breakpoints) {
+            let mut decoded = decode_message(msg)?;
+            if let Some(last) = decoded.last_mut() {
+                last.cache_control = breakpoint;
+            }
+            messages.extend(decoded);
         }
 
         let tools = req.tools.map(|tools| {
@@ -45,6 +59,7 @@ impl IngressDecoder for AnthropicDecoder {
                     name: t.name,
                     description: t.description,
                     parameters: t.input_schema,
+                    cache_control: t.cache_control,
                 })
                 .collect()
         });
@@ -58,12 +73,30 @@ impl IngressDecoder for AnthropicDecoder {
             top_p: req.top_p,
             tools,
             tool_choice: req.tool_choice,
+            reasoning: req.thinking.map(|t| ReasoningConfig {
+                // `enabled`, or `adaptive` which leaves the budget to the model.
+                enabled: t.kind != "disabled",
+                effort: None,
+                budget_tokens: t.budget_tokens,
+            }),
             source_protocol: Protocol::Anthropic,
             extra: Default::default(),
         })
     }
 }
 
+/// The last `cache_control` marker among a message's (or the system
+/// prompt's) content blocks. Internally a breakpoint closes the whole
+/// message, which caches the same prefix when, as usual, the client marked
+/// the final block.
+fn cache_breakpoint(content: Option<&Value>) -> Option<Value> {
+    content?
+        .as_array()?
+        .iter()
+        .rev()
+        .find_map(|block| block.get("cache_control").filter(|c| !c.is_null()).cloned())
+}
+
 fn decode_message(msg: AnthropicMessage) -> Result<Vec<InternalMessage>> {
     let role = match msg.role.as_str() {
         "user" => Role::User,
@@ -89,19 +122,20 @@ fn decode_message(msg: AnthropicMessage) -> Result<Vec<InternalMessage>> {
                     AnthropicContentBlock::Text { text } => {
                         content_blocks.push(ContentBlock::Text { text });
                     }
-                    AnthropicContentBlock::Thinking { thinking, .. } => {
-                        if !thinking.trim().is_empty() {
-                            content_blocks.push(ContentBlock::Text { text: thinking });
-                        }
+                    AnthropicContentBlock::Thinking { thinking, signature } => {
+                        content_blocks.push(ContentBlock::Thinking { thinking, signature });
+                    }
+                    AnthropicContentBlock::RedactedThinking { data } => {
+                        content_blocks.push(ContentBlock::RedactedThinking { data });
                     }
                     AnthropicContentBlock::Image { source } => {
                         content_blocks.push(ContentBlock::Image {
-                            source: ImageSource {
-                                media_type: source.media_type,
-                                data: source.data,
-                            },
+                            source: image_source(source),
                         });
                     }
+                    AnthropicContentBlock::Document { source, title } => {
+                        content_blocks.push(document_block(source, title));
+                    }
                     AnthropicContentBlock::ToolUse { id, name, input } => {
                         tcs.push(ToolCall {
                             id: id.clone(),
@@ -132,6 +166,7 @@ fn decode_message(msg: AnthropicMessage) -> Result<Vec<InternalMessage>> {
                         content: MessageContent::Text(text.clone()),
                         tool_calls: tool_calls_opt,
                         tool_call_id: tc_id,
+                        cache_control: None,
                     }]);
                 }
             }
@@ -149,6 +184,7 @@ fn decode_message(msg: AnthropicMessage) -> Result<Vec<InternalMessage>> {
         content,
         tool_calls,
         tool_call_id,
+        cache_control: None,
     }])
 }
 
@@ -172,20 +208,22 @@ fn decode_user_blocks(blocks: Vec<AnthropicContentBlock>) -> Result<Vec<Internal
                     content: MessageContent::Text(tool_text),
                     tool_calls: None,
                     tool_call_id: Some(tool_use_id),
+                    cache_control: None,
                 });
             }
             AnthropicContentBlock::Text { text } => user_blocks.push(ContentBlock::Text { text }),
-            AnthropicContentBlock::Thinking { thinking, .. } => {
-                if !thinking.trim().is_empty() {
-                    user_blocks.push(ContentBlock::Text { text: thinking });
-                }
+            AnthropicContentBlock::Thinking { thinking, signature } => {
+                user_blocks.push(ContentBlock::Thinking { thinking, signature })
+            }
+            AnthropicContentBlock::RedactedThinking { data } => {
+                user_blocks.push(ContentBlock::RedactedThinking { data })
             }
             AnthropicContentBlock::Image { source } => user_blocks.push(ContentBlock::Image {
-                source: ImageSource {
-                    media_type: source.media_type,
-                    data: source.data,
-                },
+                source: image_source(source),
             }),
+            AnthropicContentBlock::Document { source, title } => {
+                user_blocks.push(document_block(source, title))
+            }
             AnthropicContentBlock::ToolUse { id, name, input } => {
                 user_blocks.push(ContentBlock::ToolUse { id, name, input })
             }
@@ -209,9 +247,45 @@ fn decode_user_blocks(blocks: Vec<AnthropicContentBlock>) -> Result<Vec<Internal
                 content,
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
         );
     }
 
     Ok(messages)
 }
+
+/// Plain-text documents are carried as base64 `text/plain` so other
+/// upstreams still see a file; custom-content documents become their text.
+fn document_block(source: AnthropicImageSource, title: Option<String>) -> ContentBlock {
+    let source = match source.source_type.as_str() {
+        "text" => ImageSource {
+            media_type: "text/plain".to_string(),
+            data: STANDARD.encode(source.data),
+        },
+        "content" => {
+            let text = match source.content {
+                Some(Value::String(text)) => text,
+                Some(Value::Array(blocks)) => blocks
+                    .iter()
+                    .filter_map(|b| b.get("text").and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            return ContentBlock::Text { text };
+        }
+        _ => image_source(source),
+    };
+    ContentBlock::Document { source, name: title }
+}
+
+fn image_source(source: AnthropicImageSource) -> ImageSource {
+    match source.url {
+        Some(url) if source.source_type == "url" => image_from_url(&url),
+        _ => ImageSource {
+            media_type: source.media_type,
+            data: source.data,
+        },
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::{inline_data, remote_url};
+use crate::protocol::semantic::reasoning::anthropic_thinking;
 use crate::protocol::types::*;
 use crate::protocol::EgressEncoder;
 
@@ -10,22 +14,40 @@ pub struct AnthropicEncoder;
 impl EgressEncoder for AnthropicEncoder {
     fn encode_request(&self, req: &InternalRequest) -> Result<(Value, HeaderMap)> {
         let mut system_text = String::new();
+        let mut system_blocks = Vec::new();
         let mut raw_messages = Vec::new();
 
         for msg in &req.messages {
             if msg.role == Role::System {
+                let text = msg.content.as_text();
                 if !system_text.is_empty() {
                     system_text.push('\n');
                 }
-                system_text.push_str(&msg.content.as_text());
+                system_text.push_str(&text);
+                if !text.trim().is_empty() {
+                    let mut block = serde_json::json!({"type": "text", "text": text});
+                    if let Some(cache_control) = &msg.cache_control {
+                        block["cache_control"] = cache_control.clone();
+                    }
+                    system_blocks.push(block);
+                }
                 continue;
             }
 
-            raw_messages.push(encode_message(msg)?);
+            let mut message = encode_message(msg)?;
+            if let Some(cache_control) = &msg.cache_control {
+                mark_cache_breakpoint(&mut message, cache_control);
+            }
+            raw_messages.push(message);
         }
         let messages = normalize_anthropic_messages(raw_messages);
 
-        let max_tokens = req.max_tokens.unwrap_or(4096);
+        let thinking = req
+            .reasoning
+            .as_ref()
+            .map(|r| anthropic_thinking(r, req.max_tokens.unwrap_or(4096)));
+        let max_tokens = thinking.as_ref().map_or(req.max_tokens.unwrap_or(4096), |(_, m)| *m);
+        let thinking_on = req.reasoning.as_ref().is_some_and(|r| r.enabled);
 
         let mut body = serde_json::json!({
             "model": req.model,
@@ -36,25 +58,41 @@ impl EgressEncoder for AnthropicEncoder {
 
         let obj = body.as_object_mut().unwrap();
 
-        if !system_text.is_empty() {
+        // Breakpoints need the block form; plain prompts stay a string.
+        let system_cached = req
+            .messages
+            .iter()
+            .any(|m| m.role == Role::System && m.cache_control.is_some());
+        if system_cached && !system_blocks.is_empty() {
+            obj.insert("system".into(), Value::Array(system_blocks));
+        } else if !system_text.is_empty() {
             obj.insert("system".into(), Value::String(system_text));
         }
-        if let Some(t) = req.temperature {
+        // Extended thinking only runs at the default temperature and with a
+        // top_p of at least 0.95.
+        if let Some(t) = req.temperature.filter(|_| !thinking_on) {
             obj.insert("temperature".into(), t.into());
         }
-        if let Some(p) = req.top_p {
+        if let Some(p) = req.top_p.filter(|p| !thinking_on || *p >= 0.95) {
             obj.insert("top_p".into(), p.into());
         }
+        if let Some((thinking, _)) = thinking {
+            obj.insert("thinking".into(), thinking);
+        }
 
         if let Some(ref tools) = req.tools {
             let tools_val: Vec<Value> = tools
                 .iter()
                 .map(|t| {
-                    serde_json::json!({
+                    let mut tool = serde_json::json!({
                         "name": t.name,
                         "description": t.description,
                         "input_schema": t.parameters,
-                    })
+                    });
+                    if let Some(cache_control) = &t.cache_control {
+                        tool["cache_control"] = cache_control.clone();
+                    }
+                    tool
                 })
                 .collect();
             obj.insert("tools".into(), Value::Array(tools_val));
@@ -193,7 +231,7 @@ fn validate_anthropic_payload(body: &Value) -> Result<()> {
                                     );
                                 }
                             }
-                            "image" | "thinking" => {}
+                            "image" | "document" | "thinking" | "redacted_thinking" => {}
                             other => {
                                 anyhow::bail!(
                                     "anthropic payload message[{idx}] unsupported block type: {other}"
@@ -282,15 +320,38 @@ fn encode_message(msg: &InternalMessage) -> Result<Value> {
                     ContentBlock::Text { text } => {
                         serde_json::json!({"type": "text", "text": text})
                     }
-                    ContentBlock::Image { source } => {
-                        serde_json::json!({
+                    ContentBlock::Image { source } => match remote_url(source) {
+                        Some(url) => serde_json::json!({
                             "type": "image",
-                            "source": {
-                                "type": "base64",
-                                "media_type": source.media_type,
-                                "data": source.data,
-                            }
-                        })
+                            "source": {"type": "url", "url": url}
+                        }),
+                        None => {
+                            let (media_type, data) = inline_data(source).unwrap_or_default();
+                            serde_json::json!({
+                                "type": "image",
+                                "source": {
+                                    "type": "base64",
+                                    "media_type": media_type,
+                                    "data": data,
+                                }
+                            })
+                        }
+                    },
+                    ContentBlock::Document { source, name } => {
+                        let mut doc = serde_json::json!({
+                            "type": "document",
+                            "source": document_source(source),
+                        });
+                        if let Some(name) = name {
+                            doc["title"] = Value::String(name.clone());
+                        }
+                        doc
+                    }
+                    ContentBlock::Thinking { thinking, signature } => {
+                        serde_json::json!({"type": "thinking", "thinking": thinking, "signature": signature})
+                    }
+                    ContentBlock::RedactedThinking { data } => {
+                        serde_json::json!({"type": "redacted_thinking", "data": data})
                     }
                     ContentBlock::ToolUse { id, name, input } => {
                         serde_json::json!({
@@ -322,6 +383,25 @@ fn encode_message(msg: &InternalMessage) -> Result<Value> {
     }))
 }
 
+/// Plain-text documents go out as a `text` source, which Anthropic can cite
+/// by character range; everything else as base64 or a URL.
+fn document_source(source: &ImageSource) -> Value {
+    if let Some(url) = remote_url(source) {
+        return serde_json::json!({"type": "url", "url": url});
+    }
+    let (media_type, data) = inline_data(source).unwrap_or_default();
+    if media_type == "text/plain"
+        && let Ok(bytes) = STANDARD.decode(data)
+    {
+        return serde_json::json!({
+            "type": "text",
+            "media_type": "text/plain",
+            "data": String::from_utf8_lossy(&bytes),
+        });
+    }
+    serde_json::json!({"type": "base64", "media_type": media_type, "data": data})
+}
+
 fn anthropic_tool_result_payload(msg: &InternalMessage) -> (Value, Option<String>) {
     match &msg.content {
         MessageContent::Text(t) => (Value::String(t.clone()), None),
@@ -394,6 +474,22 @@ fn normalize_anthropic_messages(messages: Vec<Value>) -> Vec<Value> {
     normalized
 }
 
+/// Put a message's cache breakpoint on its last block that can carry one;
+/// thinking blocks cannot.
+fn mark_cache_breakpoint(message: &mut Value, cache_control: &Value) {
+    let mut blocks = content_to_blocks(message.get("content").cloned().unwrap_or(Value::Null));
+    let Some(block) = blocks.iter_mut().rev().find(|b| {
+        !matches!(
+            b.get("type").and_then(|t| t.as_str()),
+            Some("thinking" | "redacted_thinking")
+        )
+    }) else {
+        return;
+    };
+    block["cache_control"] = cache_control.clone();
+    message["content"] = Value::Array(blocks);
+}
+
 fn content_to_blocks(content: Value) -> Vec<Value> {
     match content {
         Value::String(s) => {
@@ -407,14 +503,17 @@ fn content_to_blocks(content: Value) -> Vec<Value> {
             .into_iter()
             .filter(|v| {
                 let t = v.get("type").and_then(|x| x.as_str()).unwrap_or("");
-                if t == "text" {
-                    !v.get("text")
+                match t {
+                    "text" => !v
+                        .get("text")
                         .and_then(|x| x.as_str())
                         .unwrap_or("")
                         .trim()
-                        .is_empty()
-                } else {
-                    true
+                        .is_empty(),
+                    // Anthropic only takes back thinking it signed; reasoning
+                    // from other upstreams carries no signature.
+                    "thinking" => v.get("signature").and_then(|x| x.as_str()).is_some_and(|s| !s.is_empty()),
+                    _ => true,
                 }
             })
             .collect(),
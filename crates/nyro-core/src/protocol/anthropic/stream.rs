@@ -23,6 +23,9 @@ impl ResponseParser for AnthropicResponseParser {
             .to_string();
 
         let mut content_text = String::new();
+        let mut reasoning = String::new();
+        let mut reasoning_signature = None;
+        let mut redacted_reasoning = Vec::new();
         let mut tool_calls = Vec::new();
 
         if let Some(blocks) = resp.get("content").and_then(|c| c.as_array()) {
@@ -33,6 +36,19 @@ impl ResponseParser for AnthropicResponseParser {
                             content_text.push_str(text);
                         }
                     }
+                    Some("thinking") => {
+                        if let Some(text) = block.get("thinking").and_then(|t| t.as_str()) {
+                            reasoning.push_str(text);
+                        }
+                        if let Some(signature) = block.get("signature").and_then(|s| s.as_str()) {
+                            reasoning_signature = Some(signature.to_string());
+                        }
+                    }
+                    Some("redacted_thinking") => {
+                        if let Some(data) = block.get("data").and_then(|d| d.as_str()) {
+                            redacted_reasoning.push(data.to_string());
+                        }
+                    }
                     Some("tool_use") => {
                         if let (Some(tc_id), Some(name)) = (
                             block.get("id").and_then(|v| v.as_str()),
@@ -66,7 +82,9 @@ impl ResponseParser for AnthropicResponseParser {
             id,
             model,
             content: content_text,
-            reasoning_content: None,
+            reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
+            reasoning_signature,
+            redacted_reasoning,
             tool_calls,
             response_items: None,
             stop_reason,
@@ -83,11 +101,19 @@ impl ResponseFormatter for AnthropicResponseFormatter {
     fn format_response(&self, resp: &InternalResponse) -> Value {
         let mut content = Vec::new();
 
-        if let Some(reasoning) = resp.reasoning_content.as_ref().map(|v| v.trim()).filter(|v| !v.is_empty()) {
-            content.push(serde_json::json!({
+        let reasoning = resp.reasoning_content.as_ref().map(|v| v.trim()).filter(|v| !v.is_empty());
+        if reasoning.is_some() || resp.reasoning_signature.is_some() {
+            let mut block = serde_json::json!({
                 "type": "thinking",
-                "thinking": reasoning,
-            }));
+                "thinking": reasoning.unwrap_or_default(),
+            });
+            if let Some(signature) = &resp.reasoning_signature {
+                block["signature"] = Value::String(signature.clone());
+            }
+            content.push(block);
+        }
+        for data in &resp.redacted_reasoning {
+            content.push(serde_json::json!({"type": "redacted_thinking", "data": data}));
         }
 
         if !resp.content.is_empty() {
@@ -118,10 +144,7 @@ impl ResponseFormatter for AnthropicResponseFormatter {
             "content": content,
             "model": resp.model,
             "stop_reason": stop_reason,
-            "usage": {
-                "input_tokens": resp.usage.input_tokens,
-                "output_tokens": resp.usage.output_tokens,
-            }
+            "usage": anthropic_usage(&resp.usage, resp.usage.output_tokens),
         })
     }
 }
@@ -208,6 +231,11 @@ fn parse_anthropic_event(event_type: Option<&str>, data: &Value, deltas: &mut Ve
                 .unwrap_or(0) as usize;
             if let Some(block) = data.get("content_block") {
                 match block.get("type").and_then(|t| t.as_str()) {
+                    Some("redacted_thinking") => {
+                        if let Some(data) = block.get("data").and_then(|d| d.as_str()) {
+                            deltas.push(StreamDelta::RedactedReasoning(data.to_string()));
+                        }
+                    }
                     Some("tool_use") => {
                         let id = block
                             .get("id")
@@ -237,6 +265,16 @@ fn parse_anthropic_event(event_type: Option<&str>, data: &Value, deltas: &mut Ve
                             deltas.push(StreamDelta::TextDelta(text.to_string()));
                         }
                     }
+                    Some("thinking_delta") => {
+                        if let Some(text) = delta.get("thinking").and_then(|t| t.as_str()) {
+                            deltas.push(StreamDelta::ReasoningDelta(text.to_string()));
+                        }
+                    }
+                    Some("signature_delta") => {
+                        if let Some(signature) = delta.get("signature").and_then(|t| t.as_str()) {
+                            deltas.push(StreamDelta::ReasoningSignature(signature.to_string()));
+                        }
+                    }
                     Some("input_json_delta") => {
                         if let Some(json) = delta.get("partial_json").and_then(|t| t.as_str()) {
                             let idx = data
@@ -274,8 +312,8 @@ fn parse_anthropic_event(event_type: Option<&str>, data: &Value, deltas: &mut Ve
                     .unwrap_or(0) as u32;
                 if output > 0 {
                     deltas.push(StreamDelta::Usage(TokenUsage {
-                        input_tokens: 0,
                         output_tokens: output,
+                        ..Default::default()
                     }));
                 }
             }
@@ -326,7 +364,7 @@ impl AnthropicStreamFormatter {
                 "content": [],
                 "model": self.model,
                 "stop_reason": null,
-                "usage": {"input_tokens": self.usage.input_tokens, "output_tokens": 0}
+                "usage": anthropic_usage(&self.usage, 0)
             }
         });
         events.push(SseEvent::new(Some("message_start"), msg_start.to_string()));
@@ -348,17 +386,9 @@ impl StreamFormatter for AnthropicStreamFormatter {
                 StreamDelta::ReasoningDelta(text) => {
                     self.ensure_message_start(&mut events);
                     self.close_text_block_if_open(&mut events);
+                    self.close_tool_block_if_open(&mut events);
                     if !self.in_thinking_block {
-                        self.in_thinking_block = true;
-                        let block_start = serde_json::json!({
-                            "type": "content_block_start",
-                            "index": self.block_index,
-                            "content_block": {"type": "thinking", "thinking": ""}
-                        });
-                        events.push(SseEvent::new(
-                            Some("content_block_start"),
-                            block_start.to_string(),
-                        ));
+                        self.open_thinking_block(&mut events);
                     }
                     let delta_ev = serde_json::json!({
                         "type": "content_block_delta",
@@ -370,6 +400,47 @@ impl StreamFormatter for AnthropicStreamFormatter {
                         delta_ev.to_string(),
                     ));
                 }
+                StreamDelta::ReasoningSignature(signature) => {
+                    self.ensure_message_start(&mut events);
+                    if !self.in_thinking_block {
+                        // Signed reasoning with no visible text still needs
+                        // a thinking block to carry the signature.
+                        self.close_text_block_if_open(&mut events);
+                        self.close_tool_block_if_open(&mut events);
+                        self.open_thinking_block(&mut events);
+                    }
+                    let delta_ev = serde_json::json!({
+                        "type": "content_block_delta",
+                        "index": self.block_index,
+                        "delta": {"type": "signature_delta", "signature": signature}
+                    });
+                    events.push(SseEvent::new(
+                        Some("content_block_delta"),
+                        delta_ev.to_string(),
+                    ));
+                    self.close_thinking_block_if_open(&mut events);
+                }
+                StreamDelta::RedactedReasoning(data) => {
+                    self.ensure_message_start(&mut events);
+                    self.close_thinking_block_if_open(&mut events);
+                    self.close_text_block_if_open(&mut events);
+                    self.close_tool_block_if_open(&mut events);
+                    let block_start = serde_json::json!({
+                        "type": "content_block_start",
+                        "index": self.block_index,
+                        "content_block": {"type": "redacted_thinking", "data": data}
+                    });
+                    events.push(SseEvent::new(
+                        Some("content_block_start"),
+                        block_start.to_string(),
+                    ));
+                    let stop = serde_json::json!({
+                        "type": "content_block_stop",
+                        "index": self.block_index,
+                    });
+                    events.push(SseEvent::new(Some("content_block_stop"), stop.to_string()));
+                    self.block_index += 1;
+                }
                 StreamDelta::TextDelta(text) => {
                     if !self.in_text_block && text.trim().is_empty() {
                         continue;
@@ -431,14 +502,7 @@ impl StreamFormatter for AnthropicStreamFormatter {
                         delta_ev.to_string(),
                     ));
                 }
-                StreamDelta::Usage(u) => {
-                    if u.input_tokens > 0 {
-                        self.usage.input_tokens = u.input_tokens;
-                    }
-                    if u.output_tokens > 0 {
-                        self.usage.output_tokens = u.output_tokens;
-                    }
-                }
+                StreamDelta::Usage(u) => self.usage.merge(u),
                 StreamDelta::Done { stop_reason } => {
                     self.ensure_message_start(&mut events);
                     self.close_thinking_block_if_open(&mut events);
@@ -476,6 +540,19 @@ impl StreamFormatter for AnthropicStreamFormatter {
 }
 
 impl AnthropicStreamFormatter {
+    fn open_thinking_block(&mut self, events: &mut Vec<SseEvent>) {
+        self.in_thinking_block = true;
+        let block_start = serde_json::json!({
+            "type": "content_block_start",
+            "index": self.block_index,
+            "content_block": {"type": "thinking", "thinking": ""}
+        });
+        events.push(SseEvent::new(
+            Some("content_block_start"),
+            block_start.to_string(),
+        ));
+    }
+
     fn close_text_block_if_open(&mut self, events: &mut Vec<SseEvent>) {
         if !self.in_text_block {
             return;
@@ -518,17 +595,25 @@ impl AnthropicStreamFormatter {
 
 fn extract_anthropic_usage(v: &Value) -> TokenUsage {
     if let Some(u) = v.get("usage") {
+        let count = |key: &str| u.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let cache_read_tokens = count("cache_read_input_tokens");
+        let cache_creation_tokens = count("cache_creation_input_tokens");
         TokenUsage {
-            input_tokens: u
-                .get("input_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0) as u32,
-            output_tokens: u
-                .get("output_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0) as u32,
+            input_tokens: count("input_tokens") + cache_read_tokens + cache_creation_tokens,
+            output_tokens: count("output_tokens"),
+            cache_read_tokens,
+            cache_creation_tokens,
         }
     } else {
         TokenUsage::default()
     }
 }
+
+fn anthropic_usage(usage: &TokenUsage, output_tokens: u32) -> Value {
+    serde_json::json!({
+        "input_tokens": usage.uncached_input_tokens(),
+        "cache_creation_input_tokens": usage.cache_creation_tokens,
+        "cache_read_input_tokens": usage.cache_read_tokens,
+        "output_tokens": output_tokens,
+    })
+}
@@ -13,6 +13,14 @@ pub struct AnthropicRequest {
     pub top_p: Option<f64>,
     pub tools: Option<Vec<AnthropicToolDef>>,
     pub tool_choice: Option<Value>,
+    pub thinking: Option<AnthropicThinking>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnthropicThinking {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub budget_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -46,8 +54,16 @@ pub enum AnthropicContentBlock {
         #[serde(default)]
         signature: Option<String>,
     },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
     #[serde(rename = "image")]
     Image { source: AnthropicImageSource },
+    #[serde(rename = "document")]
+    Document {
+        source: AnthropicImageSource,
+        #[serde(default)]
+        title: Option<String>,
+    },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
@@ -63,10 +79,18 @@ pub enum AnthropicContentBlock {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AnthropicImageSource {
+    /// `base64` (with `media_type` and `data`) or `url`; documents may also
+    /// be `text` (plain text in `data`) or `content` (blocks in `content`).
     #[serde(rename = "type")]
     pub source_type: String,
+    #[serde(default)]
     pub media_type: String,
+    #[serde(default)]
     pub data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -74,4 +98,5 @@ pub struct AnthropicToolDef {
     pub name: String,
     pub description: Option<String>,
     pub input_schema: Value,
+    pub cache_control: Option<Value>,
 }
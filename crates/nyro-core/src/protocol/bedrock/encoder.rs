@@ -0,0 +1,332 @@
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use serde_json::{Value, json};
+
+use crate::protocol::semantic::multimodal::{document_extension, document_name, inline_data};
+use crate::protocol::semantic::reasoning::anthropic_thinking;
+use crate::protocol::types::*;
+use crate::protocol::EgressEncoder;
+
+/// AWS Bedrock Converse / ConverseStream egress. The model id travels in the
+/// path, not the body.
+pub struct BedrockEncoder;
+
+impl EgressEncoder for BedrockEncoder {
+    fn encode_request(&self, req: &InternalRequest) -> Result<(Value, HeaderMap)> {
+        let mut system = Vec::new();
+        let mut messages: Vec<Value> = Vec::new();
+        // Cache breakpoints become `cachePoint` blocks on the Claude models.
+        let cache_points = is_claude(&req.model);
+
+        for msg in &req.messages {
+            if msg.role == Role::System {
+                let text = msg.content.as_text();
+                if !text.trim().is_empty() {
+                    system.push(json!({ "text": text }));
+                    if cache_points && msg.cache_control.is_some() {
+                        system.push(cache_point());
+                    }
+                }
+                continue;
+            }
+
+            let role = if msg.role == Role::Assistant { "assistant" } else { "user" };
+            let mut blocks = encode_blocks(msg)?;
+            if cache_points && msg.cache_control.is_some() && !blocks.is_empty() {
+                blocks.push(cache_point());
+            }
+            if blocks.is_empty() {
+                continue;
+            }
+            // Converse requires strictly alternating roles.
+            match messages.last_mut() {
+                Some(last) if last["role"] == role => {
+                    if let Some(content) = last["content"].as_array_mut() {
+                        content.extend(blocks);
+                    }
+                }
+                _ => messages.push(json!({ "role": role, "content": blocks })),
+            }
+        }
+        if messages.is_empty() {
+            anyhow::bail!("bedrock payload has empty messages");
+        }
+
+        let mut body = json!({ "messages": messages });
+        let obj = body.as_object_mut().unwrap();
+        if !system.is_empty() {
+            obj.insert("system".into(), Value::Array(system));
+        }
+
+        // Of the Bedrock families only Claude takes reasoning controls, as
+        // Anthropic `thinking` with the same sampling limits.
+        let thinking = req
+            .reasoning
+            .as_ref()
+            .filter(|_| is_claude(&req.model))
+            .map(|r| anthropic_thinking(r, req.max_tokens.unwrap_or(4096)));
+        let thinking_on = thinking.is_some() && req.reasoning.as_ref().is_some_and(|r| r.enabled);
+
+        let mut inference = serde_json::Map::new();
+        if let Some(m) = thinking.as_ref().map(|(_, m)| *m).or(req.max_tokens) {
+            inference.insert("maxTokens".into(), m.into());
+        }
+        if let Some(t) = req.temperature.filter(|_| !thinking_on) {
+            inference.insert("temperature".into(), t.into());
+        }
+        if let Some(p) = req.top_p.filter(|p| !thinking_on || *p >= 0.95) {
+            inference.insert("topP".into(), p.into());
+        }
+        match req.extra.get("stop").or_else(|| req.extra.get("stop_sequences")) {
+            Some(Value::String(stop)) => {
+                inference.insert("stopSequences".into(), json!([stop]));
+            }
+            Some(stop @ Value::Array(_)) => {
+                inference.insert("stopSequences".into(), stop.clone());
+            }
+            _ => {}
+        }
+        if !inference.is_empty() {
+            obj.insert("inferenceConfig".into(), Value::Object(inference));
+        }
+
+        if let Some(ref tools) = req.tools
+            && !tools.is_empty()
+        {
+            let mut specs = Vec::new();
+            for t in tools {
+                let mut spec = json!({
+                    "name": t.name,
+                    "inputSchema": { "json": t.parameters },
+                });
+                if let Some(ref d) = t.description {
+                    spec["description"] = json!(d);
+                }
+                specs.push(json!({ "toolSpec": spec }));
+                if cache_points && t.cache_control.is_some() {
+                    specs.push(cache_point());
+                }
+            }
+            let mut tool_config = json!({ "tools": specs });
+            if let Some(choice) = req.tool_choice.as_ref().and_then(map_tool_choice) {
+                tool_config["toolChoice"] = choice;
+            }
+            obj.insert("toolConfig".into(), tool_config);
+        }
+
+        // Model-specific knobs (e.g. Anthropic `top_k`) pass through untouched.
+        let mut fields = match req.extra.get("additionalModelRequestFields") {
+            Some(Value::Object(fields)) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        if let Some((thinking, _)) = thinking {
+            fields.entry("thinking").or_insert(thinking);
+        }
+        if !fields.is_empty() {
+            obj.insert("additionalModelRequestFields".into(), Value::Object(fields));
+        }
+
+        Ok((body, HeaderMap::new()))
+    }
+
+    fn egress_path(&self, model: &str, stream: bool) -> String {
+        let action = if stream { "converse-stream" } else { "converse" };
+        format!("/model/{}/{action}", encode_model_id(model))
+    }
+}
+
+fn cache_point() -> Value {
+    json!({ "cachePoint": { "type": "default" } })
+}
+
+/// Claude model ids (`anthropic.claude-…`) and inference profiles for them
+/// (`us.anthropic.claude-…`, ARNs ending in one).
+fn is_claude(model: &str) -> bool {
+    let model = model.to_ascii_lowercase();
+    model.contains("anthropic.") || model.contains("claude")
+}
+
+/// Model ids and inference-profile ARNs contain `:` and `/`, which must be
+/// percent-encoded as a single path segment.
+fn encode_model_id(model: &str) -> String {
+    let mut out = String::with_capacity(model.len());
+    for b in model.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+fn map_tool_choice(raw: &Value) -> Option<Value> {
+    let (kind, name) = match raw {
+        Value::String(s) => (s.as_str(), None),
+        Value::Object(obj) => (
+            obj.get("type").and_then(Value::as_str).unwrap_or(""),
+            obj.get("name")
+                .or_else(|| obj.get("function").and_then(|f| f.get("name")))
+                .and_then(Value::as_str),
+        ),
+        _ => return None,
+    };
+    match kind {
+        "auto" => Some(json!({ "auto": {} })),
+        "required" | "any" => Some(json!({ "any": {} })),
+        "tool" | "function" => name.map(|n| json!({ "tool": { "name": n } })),
+        _ => None,
+    }
+}
+
+fn encode_blocks(msg: &InternalMessage) -> Result<Vec<Value>> {
+    let mut blocks = Vec::new();
+
+    if msg.role == Role::Tool {
+        let (tool_use_id, content) = match &msg.content {
+            MessageContent::Blocks(bs) => bs
+                .iter()
+                .find_map(|b| match b {
+                    ContentBlock::ToolResult { tool_use_id, content } => {
+                        Some((tool_use_id.clone(), content.clone()))
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| (String::new(), Value::String(msg.content.as_text()))),
+            MessageContent::Text(t) => (String::new(), Value::String(t.clone())),
+        };
+        let tool_use_id = msg
+            .tool_call_id
+            .clone()
+            .filter(|id| !id.is_empty())
+            .unwrap_or(tool_use_id);
+        blocks.push(tool_result(&tool_use_id, &content));
+        return Ok(blocks);
+    }
+
+    match &msg.content {
+        MessageContent::Text(t) => {
+            if !t.trim().is_empty() {
+                blocks.push(json!({ "text": t }));
+            }
+        }
+        MessageContent::Blocks(bs) => {
+            for b in bs {
+                match b {
+                    ContentBlock::Text { text } => {
+                        if !text.trim().is_empty() {
+                            blocks.push(json!({ "text": text }));
+                        }
+                    }
+                    ContentBlock::Image { source } => blocks.push(encode_image(source)?),
+                    ContentBlock::Document { source, name } => {
+                        if let Some(document) = encode_document(source, name.as_deref()) {
+                            blocks.push(document);
+                        }
+                    }
+                    // Only signed reasoning is accepted back.
+                    ContentBlock::Thinking { thinking, signature: Some(signature) } => {
+                        blocks.push(json!({
+                            "reasoningContent": {
+                                "reasoningText": { "text": thinking, "signature": signature }
+                            }
+                        }));
+                    }
+                    ContentBlock::Thinking { signature: None, .. } => {}
+                    ContentBlock::RedactedThinking { data } => {
+                        blocks.push(json!({ "reasoningContent": { "redactedContent": data } }));
+                    }
+                    ContentBlock::ToolUse { id, name, input } => {
+                        blocks.push(json!({
+                            "toolUse": { "toolUseId": id, "name": name, "input": input }
+                        }));
+                    }
+                    ContentBlock::ToolResult { tool_use_id, content } => {
+                        blocks.push(tool_result(tool_use_id, content));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(ref calls) = msg.tool_calls {
+        for tc in calls {
+            let input: Value =
+                serde_json::from_str(&tc.arguments).unwrap_or(Value::Object(Default::default()));
+            blocks.push(json!({
+                "toolUse": { "toolUseId": tc.id, "name": tc.name, "input": input }
+            }));
+        }
+    }
+    Ok(blocks)
+}
+
+fn tool_result(tool_use_id: &str, content: &Value) -> Value {
+    let content = match content {
+        Value::String(s) => json!([{ "text": s }]),
+        // Anthropic-style block arrays carry their text in `text` fields.
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| match item.get("text").and_then(Value::as_str) {
+                    Some(text) => json!({ "text": text }),
+                    None => json!({ "json": item }),
+                })
+                .collect(),
+        ),
+        other => json!([{ "json": other }]),
+    };
+    json!({ "toolResult": { "toolUseId": tool_use_id, "content": content } })
+}
+
+/// Converse only takes inline bytes; a remote image URL that was not fetched
+/// is an error rather than silently leaving the image out.
+fn encode_image(source: &ImageSource) -> Result<Value> {
+    let Some((media_type, data)) = inline_data(source) else {
+        anyhow::bail!("remote image URLs are not supported by Bedrock");
+    };
+    let format = match media_type {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "jpeg",
+    };
+    Ok(json!({ "image": { "format": format, "source": { "bytes": data } } }))
+}
+
+fn encode_document(source: &ImageSource, name: Option<&str>) -> Option<Value> {
+    let Some((media_type, data)) = inline_data(source) else {
+        tracing::warn!("dropping remote document URL not supported by Bedrock");
+        return None;
+    };
+    let Some(format) = document_extension(media_type) else {
+        tracing::warn!("dropping {media_type} document not supported by Bedrock");
+        return None;
+    };
+    Some(json!({
+        "document": {
+            "format": format,
+            "name": document_title(&document_name(name, media_type)),
+            "source": { "bytes": data },
+        }
+    }))
+}
+
+/// Converse rejects document names with anything but letters, digits,
+/// single spaces, hyphens, parentheses and square brackets.
+fn document_title(name: &str) -> String {
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let cleaned: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '(' | ')' | '[' | ']') {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let title = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() { "document".to_string() } else { title }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::protocol::types::*;
+use crate::protocol::*;
+
+// ── Non-streaming response parser ──
+
+pub struct BedrockResponseParser;
+
+impl ResponseParser for BedrockResponseParser {
+    fn parse_response(&self, resp: Value) -> Result<InternalResponse> {
+        let mut content = String::new();
+        let mut reasoning = String::new();
+        let mut reasoning_signature = None;
+        let mut redacted_reasoning = Vec::new();
+        let mut tool_calls = Vec::new();
+
+        let blocks = resp
+            .pointer("/output/message/content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten();
+        for block in blocks {
+            if let Some(text) = block.get("text").and_then(Value::as_str) {
+                content.push_str(text);
+            }
+            if let Some(text) = block
+                .pointer("/reasoningContent/reasoningText/text")
+                .and_then(Value::as_str)
+            {
+                reasoning.push_str(text);
+            }
+            if let Some(signature) = block
+                .pointer("/reasoningContent/reasoningText/signature")
+                .and_then(Value::as_str)
+            {
+                reasoning_signature = Some(signature.to_string());
+            }
+            if let Some(data) = block
+                .pointer("/reasoningContent/redactedContent")
+                .and_then(Value::as_str)
+            {
+                redacted_reasoning.push(data.to_string());
+            }
+            if let Some(tool_use) = block.get("toolUse") {
+                tool_calls.push(ToolCall {
+                    id: str_field(tool_use, "toolUseId"),
+                    name: str_field(tool_use, "name"),
+                    arguments: tool_use
+                        .get("input")
+                        .cloned()
+                        .unwrap_or(Value::Object(Default::default()))
+                        .to_string(),
+                });
+            }
+        }
+
+        Ok(InternalResponse {
+            id: format!("bedrock-{}", uuid::Uuid::new_v4().simple()),
+            model: String::new(),
+            content,
+            reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
+            reasoning_signature,
+            redacted_reasoning,
+            tool_calls,
+            response_items: None,
+            stop_reason: resp
+                .get("stopReason")
+                .and_then(Value::as_str)
+                .map(stop_reason),
+            usage: extract_bedrock_usage(resp.get("usage")),
+        })
+    }
+}
+
+// ── Stream parser (decoded event-stream messages → deltas) ──
+
+/// Consumes the JSON lines `proxy::framing::EventStreamFramer` produces from
+/// the binary `application/vnd.amazon.eventstream` body: one
+/// `{"event": <type>, "payload": {...}}` record per message.
+#[derive(Default)]
+pub struct BedrockStreamParser {
+    buffer: String,
+    started: bool,
+    /// Converse content-block index → tool call index.
+    tool_indices: HashMap<u64, usize>,
+    stop_reason: Option<String>,
+    done: bool,
+}
+
+impl BedrockStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn parse_line(&mut self, line: &str, deltas: &mut Vec<StreamDelta>) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        let payload = record.get("payload").cloned().unwrap_or(Value::Null);
+        if let Some(exception) = record.get("exception").and_then(Value::as_str) {
+            let message = payload
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("upstream stream error");
+            // Headers are already sent; the client sees the stream end early.
+            tracing::warn!("bedrock stream {exception}: {message}");
+            return;
+        }
+
+        if !self.started {
+            self.started = true;
+            deltas.push(StreamDelta::MessageStart {
+                id: format!("bedrock-{}", uuid::Uuid::new_v4().simple()),
+                model: String::new(),
+            });
+        }
+
+        let block_index = payload
+            .get("contentBlockIndex")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        match record.get("event").and_then(Value::as_str).unwrap_or("") {
+            "contentBlockStart" => {
+                if let Some(tool_use) = payload.pointer("/start/toolUse") {
+                    let index = self.tool_indices.len();
+                    self.tool_indices.insert(block_index, index);
+                    deltas.push(StreamDelta::ToolCallStart {
+                        index,
+                        id: str_field(tool_use, "toolUseId"),
+                        name: str_field(tool_use, "name"),
+                    });
+                }
+            }
+            "contentBlockDelta" => {
+                let Some(delta) = payload.get("delta") else {
+                    return;
+                };
+                if let Some(text) = delta.get("text").and_then(Value::as_str) {
+                    if !text.is_empty() {
+                        deltas.push(StreamDelta::TextDelta(text.to_string()));
+                    }
+                } else if let Some(text) = delta
+                    .pointer("/reasoningContent/text")
+                    .and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::ReasoningDelta(text.to_string()));
+                } else if let Some(signature) = delta
+                    .pointer("/reasoningContent/signature")
+                    .and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::ReasoningSignature(signature.to_string()));
+                } else if let Some(data) = delta
+                    .pointer("/reasoningContent/redactedContent")
+                    .and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::RedactedReasoning(data.to_string()));
+                } else if let Some(input) =
+                    delta.pointer("/toolUse/input").and_then(Value::as_str)
+                    && let Some(&index) = self.tool_indices.get(&block_index)
+                {
+                    deltas.push(StreamDelta::ToolCallDelta {
+                        index,
+                        arguments: input.to_string(),
+                    });
+                }
+            }
+            "messageStop" => {
+                self.stop_reason = Some(
+                    payload
+                        .get("stopReason")
+                        .and_then(Value::as_str)
+                        .map(stop_reason)
+                        .unwrap_or_else(|| "stop".to_string()),
+                );
+            }
+            // Usage arrives after `messageStop`, so it closes the stream.
+            "metadata" => {
+                deltas.push(StreamDelta::Usage(extract_bedrock_usage(payload.get("usage"))));
+                self.push_done(deltas);
+            }
+            _ => {}
+        }
+    }
+
+    fn push_done(&mut self, deltas: &mut Vec<StreamDelta>) {
+        if self.done {
+            return;
+        }
+        if let Some(stop_reason) = self.stop_reason.take() {
+            self.done = true;
+            deltas.push(StreamDelta::Done { stop_reason });
+        }
+    }
+}
+
+impl StreamParser for BedrockStreamParser {
+    fn parse_chunk(&mut self, raw: &str) -> Result<Vec<StreamDelta>> {
+        self.buffer.push_str(raw);
+        let mut deltas = Vec::new();
+
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            self.parse_line(&line, &mut deltas);
+        }
+
+        Ok(deltas)
+    }
+
+    fn finish(&mut self) -> Result<Vec<StreamDelta>> {
+        let remaining = std::mem::take(&mut self.buffer);
+        let mut deltas = Vec::new();
+        self.parse_line(&remaining, &mut deltas);
+        self.push_done(&mut deltas);
+        Ok(deltas)
+    }
+}
+
+fn stop_reason(reason: &str) -> String {
+    match reason {
+        "end_turn" | "stop_sequence" => "stop".to_string(),
+        "max_tokens" => "length".to_string(),
+        "tool_use" => "tool_calls".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn extract_bedrock_usage(usage: Option<&Value>) -> TokenUsage {
+    let count = |key: &str| {
+        usage
+            .and_then(|u| u.get(key))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32
+    };
+    // Like Anthropic, `inputTokens` leaves out cached prompt tokens.
+    let cache_read_tokens = count("cacheReadInputTokens");
+    let cache_creation_tokens = count("cacheWriteInputTokens");
+    TokenUsage {
+        input_tokens: count("inputTokens") + cache_read_tokens + cache_creation_tokens,
+        output_tokens: count("outputTokens"),
+        cache_read_tokens,
+        cache_creation_tokens,
+    }
+}
+
+fn str_field(v: &Value, key: &str) -> String {
+    v.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
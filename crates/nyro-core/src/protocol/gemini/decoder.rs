@@ -1,6 +1,8 @@
 use anyhow::Result;
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::{guess_from_url, image_from_url};
+use crate::protocol::semantic::reasoning::config_from_effort;
 use crate::protocol::types::*;
 use crate::protocol::{IngressDecoder, Protocol};
 
@@ -30,6 +32,7 @@ impl GeminiDecoder {
                     content: MessageContent::Text(text),
                     tool_calls: None,
                     tool_call_id: None,
+                    cache_control: None,
                 });
             }
         }
@@ -46,6 +49,7 @@ impl GeminiDecoder {
                         name: fd.name.clone(),
                         description: fd.description.clone(),
                         parameters: fd.parameters.clone().unwrap_or(Value::Object(Default::default())),
+                        cache_control: None,
                     })
                 })
                 .collect()
@@ -57,6 +61,11 @@ impl GeminiDecoder {
             .and_then(|c| c.max_output_tokens);
         let temperature = req.generation_config.as_ref().and_then(|c| c.temperature);
         let top_p = req.generation_config.as_ref().and_then(|c| c.top_p);
+        let reasoning = req
+            .generation_config
+            .as_ref()
+            .and_then(|c| c.thinking_config.as_ref())
+            .and_then(decode_thinking_config);
 
         Ok(InternalRequest {
             messages,
@@ -67,6 +76,7 @@ impl GeminiDecoder {
             top_p,
             tools,
             tool_choice: None,
+            reasoning,
             source_protocol: Protocol::Gemini,
             extra: Default::default(),
         })
@@ -79,6 +89,21 @@ impl IngressDecoder for GeminiDecoder {
     }
 }
 
+fn decode_thinking_config(config: &GeminiThinkingConfig) -> Option<ReasoningConfig> {
+    match config.thinking_budget {
+        Some(0) => Some(ReasoningConfig::default()),
+        Some(budget) => Some(ReasoningConfig {
+            enabled: true,
+            effort: None,
+            budget_tokens: u32::try_from(budget).ok(),
+        }),
+        None => config
+            .thinking_level
+            .as_deref()
+            .and_then(|level| config_from_effort(&Value::String(level.to_string()))),
+    }
+}
+
 fn decode_content(content: &GeminiContent) -> Result<InternalMessage> {
     let mut role = match content.role.as_deref() {
         Some("user") | None => Role::User,
@@ -98,12 +123,18 @@ fn decode_content(content: &GeminiContent) -> Result<InternalMessage> {
                 blocks.push(ContentBlock::Text { text: text.clone() });
             }
             GeminiPart::InlineData { inline_data } => {
-                blocks.push(ContentBlock::Image {
-                    source: ImageSource {
-                        media_type: inline_data.mime_type.clone(),
-                        data: inline_data.data.clone(),
-                    },
-                });
+                let source = ImageSource {
+                    media_type: inline_data.mime_type.clone(),
+                    data: inline_data.data.clone(),
+                };
+                blocks.push(media_block(source, Some(&inline_data.mime_type)));
+            }
+            GeminiPart::FileData { file_data } => {
+                let mime_type = file_data
+                    .mime_type
+                    .as_deref()
+                    .or_else(|| guess_from_url(&file_data.file_uri));
+                blocks.push(media_block(image_from_url(&file_data.file_uri), mime_type));
             }
             GeminiPart::FunctionCall { function_call } => {
                 let id = format!("call_{}", uuid::Uuid::new_v4().simple());
@@ -148,5 +179,15 @@ fn decode_content(content: &GeminiContent) -> Result<InternalMessage> {
         content,
         tool_calls: tool_calls_opt,
         tool_call_id: None,
+        cache_control: None,
     })
 }
+
+/// Gemini sends every file as `inlineData`/`fileData`; anything that is not
+/// an image is a document.
+fn media_block(source: ImageSource, mime_type: Option<&str>) -> ContentBlock {
+    match mime_type {
+        Some(mime_type) if !mime_type.starts_with("image/") => ContentBlock::Document { source, name: None },
+        _ => ContentBlock::Image { source },
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::protocol::types::*;
+use crate::protocol::{EmbeddingEncoder, Protocol};
+
+/// Decode `models/{model}:embedContent`.
+pub fn decode_embed_content(body: &Value, model: &str) -> Result<EmbeddingRequest> {
+    Ok(EmbeddingRequest {
+        model: model.to_string(),
+        input: vec![content_text(body)?],
+        dimensions: output_dimensionality(body),
+        source_protocol: Protocol::Gemini,
+        extra: HashMap::new(),
+    })
+}
+
+/// Decode `models/{model}:batchEmbedContents`. Every entry must share one
+/// output dimensionality, as it becomes a single upstream request.
+pub fn decode_batch_embed_contents(body: &Value, model: &str) -> Result<EmbeddingRequest> {
+    let requests = body
+        .get("requests")
+        .and_then(Value::as_array)
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("batchEmbedContents requires a non-empty requests array"))?;
+
+    let input = requests.iter().map(content_text).collect::<Result<Vec<_>>>()?;
+    let dimensions = output_dimensionality(&requests[0]);
+    if requests.iter().any(|r| output_dimensionality(r) != dimensions) {
+        anyhow::bail!("batchEmbedContents requests must use the same outputDimensionality");
+    }
+
+    Ok(EmbeddingRequest {
+        model: model.to_string(),
+        input,
+        dimensions,
+        source_protocol: Protocol::Gemini,
+        extra: HashMap::new(),
+    })
+}
+
+pub fn format_embed_content(resp: &EmbeddingResponse) -> Value {
+    let values = resp.embeddings.first().cloned().unwrap_or_default();
+    json!({ "embedding": { "values": values } })
+}
+
+pub fn format_batch_embed_contents(resp: &EmbeddingResponse) -> Value {
+    let embeddings: Vec<Value> = resp
+        .embeddings
+        .iter()
+        .map(|values| json!({ "values": values }))
+        .collect();
+    json!({ "embeddings": embeddings })
+}
+
+/// Gemini egress. Always uses `batchEmbedContents`, which also covers a
+/// single input and keeps the path independent of the input count.
+pub struct GeminiEmbeddingEncoder;
+
+impl EmbeddingEncoder for GeminiEmbeddingEncoder {
+    fn encode_embedding_request(&self, req: &EmbeddingRequest) -> Result<Value> {
+        let requests: Vec<Value> = req
+            .input
+            .iter()
+            .map(|text| {
+                let mut entry = json!({
+                    "model": format!("models/{}", req.model),
+                    "content": { "parts": [{ "text": text }] },
+                });
+                if let Some(dimensions) = req.dimensions {
+                    entry["outputDimensionality"] = json!(dimensions);
+                }
+                entry
+            })
+            .collect();
+        Ok(json!({ "requests": requests }))
+    }
+
+    fn embedding_path(&self, model: &str) -> String {
+        format!("/v1beta/models/{model}:batchEmbedContents")
+    }
+
+    fn parse_embedding_response(&self, resp: Value) -> Result<EmbeddingResponse> {
+        let entries: Vec<&Value> = match resp.get("embeddings").and_then(Value::as_array) {
+            Some(list) => list.iter().collect(),
+            None => resp.get("embedding").into_iter().collect(),
+        };
+        if entries.is_empty() {
+            anyhow::bail!("Gemini embedding response missing embeddings");
+        }
+        let embeddings = entries
+            .into_iter()
+            .map(|e| {
+                e.get("values")
+                    .and_then(Value::as_array)
+                    .map(|values| values.iter().map(|v| v.as_f64().unwrap_or_default()).collect())
+                    .ok_or_else(|| anyhow::anyhow!("Gemini embedding missing values"))
+            })
+            .collect::<Result<_>>()?;
+
+        // Gemini does not report usage for embeddings; the gateway estimates it.
+        Ok(EmbeddingResponse {
+            model: String::new(),
+            embeddings,
+            usage: TokenUsage::default(),
+        })
+    }
+}
+
+fn content_text(request: &Value) -> Result<String> {
+    let parts = request
+        .pointer("/content/parts")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow::anyhow!("embed request missing content.parts"))?;
+    Ok(parts
+        .iter()
+        .filter_map(|p| p.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn output_dimensionality(request: &Value) -> Option<u32> {
+    request
+        .get("outputDimensionality")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_roundtrip_keeps_order() {
+        let body = json!({
+            "requests": [
+                {"model": "models/e", "content": {"parts": [{"text": "first"}]}},
+                {"model": "models/e", "content": {"parts": [{"text": "second"}]}}
+            ]
+        });
+        let req = decode_batch_embed_contents(&body, "e").unwrap();
+        assert_eq!(req.input, vec!["first", "second"]);
+
+        let resp = EmbeddingResponse {
+            model: "e".to_string(),
+            embeddings: vec![vec![0.1], vec![0.2]],
+            usage: TokenUsage::default(),
+        };
+        let out = format_batch_embed_contents(&resp);
+        assert_eq!(out["embeddings"][1]["values"], json!([0.2]));
+    }
+
+    #[test]
+    fn single_embed_reads_dimensionality() {
+        let body = json!({
+            "content": {"parts": [{"text": "hi"}]},
+            "outputDimensionality": 64
+        });
+        let req = decode_embed_content(&body, "e").unwrap();
+        assert_eq!(req.dimensions, Some(64));
+        assert!(decode_embed_content(&json!({}), "e").is_err());
+    }
+
+    #[test]
+    fn encoder_targets_batch_endpoint() {
+        let req = EmbeddingRequest {
+            model: "text-embedding-004".to_string(),
+            input: vec!["a".to_string()],
+            dimensions: Some(8),
+            source_protocol: Protocol::OpenAI,
+            extra: HashMap::new(),
+        };
+        let body = GeminiEmbeddingEncoder.encode_embedding_request(&req).unwrap();
+        assert_eq!(body["requests"][0]["model"], "models/text-embedding-004");
+        assert_eq!(body["requests"][0]["outputDimensionality"], 8);
+        assert_eq!(
+            GeminiEmbeddingEncoder.embedding_path("text-embedding-004"),
+            "/v1beta/models/text-embedding-004:batchEmbedContents"
+        );
+
+        let resp = GeminiEmbeddingEncoder
+            .parse_embedding_response(json!({"embeddings": [{"values": [0.5, 1.0]}]}))
+            .unwrap();
+        assert_eq!(resp.embeddings, vec![vec![0.5, 1.0]]);
+    }
+}
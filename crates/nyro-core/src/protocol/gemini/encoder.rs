@@ -2,6 +2,8 @@ use anyhow::Result;
 use reqwest::header::HeaderMap;
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::{PDF_MEDIA_TYPE, guess_from_url, inline_data, remote_url};
+use crate::protocol::semantic::reasoning::thinking_budget;
 use crate::protocol::types::*;
 use crate::protocol::EgressEncoder;
 
@@ -17,7 +19,12 @@ impl EgressEncoder for GeminiEncoder {
                 system_parts.push(serde_json::json!({"text": msg.content.as_text()}));
                 continue;
             }
-            contents.push(encode_content(msg)?);
+            let content = encode_content(msg)?;
+            // A turn that held only reasoning has nothing left to send.
+            if content["parts"].as_array().is_some_and(|parts| parts.is_empty()) {
+                continue;
+            }
+            contents.push(content);
         }
 
         let mut body = serde_json::json!({
@@ -43,6 +50,15 @@ impl EgressEncoder for GeminiEncoder {
         if let Some(p) = req.top_p {
             gen_config.insert("topP".into(), p.into());
         }
+        if let Some(reasoning) = &req.reasoning {
+            let thinking = if reasoning.enabled {
+                let budget = thinking_budget(reasoning).map_or(-1, i64::from);
+                serde_json::json!({"thinkingBudget": budget, "includeThoughts": true})
+            } else {
+                serde_json::json!({"thinkingBudget": 0})
+            };
+            gen_config.insert("thinkingConfig".into(), thinking);
+        }
         if !gen_config.is_empty() {
             obj.insert("generationConfig".into(), Value::Object(gen_config));
         }
@@ -146,16 +162,12 @@ fn encode_content(msg: &InternalMessage) -> Result<Value> {
         MessageContent::Blocks(blocks) => {
             blocks
                 .iter()
-                .map(|b| match b {
+                .filter_map(|b| Some(match b {
                     ContentBlock::Text { text } => serde_json::json!({"text": text}),
-                    ContentBlock::Image { source } => {
-                        serde_json::json!({
-                            "inlineData": {
-                                "mimeType": source.media_type,
-                                "data": source.data,
-                            }
-                        })
-                    }
+                    // Earlier reasoning is not replayed to other model families.
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => return None,
+                    ContentBlock::Image { source } => media_part(source, "image/jpeg"),
+                    ContentBlock::Document { source, .. } => media_part(source, PDF_MEDIA_TYPE),
                     ContentBlock::ToolUse { id: _, name, input } => {
                         serde_json::json!({"functionCall": {"name": name, "args": input}})
                     }
@@ -167,10 +179,32 @@ fn encode_content(msg: &InternalMessage) -> Result<Value> {
                             "functionResponse": {"name": tool_use_id, "response": content}
                         })
                     }
-                })
+                }))
                 .collect()
         }
     };
 
     Ok(serde_json::json!({"role": role, "parts": parts}))
 }
+
+/// `fileData` for a URL, `inlineData` for bytes. `default_mime` stands in
+/// when the URL does not reveal the type.
+fn media_part(source: &ImageSource, default_mime: &str) -> Value {
+    match remote_url(source) {
+        Some(url) => serde_json::json!({
+            "fileData": {
+                "mimeType": guess_from_url(url).unwrap_or(default_mime),
+                "fileUri": url,
+            }
+        }),
+        None => {
+            let (mime_type, data) = inline_data(source).unwrap_or_default();
+            serde_json::json!({
+                "inlineData": {
+                    "mimeType": mime_type,
+                    "data": data,
+                }
+            })
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod types;
 pub mod decoder;
+pub mod embeddings;
 pub mod encoder;
 pub mod stream;
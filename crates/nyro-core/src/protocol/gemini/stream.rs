@@ -19,12 +19,17 @@ impl ResponseParser for GeminiResponseParser {
         let content_obj = candidate.and_then(|c| c.get("content"));
 
         let mut text = String::new();
+        let mut reasoning = String::new();
         let mut tool_calls = Vec::new();
 
         if let Some(parts) = content_obj.and_then(|c| c.get("parts")).and_then(|p| p.as_array()) {
             for part in parts {
                 if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
-                    text.push_str(t);
+                    if is_thought(part) {
+                        reasoning.push_str(t);
+                    } else {
+                        text.push_str(t);
+                    }
                 }
                 if let Some(fc) = part.get("functionCall") {
                     let name = fc.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string();
@@ -59,7 +64,9 @@ impl ResponseParser for GeminiResponseParser {
             id: format!("gen-{}", uuid::Uuid::new_v4().simple()),
             model,
             content: text,
-            reasoning_content: None,
+            reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
+            reasoning_signature: None,
+            redacted_reasoning: Vec::new(),
             tool_calls,
             response_items: None,
             stop_reason,
@@ -76,6 +83,9 @@ impl ResponseFormatter for GeminiResponseFormatter {
     fn format_response(&self, resp: &InternalResponse) -> Value {
         let mut parts = Vec::new();
 
+        if let Some(reasoning) = resp.reasoning_content.as_deref().filter(|r| !r.is_empty()) {
+            parts.push(serde_json::json!({"text": reasoning, "thought": true}));
+        }
         if !resp.content.is_empty() {
             parts.push(serde_json::json!({"text": resp.content}));
         }
@@ -100,11 +110,7 @@ impl ResponseFormatter for GeminiResponseFormatter {
                 "content": {"role": "model", "parts": parts},
                 "finishReason": finish_reason,
             }],
-            "usageMetadata": {
-                "promptTokenCount": resp.usage.input_tokens,
-                "candidatesTokenCount": resp.usage.output_tokens,
-                "totalTokenCount": resp.usage.input_tokens + resp.usage.output_tokens,
-            }
+            "usageMetadata": gemini_usage(&resp.usage),
         })
     }
 }
@@ -181,8 +187,10 @@ fn parse_gemini_chunk(chunk: &Value, deltas: &mut Vec<StreamDelta>, first: &mut
         .and_then(|p| p.as_array())
     {
         for part in parts {
-            if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                if !text.is_empty() {
+            if let Some(text) = part.get("text").and_then(|t| t.as_str()).filter(|t| !t.is_empty()) {
+                if is_thought(part) {
+                    deltas.push(StreamDelta::ReasoningDelta(text.to_string()));
+                } else {
                     deltas.push(StreamDelta::TextDelta(text.to_string()));
                 }
             }
@@ -264,12 +272,14 @@ impl StreamFormatter for GeminiStreamFormatter {
                 StreamDelta::ReasoningDelta(text) => {
                     let chunk = serde_json::json!({
                         "candidates": [{
-                            "content": {"role": "model", "parts": [{"text": text}]},
+                            "content": {"role": "model", "parts": [{"text": text, "thought": true}]},
                         }],
                         "modelVersion": self.model,
                     });
                     events.push(SseEvent::new(None, chunk.to_string()));
                 }
+                // Signed and withheld reasoning has no form in this dialect.
+                StreamDelta::ReasoningSignature(_) | StreamDelta::RedactedReasoning(_) => {}
                 StreamDelta::TextDelta(text) => {
                     let chunk = serde_json::json!({
                         "candidates": [{
@@ -305,14 +315,7 @@ impl StreamFormatter for GeminiStreamFormatter {
                     });
                     events.push(SseEvent::new(None, chunk.to_string()));
                 }
-                StreamDelta::Usage(u) => {
-                    if u.input_tokens > 0 {
-                        self.usage.input_tokens = u.input_tokens;
-                    }
-                    if u.output_tokens > 0 {
-                        self.usage.output_tokens = u.output_tokens;
-                    }
-                }
+                StreamDelta::Usage(u) => self.usage.merge(u),
                 StreamDelta::Done { stop_reason } => {
                     let gemini_reason = match stop_reason.as_str() {
                         "stop" => "STOP",
@@ -324,11 +327,7 @@ impl StreamFormatter for GeminiStreamFormatter {
                             "content": {"role": "model", "parts": []},
                             "finishReason": gemini_reason,
                         }],
-                        "usageMetadata": {
-                            "promptTokenCount": self.usage.input_tokens,
-                            "candidatesTokenCount": self.usage.output_tokens,
-                            "totalTokenCount": self.usage.input_tokens + self.usage.output_tokens,
-                        }
+                        "usageMetadata": gemini_usage(&self.usage),
                     });
                     events.push(SseEvent::new(None, chunk.to_string()));
                 }
@@ -347,6 +346,11 @@ impl StreamFormatter for GeminiStreamFormatter {
     }
 }
 
+/// Thought summaries, sent when the request set `includeThoughts`.
+fn is_thought(part: &Value) -> bool {
+    part.get("thought").and_then(Value::as_bool) == Some(true)
+}
+
 fn extract_gemini_usage(v: &Value) -> TokenUsage {
     let usage = v
         .get("usageMetadata")
@@ -380,7 +384,22 @@ fn extract_gemini_usage(v: &Value) -> TokenUsage {
     TokenUsage {
         input_tokens: input as u32,
         output_tokens: output as u32,
+        cache_read_tokens: first_u64(u, &["cachedContentTokenCount"]).unwrap_or(0) as u32,
+        ..Default::default()
+    }
+}
+
+fn gemini_usage(usage: &TokenUsage) -> Value {
+    let mut metadata = serde_json::json!({
+        "promptTokenCount": usage.input_tokens,
+        "candidatesTokenCount": usage.output_tokens,
+        "totalTokenCount": usage.input_tokens + usage.output_tokens,
+    });
+    // Gemini only reports the field when part of the prompt was cached.
+    if usage.cache_read_tokens > 0 {
+        metadata["cachedContentTokenCount"] = usage.cache_read_tokens.into();
     }
+    metadata
 }
 
 fn first_u64(obj: &Value, keys: &[&str]) -> Option<u64> {
@@ -27,6 +27,10 @@ pub enum GeminiPart {
         #[serde(rename = "inlineData")]
         inline_data: GeminiInlineData,
     },
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: GeminiFileData,
+    },
     FunctionCall {
         #[serde(rename = "functionCall")]
         function_call: GeminiFunctionCall,
@@ -44,6 +48,14 @@ pub struct GeminiInlineData {
     pub data: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GeminiFileData {
+    #[serde(rename = "mimeType", default)]
+    pub mime_type: Option<String>,
+    #[serde(rename = "fileUri")]
+    pub file_uri: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GeminiFunctionCall {
     pub name: String,
@@ -63,6 +75,17 @@ pub struct GeminiGenerationConfig {
     pub max_output_tokens: Option<u32>,
     #[serde(rename = "topP")]
     pub top_p: Option<f64>,
+    #[serde(rename = "thinkingConfig")]
+    pub thinking_config: Option<GeminiThinkingConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GeminiThinkingConfig {
+    /// `0` turns thinking off, `-1` leaves the budget to the model.
+    #[serde(rename = "thinkingBudget")]
+    pub thinking_budget: Option<i64>,
+    #[serde(rename = "thinkingLevel")]
+    pub thinking_level: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -2,6 +2,8 @@ pub mod types;
 pub mod openai;
 pub mod anthropic;
 pub mod gemini;
+pub mod ollama;
+pub mod bedrock;
 pub mod semantic;
 
 use reqwest::header::HeaderMap;
@@ -16,16 +18,26 @@ pub enum Protocol {
     /// Routes as "openai" but uses Responses-specific formatters.
     #[serde(rename = "openai_responses")]
     ResponsesAPI,
+    /// Ollama native API (`/api/chat`, NDJSON streaming).
+    Ollama,
+    /// Ollama `/api/generate` ingress. Routes as "ollama" but answers with
+    /// `response` text instead of a chat `message`.
+    #[serde(rename = "ollama_generate")]
+    OllamaGenerate,
+    /// AWS Bedrock Converse API. Egress only; there is no Bedrock ingress.
+    Bedrock,
 }
 
 impl Protocol {
     /// The base protocol string used for route matching.
-    /// `ResponsesAPI` shares routes with `OpenAI`.
+    /// `ResponsesAPI` shares routes with `OpenAI`, `OllamaGenerate` with `Ollama`.
     pub fn route_protocol(&self) -> &'static str {
         match self {
             Protocol::OpenAI | Protocol::ResponsesAPI => "openai",
             Protocol::Anthropic => "anthropic",
             Protocol::Gemini => "gemini",
+            Protocol::Ollama | Protocol::OllamaGenerate => "ollama",
+            Protocol::Bedrock => "bedrock",
         }
     }
 }
@@ -37,6 +49,9 @@ impl std::fmt::Display for Protocol {
             Protocol::Anthropic => write!(f, "anthropic"),
             Protocol::Gemini => write!(f, "gemini"),
             Protocol::ResponsesAPI => write!(f, "openai_responses"),
+            Protocol::Ollama => write!(f, "ollama"),
+            Protocol::OllamaGenerate => write!(f, "ollama_generate"),
+            Protocol::Bedrock => write!(f, "bedrock"),
         }
     }
 }
@@ -50,6 +65,9 @@ impl std::str::FromStr for Protocol {
             "anthropic" => Ok(Protocol::Anthropic),
             "gemini" => Ok(Protocol::Gemini),
             "openai_responses" => Ok(Protocol::ResponsesAPI),
+            "ollama" => Ok(Protocol::Ollama),
+            "ollama_generate" => Ok(Protocol::OllamaGenerate),
+            "bedrock" => Ok(Protocol::Bedrock),
             _ => anyhow::bail!("unknown protocol: {s}"),
         }
     }
@@ -102,6 +120,22 @@ pub trait StreamFormatter: Send {
     fn usage(&self) -> types::TokenUsage;
 }
 
+// ── Embeddings: internal ↔ provider ──
+
+pub trait EmbeddingEncoder: Send {
+    fn encode_embedding_request(
+        &self,
+        req: &types::EmbeddingRequest,
+    ) -> anyhow::Result<serde_json::Value>;
+
+    fn embedding_path(&self, model: &str) -> String;
+
+    fn parse_embedding_response(
+        &self,
+        resp: serde_json::Value,
+    ) -> anyhow::Result<types::EmbeddingResponse>;
+}
+
 // ── SSE helper ──
 
 #[derive(Debug, Clone)]
@@ -129,6 +163,15 @@ impl SseEvent {
 }
 
 // ── Factory functions ──
+//
+// `get_decoder`, `get_response_formatter` and `get_stream_formatter` take the
+// ingress protocol. The proxy never passes an egress-only protocol to them;
+// if a caller does, it is served as OpenAI and logged rather than failing the
+// request mid-flight.
+
+fn egress_only_ingress(protocol: Protocol, factory: &str) {
+    tracing::error!("{factory} called with egress-only protocol {protocol}, falling back to openai");
+}
 
 pub fn get_decoder(protocol: Protocol) -> Box<dyn IngressDecoder + Send> {
     match protocol {
@@ -136,53 +179,104 @@ pub fn get_decoder(protocol: Protocol) -> Box<dyn IngressDecoder + Send> {
         Protocol::Anthropic => Box::new(anthropic::decoder::AnthropicDecoder),
         Protocol::Gemini => Box::new(gemini::decoder::GeminiDecoder),
         Protocol::ResponsesAPI => Box::new(openai::responses::decoder::ResponsesDecoder),
+        Protocol::Ollama => Box::new(ollama::decoder::OllamaDecoder),
+        Protocol::OllamaGenerate => Box::new(ollama::decoder::OllamaGenerateDecoder),
+        Protocol::Bedrock => {
+            egress_only_ingress(protocol, "get_decoder");
+            Box::new(openai::decoder::OpenAIDecoder)
+        }
     }
 }
 
 pub fn get_encoder(protocol: Protocol) -> Box<dyn EgressEncoder + Send> {
     match protocol {
-        Protocol::OpenAI | Protocol::ResponsesAPI => Box::new(openai::encoder::OpenAIEncoder),
+        Protocol::OpenAI => Box::new(openai::encoder::OpenAIEncoder),
+        Protocol::ResponsesAPI => Box::new(openai::responses::encoder::ResponsesEncoder),
         Protocol::Anthropic => Box::new(anthropic::encoder::AnthropicEncoder),
         Protocol::Gemini => Box::new(gemini::encoder::GeminiEncoder),
+        Protocol::Ollama | Protocol::OllamaGenerate => Box::new(ollama::encoder::OllamaEncoder),
+        Protocol::Bedrock => Box::new(bedrock::encoder::BedrockEncoder),
+    }
+}
+
+/// Embedding egress for a provider protocol, if it has an embeddings API.
+pub fn get_embedding_encoder(protocol: Protocol) -> Option<Box<dyn EmbeddingEncoder>> {
+    match protocol {
+        Protocol::OpenAI | Protocol::ResponsesAPI => {
+            Some(Box::new(openai::embeddings::OpenAIEmbeddingEncoder))
+        }
+        Protocol::Gemini => Some(Box::new(gemini::embeddings::GeminiEmbeddingEncoder)),
+        Protocol::Ollama | Protocol::OllamaGenerate => {
+            Some(Box::new(ollama::embeddings::OllamaEmbeddingEncoder))
+        }
+        Protocol::Anthropic | Protocol::Bedrock => None,
     }
 }
 
 pub fn get_response_parser(protocol: Protocol) -> Box<dyn ResponseParser> {
     match protocol {
-        Protocol::OpenAI | Protocol::ResponsesAPI => Box::new(openai::stream::OpenAIResponseParser),
+        Protocol::OpenAI => Box::new(openai::stream::OpenAIResponseParser),
+        Protocol::ResponsesAPI => Box::new(openai::responses::parser::ResponsesResponseParser),
         Protocol::Anthropic => Box::new(anthropic::stream::AnthropicResponseParser),
         Protocol::Gemini => Box::new(gemini::stream::GeminiResponseParser),
+        Protocol::Ollama | Protocol::OllamaGenerate => {
+            Box::new(ollama::stream::OllamaResponseParser)
+        }
+        Protocol::Bedrock => Box::new(bedrock::stream::BedrockResponseParser),
     }
 }
 
 pub fn get_response_formatter(protocol: Protocol) -> Box<dyn ResponseFormatter> {
     match protocol {
         Protocol::OpenAI => Box::new(openai::stream::OpenAIResponseFormatter),
+        Protocol::Bedrock => {
+            egress_only_ingress(protocol, "get_response_formatter");
+            Box::new(openai::stream::OpenAIResponseFormatter)
+        }
         Protocol::Anthropic => Box::new(anthropic::stream::AnthropicResponseFormatter),
         Protocol::Gemini => Box::new(gemini::stream::GeminiResponseFormatter),
         Protocol::ResponsesAPI => {
             Box::new(openai::responses::formatter::ResponsesResponseFormatter)
         }
+        Protocol::Ollama => Box::new(ollama::stream::OllamaResponseFormatter(
+            ollama::stream::OllamaEndpoint::Chat,
+        )),
+        Protocol::OllamaGenerate => Box::new(ollama::stream::OllamaResponseFormatter(
+            ollama::stream::OllamaEndpoint::Generate,
+        )),
     }
 }
 
 pub fn get_stream_parser(protocol: Protocol) -> Box<dyn StreamParser> {
     match protocol {
-        Protocol::OpenAI | Protocol::ResponsesAPI => {
-            Box::new(openai::stream::OpenAIStreamParser::new())
-        }
+        Protocol::OpenAI => Box::new(openai::stream::OpenAIStreamParser::new()),
+        Protocol::ResponsesAPI => Box::new(openai::responses::parser::ResponsesStreamParser::new()),
         Protocol::Anthropic => Box::new(anthropic::stream::AnthropicStreamParser::new()),
         Protocol::Gemini => Box::new(gemini::stream::GeminiStreamParser::new()),
+        Protocol::Ollama | Protocol::OllamaGenerate => {
+            Box::new(ollama::stream::OllamaStreamParser::new())
+        }
+        Protocol::Bedrock => Box::new(bedrock::stream::BedrockStreamParser::new()),
     }
 }
 
 pub fn get_stream_formatter(protocol: Protocol) -> Box<dyn StreamFormatter> {
     match protocol {
         Protocol::OpenAI => Box::new(openai::stream::OpenAIStreamFormatter::new()),
+        Protocol::Bedrock => {
+            egress_only_ingress(protocol, "get_stream_formatter");
+            Box::new(openai::stream::OpenAIStreamFormatter::new())
+        }
         Protocol::Anthropic => Box::new(anthropic::stream::AnthropicStreamFormatter::new()),
         Protocol::Gemini => Box::new(gemini::stream::GeminiStreamFormatter::new()),
         Protocol::ResponsesAPI => {
             Box::new(openai::responses::stream::ResponsesStreamFormatter::new())
         }
+        Protocol::Ollama => Box::new(ollama::stream::OllamaStreamFormatter::new(
+            ollama::stream::OllamaEndpoint::Chat,
+        )),
+        Protocol::OllamaGenerate => Box::new(ollama::stream::OllamaStreamFormatter::new(
+            ollama::stream::OllamaEndpoint::Generate,
+        )),
     }
 }
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+
+use crate::protocol::semantic::multimodal::sniff_base64;
+use crate::protocol::semantic::reasoning::config_from_effort;
+use crate::protocol::types::*;
+use crate::protocol::{IngressDecoder, Protocol};
+
+/// Request fields only an Ollama upstream understands. Other encoders that
+/// forward `extra` verbatim must drop these for Ollama-sourced requests.
+pub const NATIVE_ONLY_FIELDS: &[&str] = &["options", "keep_alive", "format"];
+
+/// `options` entries with an OpenAI-style top-level equivalent.
+const PORTABLE_OPTIONS: &[&str] = &["stop", "seed", "frequency_penalty", "presence_penalty"];
+
+/// Ollama `/api/chat` ingress.
+pub struct OllamaDecoder;
+
+impl IngressDecoder for OllamaDecoder {
+    fn decode_request(&self, body: Value) -> Result<InternalRequest> {
+        let Value::Object(mut obj) = body else {
+            anyhow::bail!("request body must be a JSON object");
+        };
+        let model = take_model(&mut obj)?;
+
+        let mut messages = Vec::new();
+        // Ollama ties tool results to calls by function name, not by id.
+        let mut pending_calls: Vec<(String, String)> = Vec::new();
+        let raw_messages = match obj.remove("messages") {
+            Some(Value::Array(items)) => items,
+            Some(_) => anyhow::bail!("messages must be an array"),
+            None => Vec::new(),
+        };
+        for (i, msg) in raw_messages.iter().enumerate() {
+            messages.push(decode_message(msg, i, &mut pending_calls)?);
+        }
+
+        let tools = obj.remove("tools").and_then(|v| match v {
+            Value::Array(items) => Some(items.iter().filter_map(decode_tool).collect()),
+            _ => None,
+        });
+
+        build_request(obj, model, messages, tools, Protocol::Ollama)
+    }
+}
+
+/// Ollama `/api/generate` ingress: a prompt (plus optional system prompt and
+/// images) decoded as a one-turn chat.
+pub struct OllamaGenerateDecoder;
+
+impl IngressDecoder for OllamaGenerateDecoder {
+    fn decode_request(&self, body: Value) -> Result<InternalRequest> {
+        let Value::Object(mut obj) = body else {
+            anyhow::bail!("request body must be a JSON object");
+        };
+        let model = take_model(&mut obj)?;
+
+        let mut messages = Vec::new();
+        if let Some(Value::String(system)) = obj.remove("system")
+            && !system.is_empty()
+        {
+            messages.push(text_message(Role::System, system));
+        }
+        let prompt = match obj.remove("prompt") {
+            Some(Value::String(p)) => p,
+            _ => String::new(),
+        };
+        let images = image_blocks(obj.remove("images").as_ref());
+        let content = if images.is_empty() {
+            MessageContent::Text(prompt)
+        } else {
+            let mut blocks = vec![ContentBlock::Text { text: prompt }];
+            blocks.extend(images);
+            MessageContent::Blocks(blocks)
+        };
+        messages.push(InternalMessage {
+            role: Role::User,
+            content,
+            tool_calls: None,
+            tool_call_id: None,
+            cache_control: None,
+        });
+        // `raw`, `context`, `suffix` and `template` have no portable meaning.
+        for key in ["raw", "context", "suffix", "template"] {
+            obj.remove(key);
+        }
+
+        build_request(obj, model, messages, None, Protocol::OllamaGenerate)
+    }
+}
+
+fn take_model(obj: &mut Map<String, Value>) -> Result<String> {
+    match obj.remove("model") {
+        Some(Value::String(m)) if !m.is_empty() => Ok(m),
+        _ => anyhow::bail!("missing model"),
+    }
+}
+
+/// Shared tail of both decoders: `stream` defaults to true in Ollama, and
+/// sampling `options` are lifted onto the internal request.
+fn build_request(
+    mut obj: Map<String, Value>,
+    model: String,
+    messages: Vec<InternalMessage>,
+    tools: Option<Vec<ToolDef>>,
+    source_protocol: Protocol,
+) -> Result<InternalRequest> {
+    let stream = obj.remove("stream").and_then(|v| v.as_bool()).unwrap_or(true);
+    let mut extra: HashMap<String, Value> = HashMap::new();
+
+    let mut options = match obj.remove("options") {
+        Some(Value::Object(o)) => o,
+        _ => Map::new(),
+    };
+    let temperature = options.remove("temperature").and_then(|v| v.as_f64());
+    let top_p = options.remove("top_p").and_then(|v| v.as_f64());
+    let max_tokens = options
+        .remove("num_predict")
+        .and_then(|v| v.as_i64())
+        .filter(|n| *n > 0)
+        .map(|n| n as u32);
+    for key in PORTABLE_OPTIONS {
+        if let Some(v) = options.remove(*key) {
+            extra.insert((*key).to_string(), v);
+        }
+    }
+    if !options.is_empty() {
+        extra.insert("options".into(), Value::Object(options));
+    }
+
+    if let Some(format) = obj.remove("format") {
+        let response_format = match &format {
+            Value::String(f) if f == "json" => Some(json!({"type": "json_object"})),
+            Value::Object(_) => Some(json!({
+                "type": "json_schema",
+                "json_schema": {"name": "response", "schema": format},
+            })),
+            _ => None,
+        };
+        if let Some(rf) = response_format {
+            extra.insert("response_format".into(), rf);
+        }
+        extra.insert("format".into(), format);
+    }
+    if let Some(v) = obj.remove("keep_alive") {
+        extra.insert("keep_alive".into(), v);
+    }
+    let reasoning = obj.remove("think").as_ref().and_then(config_from_effort);
+
+    Ok(InternalRequest {
+        messages,
+        model,
+        stream,
+        temperature,
+        max_tokens,
+        top_p,
+        tools,
+        tool_choice: None,
+        reasoning,
+        source_protocol,
+        extra,
+    })
+}
+
+fn decode_message(
+    msg: &Value,
+    position: usize,
+    pending_calls: &mut Vec<(String, String)>,
+) -> Result<InternalMessage> {
+    let role = match msg.get("role").and_then(Value::as_str) {
+        Some("system") => Role::System,
+        Some("user") => Role::User,
+        Some("assistant") => Role::Assistant,
+        Some("tool") => Role::Tool,
+        other => anyhow::bail!("unknown role: {}", other.unwrap_or_default()),
+    };
+    let text = msg
+        .get("content")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let images = image_blocks(msg.get("images"));
+    let content = if images.is_empty() {
+        MessageContent::Text(text)
+    } else {
+        let mut blocks = Vec::with_capacity(images.len() + 1);
+        if !text.is_empty() {
+            blocks.push(ContentBlock::Text { text });
+        }
+        blocks.extend(images);
+        MessageContent::Blocks(blocks)
+    };
+
+    let tool_calls = msg
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .filter(|calls| !calls.is_empty())
+        .map(|calls| {
+            calls
+                .iter()
+                .enumerate()
+                .map(|(j, call)| {
+                    let function = call.get("function");
+                    let name = function
+                        .and_then(|f| f.get("name"))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let arguments = match function.and_then(|f| f.get("arguments")) {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(args) => args.to_string(),
+                        None => "{}".to_string(),
+                    };
+                    let id = format!("call_ollama_{position}_{j}");
+                    pending_calls.push((id.clone(), name.clone()));
+                    ToolCall {
+                        id,
+                        name,
+                        arguments,
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+    let tool_call_id = if role == Role::Tool {
+        let name = msg.get("tool_name").and_then(Value::as_str);
+        let pos = match name {
+            Some(name) => pending_calls.iter().position(|(_, n)| n == name),
+            None if !pending_calls.is_empty() => Some(0),
+            None => None,
+        };
+        pos.map(|p| pending_calls.remove(p).0)
+    } else {
+        None
+    };
+
+    Ok(InternalMessage {
+        role,
+        content,
+        tool_calls,
+        tool_call_id,
+        cache_control: None,
+    })
+}
+
+fn decode_tool(tool: &Value) -> Option<ToolDef> {
+    let function = tool.get("function")?;
+    Some(ToolDef {
+        name: function.get("name")?.as_str()?.to_string(),
+        description: function
+            .get("description")
+            .and_then(Value::as_str)
+            .map(String::from),
+        parameters: function
+            .get("parameters")
+            .cloned()
+            .unwrap_or(Value::Object(Default::default())),
+        cache_control: None,
+    })
+}
+
+fn image_blocks(images: Option<&Value>) -> Vec<ContentBlock> {
+    images
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        // Bare base64: the MIME type comes from the magic bytes.
+        .map(|data| ContentBlock::Image {
+            source: ImageSource {
+                media_type: sniff_base64(data).unwrap_or("image/jpeg").to_string(),
+                data: data.to_string(),
+            },
+        })
+        .collect()
+}
+
+fn text_message(role: Role, text: String) -> InternalMessage {
+    InternalMessage {
+        role,
+        content: MessageContent::Text(text),
+        tool_calls: None,
+        tool_call_id: None,
+        cache_control: None,
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::protocol::types::*;
+use crate::protocol::EmbeddingEncoder;
+
+/// Ollama native `/api/embed` egress.
+pub struct OllamaEmbeddingEncoder;
+
+impl EmbeddingEncoder for OllamaEmbeddingEncoder {
+    fn encode_embedding_request(&self, req: &EmbeddingRequest) -> Result<Value> {
+        let mut body = json!({
+            "model": req.model,
+            "input": req.input,
+        });
+        if let Some(dimensions) = req.dimensions {
+            body["dimensions"] = json!(dimensions);
+        }
+        for key in ["truncate", "keep_alive", "options"] {
+            if let Some(v) = req.extra.get(key) {
+                body[key] = v.clone();
+            }
+        }
+        Ok(body)
+    }
+
+    fn embedding_path(&self, _model: &str) -> String {
+        "/api/embed".to_string()
+    }
+
+    fn parse_embedding_response(&self, resp: Value) -> Result<EmbeddingResponse> {
+        let embeddings = resp
+            .get("embeddings")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("Ollama embed response missing embeddings"))?
+            .iter()
+            .map(|vector| {
+                vector
+                    .as_array()
+                    .map(|values| values.iter().map(|v| v.as_f64().unwrap_or_default()).collect())
+                    .ok_or_else(|| anyhow::anyhow!("Ollama embedding is not an array"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(EmbeddingResponse {
+            model: resp
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            embeddings,
+            usage: TokenUsage {
+                input_tokens: resp
+                    .get("prompt_eval_count")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0) as u32,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+    use std::collections::HashMap;
+
+    #[test]
+    fn encodes_and_parses_embed() {
+        let req = EmbeddingRequest {
+            model: "nomic-embed-text".to_string(),
+            input: vec!["a".to_string(), "b".to_string()],
+            dimensions: None,
+            source_protocol: Protocol::OpenAI,
+            extra: HashMap::from([("keep_alive".to_string(), json!("5m"))]),
+        };
+        let body = OllamaEmbeddingEncoder.encode_embedding_request(&req).unwrap();
+        assert_eq!(body["input"], json!(["a", "b"]));
+        assert_eq!(body["keep_alive"], "5m");
+
+        let resp = OllamaEmbeddingEncoder
+            .parse_embedding_response(json!({
+                "model": "nomic-embed-text",
+                "embeddings": [[0.1, 0.2], [0.3, 0.4]],
+                "prompt_eval_count": 2
+            }))
+            .unwrap();
+        assert_eq!(resp.embeddings.len(), 2);
+        assert_eq!(resp.usage.input_tokens, 2);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use serde_json::{Value, json};
+
+use crate::protocol::semantic::multimodal::inline_data;
+use crate::protocol::types::*;
+use crate::protocol::EgressEncoder;
+
+/// Top-level sampling fields that Ollama expects under `options`.
+const OPTION_KEYS: &[&str] = &[
+    "seed",
+    "frequency_penalty",
+    "presence_penalty",
+    "top_k",
+    "num_ctx",
+];
+
+/// Ollama native `/api/chat` egress.
+pub struct OllamaEncoder;
+
+impl EgressEncoder for OllamaEncoder {
+    fn encode_request(&self, req: &InternalRequest) -> Result<(Value, HeaderMap)> {
+        let mut body = json!({
+            "model": req.model,
+            "messages": encode_messages(&req.messages)?,
+            "stream": req.stream,
+        });
+        let obj = body.as_object_mut().unwrap();
+
+        let mut options = serde_json::Map::new();
+        if let Some(t) = req.temperature {
+            options.insert("temperature".into(), t.into());
+        }
+        if let Some(p) = req.top_p {
+            options.insert("top_p".into(), p.into());
+        }
+        if let Some(m) = req.max_tokens {
+            options.insert("num_predict".into(), m.into());
+        }
+        for key in OPTION_KEYS {
+            if let Some(v) = req.extra.get(*key) {
+                options.insert((*key).into(), v.clone());
+            }
+        }
+        match req.extra.get("stop") {
+            Some(Value::String(stop)) => {
+                options.insert("stop".into(), json!([stop]));
+            }
+            Some(stop @ Value::Array(_)) => {
+                options.insert("stop".into(), stop.clone());
+            }
+            _ => {}
+        }
+        // Explicit client `options` (num_ctx, repeat_penalty, …) win.
+        if let Some(Value::Object(client_options)) = req.extra.get("options") {
+            for (k, v) in client_options {
+                options.insert(k.clone(), v.clone());
+            }
+        }
+        if !options.is_empty() {
+            obj.insert("options".into(), Value::Object(options));
+        }
+
+        for key in ["keep_alive", "format"] {
+            if let Some(v) = req.extra.get(key) {
+                obj.insert(key.into(), v.clone());
+            }
+        }
+        // Levels are for gpt-oss; Ollama reads any level as `true` elsewhere.
+        if let Some(reasoning) = &req.reasoning {
+            let think = match reasoning.effort {
+                Some(effort) if reasoning.enabled => json!(effort.as_str()),
+                _ => json!(reasoning.enabled),
+            };
+            obj.insert("think".into(), think);
+        }
+        if !obj.contains_key("format")
+            && let Some(format) = req.extra.get("response_format").and_then(encode_format)
+        {
+            obj.insert("format".into(), format);
+        }
+
+        if let Some(ref tools) = req.tools {
+            let tools_val: Vec<Value> = tools
+                .iter()
+                .map(|t| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": t.name,
+                            "description": t.description,
+                            "parameters": t.parameters,
+                        }
+                    })
+                })
+                .collect();
+            obj.insert("tools".into(), Value::Array(tools_val));
+        }
+
+        Ok((body, HeaderMap::new()))
+    }
+
+    fn egress_path(&self, _model: &str, _stream: bool) -> String {
+        "/api/chat".to_string()
+    }
+}
+
+/// OpenAI `response_format` → Ollama `format` (`"json"` or a JSON schema).
+fn encode_format(response_format: &Value) -> Option<Value> {
+    match response_format.get("type").and_then(Value::as_str)? {
+        "json_object" => Some(Value::String("json".into())),
+        "json_schema" => response_format.pointer("/json_schema/schema").cloned(),
+        _ => None,
+    }
+}
+
+fn encode_messages(messages: &[InternalMessage]) -> Result<Vec<Value>> {
+    // Ollama correlates tool results by function name rather than call id.
+    let mut call_names: HashMap<&str, &str> = HashMap::new();
+    let mut out = Vec::with_capacity(messages.len());
+
+    for msg in messages {
+        let role = match msg.role {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        };
+        let (text, images) = split_content(&msg.content)?;
+        let mut encoded = json!({ "role": role, "content": text });
+
+        if !images.is_empty() {
+            encoded["images"] = json!(images);
+        }
+
+        if let Some(ref calls) = msg.tool_calls {
+            let calls: Vec<Value> = calls
+                .iter()
+                .map(|tc| {
+                    call_names.insert(tc.id.as_str(), tc.name.as_str());
+                    let arguments: Value = serde_json::from_str(&tc.arguments)
+                        .unwrap_or(Value::Object(Default::default()));
+                    json!({ "function": { "name": tc.name, "arguments": arguments } })
+                })
+                .collect();
+            encoded["tool_calls"] = Value::Array(calls);
+        }
+
+        if msg.role == Role::Tool
+            && let Some(name) = msg
+                .tool_call_id
+                .as_deref()
+                .and_then(|id| call_names.get(id))
+        {
+            encoded["tool_name"] = json!(name);
+        }
+
+        out.push(encoded);
+    }
+    Ok(out)
+}
+
+/// Text and base64 images of a message. Images given by remote URL cannot be
+/// sent to Ollama and fail the encode rather than being left out.
+fn split_content(content: &MessageContent) -> Result<(String, Vec<String>)> {
+    let blocks = match content {
+        MessageContent::Text(t) => return Ok((t.clone(), Vec::new())),
+        MessageContent::Blocks(blocks) => blocks,
+    };
+
+    let mut text = Vec::new();
+    let mut images = Vec::new();
+    for block in blocks {
+        match block {
+            ContentBlock::Text { text: t } => text.push(t.clone()),
+            ContentBlock::Image { source } => match inline_data(source) {
+                Some((_, data)) => images.push(data.to_string()),
+                None => anyhow::bail!("remote image URLs are not supported by Ollama"),
+            },
+            ContentBlock::ToolResult { content, .. } => match content.as_str() {
+                Some(t) => text.push(t.to_string()),
+                None => text.push(content.to_string()),
+            },
+            // Normalization already swapped in the extracted text if allowed.
+            ContentBlock::ToolUse { .. } | ContentBlock::Document { .. } => {}
+            // Earlier reasoning is not replayed to other model families.
+            ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {}
+        }
+    }
+    Ok((text.join("\n"), images))
+}
@@ -0,0 +1,4 @@
+pub mod decoder;
+pub mod embeddings;
+pub mod encoder;
+pub mod stream;
@@ -0,0 +1,381 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::protocol::types::*;
+use crate::protocol::*;
+
+// ── Non-streaming response parser ──
+
+pub struct OllamaResponseParser;
+
+impl ResponseParser for OllamaResponseParser {
+    fn parse_response(&self, resp: Value) -> Result<InternalResponse> {
+        let message = resp.get("message");
+        let content = message
+            .and_then(|m| m.get("content"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let reasoning_content = message
+            .and_then(|m| m.get("thinking"))
+            .and_then(Value::as_str)
+            .filter(|t| !t.is_empty())
+            .map(ToString::to_string);
+        let tool_calls: Vec<ToolCall> = message
+            .and_then(|m| m.get("tool_calls"))
+            .and_then(Value::as_array)
+            .map(|calls| calls.iter().map(parse_tool_call).collect())
+            .unwrap_or_default();
+
+        let stop_reason = Some(stop_reason(&resp, !tool_calls.is_empty()));
+
+        Ok(InternalResponse {
+            id: format!("ollama-{}", uuid::Uuid::new_v4().simple()),
+            model: resp
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            content,
+            reasoning_content,
+            reasoning_signature: None,
+            redacted_reasoning: Vec::new(),
+            tool_calls,
+            response_items: None,
+            stop_reason,
+            usage: extract_ollama_usage(&resp),
+        })
+    }
+}
+
+// ── Non-streaming response formatter ──
+
+/// Which Ollama endpoint the client called; decides where text goes
+/// (`message.content` for chat, `response` for generate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OllamaEndpoint {
+    Chat,
+    Generate,
+}
+
+pub struct OllamaResponseFormatter(pub OllamaEndpoint);
+
+impl ResponseFormatter for OllamaResponseFormatter {
+    fn format_response(&self, resp: &InternalResponse) -> Value {
+        let mut out = record(
+            self.0,
+            &resp.model,
+            &resp.content,
+            resp.reasoning_content.as_deref(),
+        );
+        let tool_calls: Vec<Value> = resp
+            .tool_calls
+            .iter()
+            .map(|tc| tool_call_json(&tc.name, &tc.arguments))
+            .collect();
+        finish_record(
+            &mut out,
+            self.0,
+            resp.stop_reason.as_deref().unwrap_or("stop"),
+            &resp.usage,
+            tool_calls,
+        );
+        out
+    }
+}
+
+// ── Stream parser (upstream Ollama NDJSON → deltas) ──
+
+#[derive(Default)]
+pub struct OllamaStreamParser {
+    buffer: String,
+    started: bool,
+    tool_index: usize,
+}
+
+impl OllamaStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn parse_line(&mut self, line: &str, deltas: &mut Vec<StreamDelta>) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        if let Some(err) = chunk.get("error") {
+            tracing::warn!("ollama stream error: {err}");
+            return;
+        }
+
+        if !self.started {
+            self.started = true;
+            deltas.push(StreamDelta::MessageStart {
+                id: format!("ollama-{}", uuid::Uuid::new_v4().simple()),
+                model: chunk
+                    .get("model")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+
+        if let Some(message) = chunk.get("message") {
+            if let Some(thinking) = message.get("thinking").and_then(Value::as_str)
+                && !thinking.is_empty()
+            {
+                deltas.push(StreamDelta::ReasoningDelta(thinking.to_string()));
+            }
+            if let Some(text) = message.get("content").and_then(Value::as_str)
+                && !text.is_empty()
+            {
+                deltas.push(StreamDelta::TextDelta(text.to_string()));
+            }
+            // Ollama sends each tool call whole, never as argument fragments.
+            for call in message
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let call = parse_tool_call(call);
+                deltas.push(StreamDelta::ToolCallStart {
+                    index: self.tool_index,
+                    id: call.id,
+                    name: call.name,
+                });
+                deltas.push(StreamDelta::ToolCallDelta {
+                    index: self.tool_index,
+                    arguments: call.arguments,
+                });
+                self.tool_index += 1;
+            }
+        }
+
+        if chunk.get("done").and_then(Value::as_bool) == Some(true) {
+            deltas.push(StreamDelta::Usage(extract_ollama_usage(&chunk)));
+            deltas.push(StreamDelta::Done {
+                stop_reason: stop_reason(&chunk, self.tool_index > 0),
+            });
+        }
+    }
+}
+
+impl StreamParser for OllamaStreamParser {
+    fn parse_chunk(&mut self, raw: &str) -> Result<Vec<StreamDelta>> {
+        self.buffer.push_str(raw);
+        let mut deltas = Vec::new();
+
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            self.parse_line(&line, &mut deltas);
+        }
+
+        Ok(deltas)
+    }
+
+    fn finish(&mut self) -> Result<Vec<StreamDelta>> {
+        let remaining = std::mem::take(&mut self.buffer);
+        let mut deltas = Vec::new();
+        self.parse_line(&remaining, &mut deltas);
+        Ok(deltas)
+    }
+}
+
+// ── Stream formatter (deltas → Ollama NDJSON) ──
+
+/// Emits one JSON record per event; the pipeline writes each `SseEvent::data`
+/// as a line. Tool calls are buffered and sent whole, as Ollama does.
+pub struct OllamaStreamFormatter {
+    endpoint: OllamaEndpoint,
+    model: String,
+    usage: TokenUsage,
+    tool_calls: BTreeMap<usize, (String, String)>,
+}
+
+impl OllamaStreamFormatter {
+    pub fn new(endpoint: OllamaEndpoint) -> Self {
+        Self {
+            endpoint,
+            model: String::new(),
+            usage: TokenUsage::default(),
+            tool_calls: BTreeMap::new(),
+        }
+    }
+}
+
+impl StreamFormatter for OllamaStreamFormatter {
+    fn format_deltas(&mut self, deltas: &[StreamDelta]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        for delta in deltas {
+            match delta {
+                StreamDelta::MessageStart { model, .. } => self.model = model.clone(),
+                StreamDelta::ReasoningDelta(text) => {
+                    let out = record(self.endpoint, &self.model, "", Some(text));
+                    events.push(SseEvent::new(None, out.to_string()));
+                }
+                // Signed and withheld reasoning has no form in this dialect.
+                StreamDelta::ReasoningSignature(_) | StreamDelta::RedactedReasoning(_) => {}
+                StreamDelta::TextDelta(text) => {
+                    let out = record(self.endpoint, &self.model, text, None);
+                    events.push(SseEvent::new(None, out.to_string()));
+                }
+                StreamDelta::ToolCallStart { index, name, .. } => {
+                    self.tool_calls.insert(*index, (name.clone(), String::new()));
+                }
+                StreamDelta::ToolCallDelta { index, arguments } => {
+                    if let Some((_, args)) = self.tool_calls.get_mut(index) {
+                        args.push_str(arguments);
+                    }
+                }
+                StreamDelta::Usage(u) => self.usage.merge(u),
+                StreamDelta::Done { stop_reason } => {
+                    let tool_calls = std::mem::take(&mut self.tool_calls)
+                        .into_values()
+                        .map(|(name, args)| tool_call_json(&name, &args))
+                        .collect();
+                    let mut out = record(self.endpoint, &self.model, "", None);
+                    finish_record(&mut out, self.endpoint, stop_reason, &self.usage, tool_calls);
+                    events.push(SseEvent::new(None, out.to_string()));
+                }
+            }
+        }
+        events
+    }
+
+    fn format_done(&mut self) -> Vec<SseEvent> {
+        vec![]
+    }
+
+    fn usage(&self) -> TokenUsage {
+        self.usage.clone()
+    }
+}
+
+fn record(endpoint: OllamaEndpoint, model: &str, text: &str, thinking: Option<&str>) -> Value {
+    let created_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Nanos, true);
+    let mut out = match endpoint {
+        OllamaEndpoint::Chat => json!({
+            "model": model,
+            "created_at": created_at,
+            "message": {"role": "assistant", "content": text},
+            "done": false,
+        }),
+        OllamaEndpoint::Generate => json!({
+            "model": model,
+            "created_at": created_at,
+            "response": text,
+            "done": false,
+        }),
+    };
+    if let Some(thinking) = thinking {
+        match endpoint {
+            OllamaEndpoint::Chat => out["message"]["thinking"] = json!(thinking),
+            OllamaEndpoint::Generate => out["thinking"] = json!(thinking),
+        }
+    }
+    out
+}
+
+fn finish_record(
+    out: &mut Value,
+    endpoint: OllamaEndpoint,
+    stop_reason: &str,
+    usage: &TokenUsage,
+    tool_calls: Vec<Value>,
+) {
+    out["done"] = json!(true);
+    out["done_reason"] = json!(if stop_reason == "length" { "length" } else { "stop" });
+    out["prompt_eval_count"] = json!(usage.input_tokens);
+    out["eval_count"] = json!(usage.output_tokens);
+    if endpoint == OllamaEndpoint::Chat && !tool_calls.is_empty() {
+        out["message"]["tool_calls"] = Value::Array(tool_calls);
+    }
+}
+
+fn tool_call_json(name: &str, arguments: &str) -> Value {
+    let arguments: Value =
+        serde_json::from_str(arguments).unwrap_or(Value::Object(Default::default()));
+    json!({ "function": { "name": name, "arguments": arguments } })
+}
+
+fn parse_tool_call(call: &Value) -> ToolCall {
+    let function = call.get("function");
+    let name = function
+        .and_then(|f| f.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let arguments = match function.and_then(|f| f.get("arguments")) {
+        Some(Value::String(s)) => s.clone(),
+        Some(args) => args.to_string(),
+        None => "{}".to_string(),
+    };
+    ToolCall {
+        id: call
+            .get("id")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple())),
+        name,
+        arguments,
+    }
+}
+
+fn stop_reason(chunk: &Value, saw_tool_call: bool) -> String {
+    if saw_tool_call {
+        return "tool_calls".to_string();
+    }
+    match chunk.get("done_reason").and_then(Value::as_str) {
+        Some("length") => "length".to_string(),
+        Some("stop") | Some("") | None => "stop".to_string(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn extract_ollama_usage(v: &Value) -> TokenUsage {
+    let count = |key: &str| v.get(key).and_then(Value::as_u64).unwrap_or(0) as u32;
+    TokenUsage {
+        input_tokens: count("prompt_eval_count"),
+        output_tokens: count("eval_count"),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_parser_handles_lines_split_across_chunks() {
+        let mut parser = OllamaStreamParser::new();
+        let first = parser
+            .parse_chunk("{\"model\":\"llama3\",\"message\":{\"role\":\"assistant\",\"content\":\"Hel")
+            .unwrap();
+        assert!(first.is_empty());
+
+        let deltas = parser
+            .parse_chunk(
+                "lo\"},\"done\":false}\n\
+                 {\"model\":\"llama3\",\"message\":{\"role\":\"assistant\",\"content\":\"\",\
+                 \"tool_calls\":[{\"function\":{\"name\":\"lookup\",\"arguments\":{\"q\":\"x\"}}}]},\"done\":false}\n",
+            )
+            .unwrap();
+        assert!(matches!(&deltas[0], StreamDelta::MessageStart { model, .. } if model == "llama3"));
+        assert!(matches!(&deltas[1], StreamDelta::TextDelta(t) if t == "Hello"));
+        assert!(matches!(&deltas[3], StreamDelta::ToolCallDelta { arguments, .. } if arguments == "{\"q\":\"x\"}"));
+
+        let tail = parser
+            .parse_chunk("{\"done\":true,\"done_reason\":\"stop\",\"prompt_eval_count\":5,\"eval_count\":7}")
+            .unwrap();
+        assert!(tail.is_empty());
+        let done = parser.finish().unwrap();
+        assert!(matches!(&done[0], StreamDelta::Usage(u) if u.input_tokens == 5 && u.output_tokens == 7));
+        assert!(matches!(&done[1], StreamDelta::Done { stop_reason } if stop_reason == "tool_calls"));
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::{file_data_source, image_from_url};
+use crate::protocol::semantic::reasoning::config_from_effort;
 use crate::protocol::types::*;
 use crate::protocol::{IngressDecoder, Protocol};
 
@@ -27,11 +29,19 @@ impl IngressDecoder for OpenAIDecoder {
                         name: func.get("name")?.as_str()?.to_string(),
                         description: func.get("description").and_then(|d| d.as_str()).map(String::from),
                         parameters: func.get("parameters").cloned().unwrap_or(Value::Object(Default::default())),
+                        cache_control: None,
                     })
                 })
                 .collect()
         });
 
+        // Encoders emit the effort from `reasoning`, in each egress's form.
+        let mut extra = req.extra;
+        let reasoning = extra.get("reasoning_effort").and_then(config_from_effort);
+        if reasoning.is_some() {
+            extra.remove("reasoning_effort");
+        }
+
         Ok(InternalRequest {
             messages,
             model: req.model,
@@ -41,8 +51,9 @@ impl IngressDecoder for OpenAIDecoder {
             top_p: req.top_p,
             tools,
             tool_choice: req.tool_choice,
+            reasoning,
             source_protocol: Protocol::OpenAI,
-            extra: req.extra,
+            extra,
         })
     }
 }
@@ -62,15 +73,13 @@ fn decode_message(msg: OpenAIMessage) -> Result<InternalMessage> {
             let blocks = parts
                 .into_iter()
                 .map(|p| match p {
-                    OpenAIContentPart::Text { text } => ContentBlock::Text { text },
-                    OpenAIContentPart::ImageUrl { image_url } => ContentBlock::Image {
-                        source: ImageSource {
-                            media_type: "image/url".to_string(),
-                            data: image_url.url,
-                        },
-                    },
+                    OpenAIContentPart::Text { text } => Ok(ContentBlock::Text { text }),
+                    OpenAIContentPart::ImageUrl { image_url } => Ok(ContentBlock::Image {
+                        source: image_from_url(&image_url.url),
+                    }),
+                    OpenAIContentPart::File { file } => file_block(file),
                 })
-                .collect();
+                .collect::<Result<_>>()?;
             MessageContent::Blocks(blocks)
         }
         None => MessageContent::Text(String::new()),
@@ -91,5 +100,21 @@ fn decode_message(msg: OpenAIMessage) -> Result<InternalMessage> {
         content,
         tool_calls,
         tool_call_id: msg.tool_call_id,
+        cache_control: None,
+    })
+}
+
+/// Uploaded files (`file_id`) live in the client's OpenAI account, which
+/// other upstreams cannot read, so only inline `file_data` is accepted.
+fn file_block(file: OpenAIFile) -> Result<ContentBlock> {
+    let Some(file_data) = file.file_data.filter(|d| !d.is_empty()) else {
+        match file.file_id {
+            Some(id) => anyhow::bail!("file_id references are not supported ({id}); send the file inline as file_data"),
+            None => anyhow::bail!("file content part needs file_data"),
+        }
+    };
+    Ok(ContentBlock::Document {
+        source: file_data_source(&file_data),
+        name: file.filename,
     })
 }
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use base64::Engine;
+use serde_json::{Value, json};
+
+use crate::protocol::types::*;
+use crate::protocol::{EmbeddingEncoder, Protocol};
+
+/// Decode an OpenAI `/v1/embeddings` request. `encoding_format` is a response
+/// concern and is left to the caller; upstreams are always asked for floats.
+pub fn decode_embedding_request(body: Value) -> Result<EmbeddingRequest> {
+    let Value::Object(mut obj) = body else {
+        anyhow::bail!("request body must be a JSON object");
+    };
+    let model = obj
+        .remove("model")
+        .and_then(|v| v.as_str().map(ToString::to_string))
+        .ok_or_else(|| anyhow::anyhow!("missing model"))?;
+    let input = match obj.remove("input") {
+        Some(Value::String(text)) => vec![text],
+        Some(Value::Array(items)) if !items.is_empty() => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(text) => Ok(text),
+                _ => anyhow::bail!("token array input is not supported; send strings"),
+            })
+            .collect::<Result<_>>()?,
+        _ => anyhow::bail!("input must be a string or a non-empty array of strings"),
+    };
+    let dimensions = obj
+        .remove("dimensions")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32);
+    obj.remove("encoding_format");
+
+    Ok(EmbeddingRequest {
+        model,
+        input,
+        dimensions,
+        source_protocol: Protocol::OpenAI,
+        extra: obj.into_iter().collect::<HashMap<_, _>>(),
+    })
+}
+
+/// Render an OpenAI embeddings list. With `base64`, vectors are packed as
+/// little-endian f32, matching what the official SDKs request by default.
+pub fn format_embedding_response(resp: &EmbeddingResponse, model: &str, base64: bool) -> Value {
+    let data: Vec<Value> = resp
+        .embeddings
+        .iter()
+        .enumerate()
+        .map(|(index, vector)| {
+            let embedding = if base64 {
+                let bytes: Vec<u8> = vector
+                    .iter()
+                    .flat_map(|v| (*v as f32).to_le_bytes())
+                    .collect();
+                Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+            } else {
+                json!(vector)
+            };
+            json!({ "object": "embedding", "index": index, "embedding": embedding })
+        })
+        .collect();
+    json!({
+        "object": "list",
+        "data": data,
+        "model": model,
+        "usage": {
+            "prompt_tokens": resp.usage.input_tokens,
+            "total_tokens": resp.usage.input_tokens,
+        },
+    })
+}
+
+/// OpenAI-compatible `/v1/embeddings` egress.
+pub struct OpenAIEmbeddingEncoder;
+
+impl EmbeddingEncoder for OpenAIEmbeddingEncoder {
+    fn encode_embedding_request(&self, req: &EmbeddingRequest) -> Result<Value> {
+        let mut body = json!({
+            "model": req.model,
+            "input": req.input,
+            "encoding_format": "float",
+        });
+        let obj = body.as_object_mut().unwrap();
+        if let Some(dimensions) = req.dimensions {
+            obj.insert("dimensions".into(), dimensions.into());
+        }
+        for (k, v) in &req.extra {
+            obj.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        Ok(body)
+    }
+
+    fn embedding_path(&self, _model: &str) -> String {
+        "/v1/embeddings".to_string()
+    }
+
+    fn parse_embedding_response(&self, resp: Value) -> Result<EmbeddingResponse> {
+        let data = resp
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("embedding response missing data"))?;
+
+        let mut indexed: Vec<(u64, Vec<f64>)> = data
+            .iter()
+            .enumerate()
+            .map(|(pos, item)| {
+                let index = item.get("index").and_then(Value::as_u64).unwrap_or(pos as u64);
+                let vector = item
+                    .get("embedding")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| anyhow::anyhow!("embedding item missing float vector"))?
+                    .iter()
+                    .map(|v| v.as_f64().unwrap_or_default())
+                    .collect();
+                Ok((index, vector))
+            })
+            .collect::<Result<_>>()?;
+        indexed.sort_by_key(|(index, _)| *index);
+
+        let usage = resp.get("usage");
+        let prompt_tokens = usage
+            .and_then(|u| u.get("prompt_tokens").or_else(|| u.get("total_tokens")))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+
+        Ok(EmbeddingResponse {
+            model: resp
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            embeddings: indexed.into_iter().map(|(_, v)| v).collect(),
+            usage: TokenUsage {
+                input_tokens: prompt_tokens,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_and_parses_out_of_order_data() {
+        let req = EmbeddingRequest {
+            model: "text-embedding-3-small".to_string(),
+            input: vec!["a".to_string(), "b".to_string()],
+            dimensions: Some(256),
+            source_protocol: Protocol::Gemini,
+            extra: HashMap::new(),
+        };
+        let body = OpenAIEmbeddingEncoder.encode_embedding_request(&req).unwrap();
+        assert_eq!(body["input"], json!(["a", "b"]));
+        assert_eq!(body["dimensions"], 256);
+
+        let resp = OpenAIEmbeddingEncoder
+            .parse_embedding_response(json!({
+                "data": [
+                    {"index": 1, "embedding": [0.5]},
+                    {"index": 0, "embedding": [0.25]}
+                ],
+                "model": "text-embedding-3-small",
+                "usage": {"prompt_tokens": 4, "total_tokens": 4}
+            }))
+            .unwrap();
+        assert_eq!(resp.embeddings, vec![vec![0.25], vec![0.5]]);
+        assert_eq!(resp.usage.input_tokens, 4);
+    }
+
+    #[test]
+    fn decodes_string_input_and_packs_base64() {
+        let req = decode_embedding_request(json!({
+            "model": "embed",
+            "input": "hello",
+            "encoding_format": "base64",
+            "user": "u1"
+        }))
+        .unwrap();
+        assert_eq!(req.input, vec!["hello"]);
+        assert_eq!(req.extra.get("user"), Some(&json!("u1")));
+        assert!(!req.extra.contains_key("encoding_format"));
+        assert!(decode_embedding_request(json!({"model": "e", "input": [[1, 2]]})).is_err());
+
+        let resp = EmbeddingResponse {
+            model: "upstream".to_string(),
+            embeddings: vec![vec![1.0]],
+            usage: TokenUsage {
+                input_tokens: 2,
+                ..Default::default()
+            },
+        };
+        let out = format_embedding_response(&resp, "embed", true);
+        assert_eq!(out["data"][0]["embedding"], "AACAPw==");
+        assert_eq!(out["model"], "embed");
+        assert_eq!(out["usage"]["total_tokens"], 2);
+    }
+}
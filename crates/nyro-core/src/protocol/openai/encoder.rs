@@ -4,8 +4,12 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use crate::protocol::ollama::decoder::NATIVE_ONLY_FIELDS;
+use crate::protocol::openai::responses::decoder::NATIVE_ONLY_FIELDS as RESPONSES_NATIVE_FIELDS;
+use crate::protocol::semantic::multimodal::{PDF_MEDIA_TYPE, document_name, image_url, inline_data};
+use crate::protocol::semantic::reasoning::reasoning_effort;
 use crate::protocol::types::*;
-use crate::protocol::EgressEncoder;
+use crate::protocol::{EgressEncoder, Protocol};
 
 pub struct OpenAIEncoder;
 
@@ -54,6 +58,9 @@ impl EgressEncoder for OpenAIEncoder {
         if let Some(ref tc) = req.tool_choice {
             obj.insert("tool_choice".into(), tc.clone());
         }
+        if let Some(reasoning) = req.reasoning.as_ref().filter(|r| r.enabled) {
+            obj.insert("reasoning_effort".into(), reasoning_effort(reasoning).as_str().into());
+        }
 
         if req.stream {
             obj.insert(
@@ -62,7 +69,15 @@ impl EgressEncoder for OpenAIEncoder {
             );
         }
 
+        let from_ollama = req.source_protocol.route_protocol() == "ollama";
+        let from_responses = req.source_protocol == Protocol::ResponsesAPI;
         for (k, v) in &req.extra {
+            if from_ollama && NATIVE_ONLY_FIELDS.contains(&k.as_str()) {
+                continue;
+            }
+            if from_responses && RESPONSES_NATIVE_FIELDS.contains(&k.as_str()) {
+                continue;
+            }
             obj.entry(k.clone()).or_insert_with(|| v.clone());
         }
 
@@ -139,6 +154,7 @@ fn normalize_messages_for_openai(
                 content: MessageContent::Text(String::new()),
                 tool_calls: Some(vec![tc]),
                 tool_call_id: None,
+                cache_control: None,
             });
             seen_tool_call_ids.insert(final_id.clone());
         } else {
@@ -171,6 +187,7 @@ fn normalize_messages_for_openai(
                         arguments: "{}".to_string(),
                     }]),
                     tool_call_id: None,
+                    cache_control: None,
                 });
                 seen_tool_call_ids.insert(final_id.clone());
             }
@@ -396,14 +413,26 @@ fn encode_message(msg: &InternalMessage) -> Result<Value> {
         MessageContent::Blocks(blocks) => {
             let parts: Vec<Value> = blocks
                 .iter()
-                .map(|b| match b {
+                .filter_map(|b| Some(match b {
                     ContentBlock::Text { text } => {
                         serde_json::json!({"type": "text", "text": text})
                     }
+                    // Chat Completions has no way to send reasoning back.
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => return None,
                     ContentBlock::Image { source } => {
                         serde_json::json!({
                             "type": "image_url",
-                            "image_url": {"url": &source.data}
+                            "image_url": {"url": image_url(source)}
+                        })
+                    }
+                    ContentBlock::Document { source, name } => {
+                        let media_type = inline_data(source).map_or(PDF_MEDIA_TYPE, |(media_type, _)| media_type);
+                        serde_json::json!({
+                            "type": "file",
+                            "file": {
+                                "filename": document_name(name.as_deref(), media_type),
+                                "file_data": image_url(source),
+                            }
                         })
                     }
                     ContentBlock::ToolUse { id, name, input } => {
@@ -420,7 +449,7 @@ fn encode_message(msg: &InternalMessage) -> Result<Value> {
                             "tool_call_id": tool_use_id,
                         })
                     }
-                })
+                }))
                 .collect();
             map.insert("content".into(), Value::Array(parts));
         }
@@ -1,5 +1,6 @@
 pub mod types;
 pub mod decoder;
+pub mod embeddings;
 pub mod encoder;
 pub mod responses;
 pub mod stream;
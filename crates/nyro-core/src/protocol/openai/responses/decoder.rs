@@ -3,9 +3,17 @@ use std::collections::HashMap;
 use anyhow::Result;
 use serde_json::Value;
 
+use crate::protocol::semantic::multimodal::{file_data_source, image_from_url};
+use crate::protocol::semantic::reasoning::config_from_effort;
 use crate::protocol::types::*;
 use crate::protocol::{IngressDecoder, Protocol};
 
+/// Request fields kept verbatim in `extra` for a Responses upstream, which
+/// can take reasoning items, item references, hosted tools and reasoning
+/// options (`summary`) as sent. Chat Completions encoders must drop these
+/// for Responses-sourced requests.
+pub const NATIVE_ONLY_FIELDS: &[&str] = &["input", "instructions", "tools", "reasoning"];
+
 pub struct ResponsesDecoder;
 
 impl IngressDecoder for ResponsesDecoder {
@@ -31,6 +39,10 @@ impl IngressDecoder for ResponsesDecoder {
         let mut messages = Vec::new();
         let tools = parse_tools(obj.get("tools"))?;
         let tool_choice = obj.get("tool_choice").cloned();
+        let reasoning = obj
+            .get("reasoning")
+            .and_then(|r| r.get("effort"))
+            .and_then(config_from_effort);
 
         if let Some(inst) = obj.get("instructions").and_then(|v| v.as_str()) {
             if !inst.is_empty() {
@@ -39,6 +51,7 @@ impl IngressDecoder for ResponsesDecoder {
                     content: MessageContent::Text(inst.to_string()),
                     tool_calls: None,
                     tool_call_id: None,
+                    cache_control: None,
                 });
             }
         }
@@ -54,6 +67,7 @@ impl IngressDecoder for ResponsesDecoder {
                     content: MessageContent::Text(text.clone()),
                     tool_calls: None,
                     tool_call_id: None,
+                    cache_control: None,
                 });
             }
             Value::Array(items) => {
@@ -81,11 +95,16 @@ impl IngressDecoder for ResponsesDecoder {
             "tools",
             "tool_choice",
         ];
-        let extra: HashMap<String, Value> = obj
+        let mut extra: HashMap<String, Value> = obj
             .iter()
             .filter(|(k, _)| !known.contains(&k.as_str()))
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
+        for key in NATIVE_ONLY_FIELDS {
+            if let Some(v) = obj.get(*key) {
+                extra.insert((*key).to_string(), v.clone());
+            }
+        }
 
         Ok(InternalRequest {
             messages,
@@ -96,6 +115,7 @@ impl IngressDecoder for ResponsesDecoder {
             top_p,
             tools,
             tool_choice,
+            reasoning,
             source_protocol: Protocol::ResponsesAPI,
             extra,
         })
@@ -130,6 +150,7 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
             content: MessageContent::Text(output_text),
             tool_calls: None,
             tool_call_id: Some(call_id),
+            cache_control: None,
         }));
     }
 
@@ -162,11 +183,37 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
                 arguments,
             }]),
             tool_call_id: None,
+            cache_control: None,
+        }));
+    }
+
+    if item_type == "reasoning" {
+        let Some(encrypted) = item.get("encrypted_content").and_then(Value::as_str) else {
+            // Stored reasoning referenced by id only means something to OpenAI.
+            return Ok(None);
+        };
+        let thinking = item
+            .get("summary")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Ok(Some(InternalMessage {
+            role: Role::Assistant,
+            content: MessageContent::Blocks(vec![ContentBlock::Thinking {
+                thinking,
+                signature: Some(encrypted.to_string()),
+            }]),
+            tool_calls: None,
+            tool_call_id: None,
+            cache_control: None,
         }));
     }
 
     if item_type != "message" {
-        // Ignore other responses item types (reasoning, file_search_call, etc).
+        // Ignore other responses item types (file_search_call, etc).
         return Ok(None);
     }
 
@@ -183,6 +230,7 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
         Some(Value::String(text)) => MessageContent::Text(text.clone()),
         Some(Value::Array(blocks)) => {
             let mut texts = Vec::new();
+            let mut media = Vec::new();
             for block in blocks {
                 let block_type = block.get("type").and_then(|v| v.as_str()).unwrap_or("text");
                 match block_type {
@@ -193,6 +241,31 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
                             anyhow::bail!("text block missing 'text' field");
                         }
                     }
+                    "input_image" => {
+                        // `image_url` is a string here, unlike Chat Completions.
+                        let url = block
+                            .get("image_url")
+                            .and_then(|v| v.as_str().or_else(|| v.get("url").and_then(|u| u.as_str())))
+                            .ok_or_else(|| anyhow::anyhow!("input_image block missing 'image_url'"))?;
+                        media.push(ContentBlock::Image {
+                            source: image_from_url(url),
+                        });
+                    }
+                    "input_file" => {
+                        let source = if let Some(data) = block.get("file_data").and_then(Value::as_str) {
+                            file_data_source(data)
+                        } else if let Some(url) = block.get("file_url").and_then(Value::as_str) {
+                            image_from_url(url)
+                        } else if let Some(id) = block.get("file_id").and_then(Value::as_str) {
+                            anyhow::bail!("file_id references are not supported ({id}); send the file as file_data or file_url");
+                        } else {
+                            anyhow::bail!("input_file block needs 'file_data' or 'file_url'");
+                        };
+                        media.push(ContentBlock::Document {
+                            source,
+                            name: block.get("filename").and_then(Value::as_str).map(str::to_string),
+                        });
+                    }
                     other => {
                         anyhow::bail!(
                             "unsupported content block type in responses input: {other}"
@@ -201,10 +274,19 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
                 }
             }
             let text = texts.join("");
-            if text.is_empty() {
-                return Ok(None);
+            if media.is_empty() {
+                if text.is_empty() {
+                    return Ok(None);
+                }
+                MessageContent::Text(text)
+            } else {
+                let mut parts = Vec::new();
+                if !text.is_empty() {
+                    parts.push(ContentBlock::Text { text });
+                }
+                parts.extend(media);
+                MessageContent::Blocks(parts)
             }
-            MessageContent::Text(text)
         }
         Some(_) => anyhow::bail!("unsupported content type in responses input item"),
         None => return Ok(None),
@@ -215,6 +297,7 @@ fn decode_input_item(item: &Value) -> Result<Option<InternalMessage>> {
         content,
         tool_calls: None,
         tool_call_id: None,
+        cache_control: None,
     }))
 }
 
@@ -256,6 +339,7 @@ fn parse_tools(raw_tools: Option<&Value>) -> Result<Option<Vec<ToolDef>>> {
             name,
             description,
             parameters,
+            cache_control: None,
         });
     }
 
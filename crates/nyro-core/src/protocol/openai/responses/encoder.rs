@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use serde_json::{Map, Value, json};
+
+use super::decoder::NATIVE_ONLY_FIELDS;
+use crate::protocol::semantic::multimodal::{PDF_MEDIA_TYPE, document_name, image_url, inline_data, remote_url};
+use crate::protocol::semantic::reasoning::reasoning_effort;
+use crate::protocol::types::*;
+use crate::protocol::{EgressEncoder, Protocol};
+
+/// Top-level Responses parameters forwarded from `extra` when the request
+/// did not come in through the Responses API itself.
+const PORTABLE_FIELDS: &[&str] = &[
+    "background",
+    "include",
+    "max_tool_calls",
+    "metadata",
+    "parallel_tool_calls",
+    "previous_response_id",
+    "prompt_cache_key",
+    "reasoning",
+    "safety_identifier",
+    "service_tier",
+    "store",
+    "text",
+    "top_logprobs",
+    "truncation",
+    "user",
+];
+
+/// OpenAI Responses API (`POST /v1/responses`) egress.
+pub struct ResponsesEncoder;
+
+impl EgressEncoder for ResponsesEncoder {
+    fn encode_request(&self, req: &InternalRequest) -> Result<(Value, HeaderMap)> {
+        let mut instructions = Vec::new();
+        let mut input = Vec::new();
+        for msg in &req.messages {
+            if msg.role == Role::System {
+                let text = msg.content.as_text();
+                if !text.trim().is_empty() {
+                    instructions.push(text);
+                }
+                continue;
+            }
+            encode_message(msg, &mut input);
+        }
+
+        let mut body = json!({
+            "model": req.model,
+            "input": input,
+            "stream": req.stream,
+        });
+        let obj = body.as_object_mut().unwrap();
+        if !instructions.is_empty() {
+            obj.insert("instructions".into(), Value::String(instructions.join("\n\n")));
+        }
+        if let Some(m) = req.max_tokens {
+            obj.insert("max_output_tokens".into(), m.into());
+        }
+        if let Some(t) = req.temperature {
+            obj.insert("temperature".into(), t.into());
+        }
+        if let Some(p) = req.top_p {
+            obj.insert("top_p".into(), p.into());
+        }
+        if let Some(ref tools) = req.tools
+            && !tools.is_empty()
+        {
+            let tools: Vec<Value> = tools
+                .iter()
+                .map(|t| {
+                    json!({
+                        "type": "function",
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.parameters,
+                    })
+                })
+                .collect();
+            obj.insert("tools".into(), Value::Array(tools));
+        }
+        if let Some(choice) = req.tool_choice.as_ref().and_then(map_tool_choice) {
+            obj.insert("tool_choice".into(), choice);
+        }
+
+        if req.source_protocol == Protocol::ResponsesAPI {
+            // Same dialect on both sides: the client's own input items
+            // (reasoning, item references) and hosted tools go out as sent.
+            for key in NATIVE_ONLY_FIELDS {
+                if let Some(v) = req.extra.get(*key) {
+                    obj.insert((*key).to_string(), v.clone());
+                }
+            }
+            for (k, v) in &req.extra {
+                obj.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+        } else {
+            translate_chat_extras(&req.extra, obj);
+            if let Some(config) = req.reasoning.as_ref().filter(|r| r.enabled)
+                && let Some(reasoning) = obj.entry("reasoning").or_insert_with(|| json!({})).as_object_mut()
+            {
+                reasoning.insert("effort".into(), reasoning_effort(config).as_str().into());
+            }
+            // Clients of other dialects keep no server-side state, so they
+            // need reasoning back as `encrypted_content` to continue it.
+            if obj.contains_key("reasoning") || has_thinking(req) {
+                include_encrypted_reasoning(obj);
+            }
+        }
+
+        Ok((body, HeaderMap::new()))
+    }
+
+    fn egress_path(&self, _model: &str, _stream: bool) -> String {
+        "/v1/responses".to_string()
+    }
+}
+
+/// Carry over the Chat Completions knobs that have a Responses equivalent;
+/// the Responses API rejects unknown top-level parameters.
+fn translate_chat_extras(extra: &HashMap<String, Value>, obj: &mut Map<String, Value>) {
+    for key in PORTABLE_FIELDS {
+        if let Some(v) = extra.get(*key) {
+            obj.insert((*key).to_string(), v.clone());
+        }
+    }
+    if !obj.contains_key("max_output_tokens")
+        && let Some(m) = extra.get("max_completion_tokens")
+    {
+        obj.insert("max_output_tokens".into(), m.clone());
+    }
+    if let Some(format) = extra.get("response_format").and_then(map_response_format)
+        && let Some(text) = obj.entry("text").or_insert_with(|| json!({})).as_object_mut()
+    {
+        text.insert("format".into(), format);
+    }
+}
+
+fn has_thinking(req: &InternalRequest) -> bool {
+    req.messages.iter().any(|msg| match &msg.content {
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .any(|b| matches!(b, ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. })),
+        MessageContent::Text(_) => false,
+    })
+}
+
+fn include_encrypted_reasoning(obj: &mut Map<String, Value>) {
+    const ENCRYPTED_REASONING: &str = "reasoning.encrypted_content";
+    if let Some(include) = obj.entry("include").or_insert_with(|| json!([])).as_array_mut()
+        && !include.iter().any(|v| v == ENCRYPTED_REASONING)
+    {
+        include.push(json!(ENCRYPTED_REASONING));
+    }
+}
+
+/// Chat `response_format` → Responses `text.format`, which flattens the
+/// `json_schema` wrapper.
+fn map_response_format(format: &Value) -> Option<Value> {
+    match format.get("type").and_then(Value::as_str)? {
+        "json_schema" => {
+            let mut flat = format.get("json_schema")?.as_object()?.clone();
+            flat.insert("type".into(), json!("json_schema"));
+            Some(Value::Object(flat))
+        }
+        other => Some(json!({ "type": other })),
+    }
+}
+
+fn map_tool_choice(raw: &Value) -> Option<Value> {
+    match raw {
+        Value::String(s) => Some(json!(if s == "any" { "required" } else { s.as_str() })),
+        Value::Object(obj) => {
+            let name = obj
+                .get("name")
+                .or_else(|| obj.get("function").and_then(|f| f.get("name")))
+                .and_then(Value::as_str);
+            match obj.get("type").and_then(Value::as_str) {
+                Some("function" | "tool") => name.map(|n| json!({ "type": "function", "name": n })),
+                Some("any") => Some(json!("required")),
+                Some("auto") => Some(json!("auto")),
+                Some("none") => Some(json!("none")),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn encode_message(msg: &InternalMessage, input: &mut Vec<Value>) {
+    if msg.role == Role::Tool {
+        let (call_id, output) = match &msg.content {
+            MessageContent::Blocks(blocks) => blocks
+                .iter()
+                .find_map(|b| match b {
+                    ContentBlock::ToolResult { tool_use_id, content } => {
+                        Some((tool_use_id.clone(), tool_output(content)))
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| (String::new(), msg.content.as_text())),
+            MessageContent::Text(t) => (String::new(), t.clone()),
+        };
+        let call_id = msg
+            .tool_call_id
+            .clone()
+            .filter(|id| !id.is_empty())
+            .unwrap_or(call_id);
+        input.push(json!({ "type": "function_call_output", "call_id": call_id, "output": output }));
+        return;
+    }
+
+    let assistant = msg.role == Role::Assistant;
+    let text_type = if assistant { "output_text" } else { "input_text" };
+    let mut content = Vec::new();
+    let mut reasoning = Vec::new();
+    let mut trailing = Vec::new();
+    match &msg.content {
+        MessageContent::Text(t) => {
+            if !t.is_empty() {
+                content.push(json!({ "type": text_type, "text": t }));
+            }
+        }
+        MessageContent::Blocks(blocks) => {
+            for block in blocks {
+                match block {
+                    ContentBlock::Text { text } => {
+                        if !text.is_empty() {
+                            content.push(json!({ "type": text_type, "text": text }));
+                        }
+                    }
+                    ContentBlock::Image { source } => {
+                        content.push(json!({ "type": "input_image", "image_url": image_url(source) }));
+                    }
+                    ContentBlock::Document { source, name } => content.push(input_file(source, name.as_deref())),
+                    // Unsigned reasoning cannot be referenced upstream.
+                    ContentBlock::Thinking { thinking, signature: Some(signature) } => {
+                        reasoning.push(reasoning_item(thinking, signature));
+                    }
+                    ContentBlock::Thinking { signature: None, .. } => {}
+                    ContentBlock::RedactedThinking { data } => reasoning.push(reasoning_item("", data)),
+                    ContentBlock::ToolUse { id, name, input } => trailing.push(json!({
+                        "type": "function_call",
+                        "call_id": id,
+                        "name": name,
+                        "arguments": input.to_string(),
+                    })),
+                    ContentBlock::ToolResult { tool_use_id, content } => trailing.push(json!({
+                        "type": "function_call_output",
+                        "call_id": tool_use_id,
+                        "output": tool_output(content),
+                    })),
+                }
+            }
+        }
+    }
+
+    // Reasoning precedes the message and calls it led to.
+    input.extend(reasoning);
+    if !content.is_empty() {
+        let role = if assistant { "assistant" } else { "user" };
+        input.push(json!({ "type": "message", "role": role, "content": content }));
+    }
+    input.extend(trailing);
+    for tc in msg.tool_calls.iter().flatten() {
+        input.push(json!({
+            "type": "function_call",
+            "call_id": tc.id,
+            "name": tc.name,
+            "arguments": tc.arguments,
+        }));
+    }
+}
+
+fn input_file(source: &ImageSource, name: Option<&str>) -> Value {
+    match remote_url(source) {
+        Some(url) => json!({ "type": "input_file", "file_url": url }),
+        None => {
+            let media_type = inline_data(source).map_or(PDF_MEDIA_TYPE, |(media_type, _)| media_type);
+            json!({
+                "type": "input_file",
+                "filename": document_name(name, media_type),
+                "file_data": image_url(source),
+            })
+        }
+    }
+}
+
+/// Earlier reasoning as a stateless input item: no `id`, with the signature
+/// as `encrypted_content`.
+fn reasoning_item(text: &str, encrypted_content: &str) -> Value {
+    let summary: Vec<Value> = if text.is_empty() {
+        Vec::new()
+    } else {
+        vec![json!({ "type": "summary_text", "text": text })]
+    };
+    json!({ "type": "reasoning", "summary": summary, "encrypted_content": encrypted_content })
+}
+
+fn tool_output(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join(""),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -1,7 +1,7 @@
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::protocol::types::{InternalResponse, ResponseItem};
+use crate::protocol::types::{InternalResponse, ResponseItem, TokenUsage};
 use crate::protocol::ResponseFormatter;
 
 pub struct ResponsesResponseFormatter;
@@ -21,16 +21,7 @@ impl ResponseFormatter for ResponsesResponseFormatter {
         if let Some(items) = &resp.response_items {
             for item in items {
                 match item {
-                    ResponseItem::Reasoning { text } => {
-                        output.push(serde_json::json!({
-                            "type": "reasoning",
-                            "id": format!("rs_{}", Uuid::new_v4().simple()),
-                            "summary": [{
-                                "type": "summary_text",
-                                "text": text
-                            }]
-                        }));
-                    }
+                    ResponseItem::Reasoning { text } => output.push(reasoning_item(text, resp)),
                     ResponseItem::FunctionCall {
                         call_id,
                         name,
@@ -48,18 +39,12 @@ impl ResponseFormatter for ResponsesResponseFormatter {
                     ResponseItem::Message { text } => {
                         output_text.push_str(text);
                     }
+                    ResponseItem::Native(item) => output.push(item.clone()),
                 }
             }
         } else {
-            if let Some(reasoning) = &resp.reasoning_content {
-                output.push(serde_json::json!({
-                    "type": "reasoning",
-                    "id": format!("rs_{}", Uuid::new_v4().simple()),
-                    "summary": [{
-                        "type": "summary_text",
-                        "text": reasoning
-                    }]
-                }));
+            if resp.reasoning_content.is_some() || resp.reasoning_signature.is_some() {
+                output.push(reasoning_item(resp.reasoning_content.as_deref().unwrap_or_default(), resp));
             }
             for tc in &resp.tool_calls {
                 output.push(serde_json::json!({
@@ -74,6 +59,15 @@ impl ResponseFormatter for ResponsesResponseFormatter {
             output_text.push_str(&resp.content);
         }
 
+        for data in &resp.redacted_reasoning {
+            output.push(serde_json::json!({
+                "type": "reasoning",
+                "id": format!("rs_{}", Uuid::new_v4().simple()),
+                "summary": [],
+                "encrypted_content": data,
+            }));
+        }
+
         if !output_text.is_empty() {
             output.push(serde_json::json!({
                 "type": "message",
@@ -95,11 +89,35 @@ impl ResponseFormatter for ResponsesResponseFormatter {
             "model": resp.model,
             "output": output,
             "output_text": output_text,
-            "usage": {
-                "input_tokens": resp.usage.input_tokens,
-                "output_tokens": resp.usage.output_tokens,
-                "total_tokens": resp.usage.input_tokens + resp.usage.output_tokens
-            }
+            "usage": responses_usage(&resp.usage),
         })
     }
 }
+
+/// A reasoning output item; the upstream's signature travels as
+/// `encrypted_content` so the client can send it back next turn.
+fn reasoning_item(text: &str, resp: &InternalResponse) -> Value {
+    let summary = if text.is_empty() {
+        serde_json::json!([])
+    } else {
+        serde_json::json!([{ "type": "summary_text", "text": text }])
+    };
+    let mut item = serde_json::json!({
+        "type": "reasoning",
+        "id": format!("rs_{}", Uuid::new_v4().simple()),
+        "summary": summary,
+    });
+    if let Some(signature) = &resp.reasoning_signature {
+        item["encrypted_content"] = Value::String(signature.clone());
+    }
+    item
+}
+
+pub(crate) fn responses_usage(usage: &TokenUsage) -> Value {
+    serde_json::json!({
+        "input_tokens": usage.input_tokens,
+        "input_tokens_details": { "cached_tokens": usage.cache_read_tokens },
+        "output_tokens": usage.output_tokens,
+        "total_tokens": usage.input_tokens + usage.output_tokens,
+    })
+}
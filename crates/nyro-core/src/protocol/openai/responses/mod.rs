@@ -1,3 +1,5 @@
 pub mod decoder;
+pub mod encoder;
 pub mod formatter;
+pub mod parser;
 pub mod stream;
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::protocol::types::*;
+use crate::protocol::{ResponseParser, StreamParser};
+
+// ── Non-streaming response parser ──
+
+pub struct ResponsesResponseParser;
+
+impl ResponseParser for ResponsesResponseParser {
+    fn parse_response(&self, resp: Value) -> Result<InternalResponse> {
+        let mut content = String::new();
+        let mut reasoning = String::new();
+        let mut reasoning_signature = None;
+        let mut tool_calls = Vec::new();
+        let mut items = Vec::new();
+
+        for item in resp
+            .get("output")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            match item.get("type").and_then(Value::as_str).unwrap_or("") {
+                "reasoning" => {
+                    reasoning.push_str(&reasoning_text(item));
+                    if let Some(encrypted) = item.get("encrypted_content").and_then(Value::as_str) {
+                        reasoning_signature = Some(encrypted.to_string());
+                    }
+                    items.push(ResponseItem::Native(item.clone()));
+                }
+                "function_call" => {
+                    let call = ToolCall {
+                        id: str_field(item, "call_id"),
+                        name: str_field(item, "name"),
+                        arguments: str_field(item, "arguments"),
+                    };
+                    items.push(ResponseItem::FunctionCall {
+                        call_id: call.id.clone(),
+                        name: call.name.clone(),
+                        arguments: call.arguments.clone(),
+                    });
+                    tool_calls.push(call);
+                }
+                "message" => {
+                    let text: String = item
+                        .get("content")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter(|part| part.get("type").and_then(Value::as_str) == Some("output_text"))
+                        .filter_map(|part| part.get("text").and_then(Value::as_str))
+                        .collect();
+                    content.push_str(&text);
+                    items.push(ResponseItem::Message { text });
+                }
+                // Hosted tool calls (web search, file search, …) ran upstream;
+                // only a Responses client can make use of them.
+                _ => items.push(ResponseItem::Native(item.clone())),
+            }
+        }
+
+        Ok(InternalResponse {
+            id: str_field(&resp, "id"),
+            model: str_field(&resp, "model"),
+            content,
+            reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
+            reasoning_signature,
+            redacted_reasoning: Vec::new(),
+            stop_reason: Some(stop_reason(&resp, !tool_calls.is_empty())),
+            tool_calls,
+            response_items: Some(items),
+            usage: extract_usage(resp.get("usage")),
+        })
+    }
+}
+
+// ── Stream parser (semantic `response.*` events → deltas) ──
+
+#[derive(Default)]
+pub struct ResponsesStreamParser {
+    buffer: String,
+    started: bool,
+    /// Upstream `output_index` → tool call index.
+    tool_indices: HashMap<u64, usize>,
+    done: bool,
+}
+
+impl ResponsesStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn parse_event(&mut self, event: &Value, deltas: &mut Vec<StreamDelta>) {
+        let output_index = event.get("output_index").and_then(Value::as_u64);
+        match event.get("type").and_then(Value::as_str).unwrap_or("") {
+            "response.created" if !self.started => {
+                self.started = true;
+                let response = event.get("response").unwrap_or(&Value::Null);
+                deltas.push(StreamDelta::MessageStart {
+                    id: str_field(response, "id"),
+                    model: str_field(response, "model"),
+                });
+            }
+            "response.output_item.added" => {
+                let item = event.get("item").unwrap_or(&Value::Null);
+                if item.get("type").and_then(Value::as_str) == Some("function_call") {
+                    let index = self.tool_indices.len();
+                    self.tool_indices.insert(output_index.unwrap_or(index as u64), index);
+                    deltas.push(StreamDelta::ToolCallStart {
+                        index,
+                        id: str_field(item, "call_id"),
+                        name: str_field(item, "name"),
+                    });
+                }
+            }
+            "response.output_item.done" => {
+                let item = event.get("item").unwrap_or(&Value::Null);
+                if item.get("type").and_then(Value::as_str) == Some("reasoning")
+                    && let Some(encrypted) = item.get("encrypted_content").and_then(Value::as_str)
+                {
+                    deltas.push(StreamDelta::ReasoningSignature(encrypted.to_string()));
+                }
+            }
+            "response.function_call_arguments.delta" => {
+                if let Some(&index) = output_index.and_then(|i| self.tool_indices.get(&i)) {
+                    deltas.push(StreamDelta::ToolCallDelta {
+                        index,
+                        arguments: str_field(event, "delta"),
+                    });
+                }
+            }
+            "response.output_text.delta" => {
+                deltas.push(StreamDelta::TextDelta(str_field(event, "delta")));
+            }
+            "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => {
+                deltas.push(StreamDelta::ReasoningDelta(str_field(event, "delta")));
+            }
+            "response.completed" | "response.incomplete" => {
+                let response = event.get("response").unwrap_or(&Value::Null);
+                deltas.push(StreamDelta::Usage(extract_usage(response.get("usage"))));
+                self.done = true;
+                deltas.push(StreamDelta::Done {
+                    stop_reason: stop_reason(response, !self.tool_indices.is_empty()),
+                });
+            }
+            "response.failed" | "error" => {
+                let message = event
+                    .pointer("/response/error/message")
+                    .or_else(|| event.get("message"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("upstream stream error");
+                tracing::warn!("responses stream failed: {message}");
+            }
+            _ => {}
+        }
+    }
+}
+
+impl StreamParser for ResponsesStreamParser {
+    fn parse_chunk(&mut self, raw: &str) -> Result<Vec<StreamDelta>> {
+        self.buffer.push_str(raw);
+        let mut deltas = Vec::new();
+
+        while let Some(pos) = self.buffer.find("\n\n") {
+            let block: String = self.buffer.drain(..pos + 2).collect();
+            for line in block.lines() {
+                if let Some(data) = line.strip_prefix("data:")
+                    && let Ok(event) = serde_json::from_str::<Value>(data.trim())
+                {
+                    self.parse_event(&event, &mut deltas);
+                }
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    fn finish(&mut self) -> Result<Vec<StreamDelta>> {
+        let mut deltas = Vec::new();
+        if !self.buffer.trim().is_empty() {
+            let remaining = std::mem::take(&mut self.buffer);
+            deltas.extend(self.parse_chunk(&format!("{remaining}\n\n"))?);
+        }
+        if self.started && !self.done {
+            self.done = true;
+            deltas.push(StreamDelta::Done {
+                stop_reason: "stop".to_string(),
+            });
+        }
+        Ok(deltas)
+    }
+}
+
+fn reasoning_text(item: &Value) -> String {
+    let parts = |key: &str| -> String {
+        item.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let summary = parts("summary");
+    if summary.is_empty() { parts("content") } else { summary }
+}
+
+fn stop_reason(resp: &Value, has_tool_calls: bool) -> String {
+    let incomplete_reason = resp
+        .pointer("/incomplete_details/reason")
+        .and_then(Value::as_str);
+    match (resp.get("status").and_then(Value::as_str), incomplete_reason) {
+        (Some("incomplete"), Some("max_output_tokens")) => "length".to_string(),
+        (Some("incomplete"), Some("content_filter")) => "content_filter".to_string(),
+        _ if has_tool_calls => "tool_calls".to_string(),
+        _ => "stop".to_string(),
+    }
+}
+
+fn extract_usage(usage: Option<&Value>) -> TokenUsage {
+    let count = |key: &str| {
+        usage
+            .and_then(|u| u.get(key))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32
+    };
+    TokenUsage {
+        input_tokens: count("input_tokens"),
+        output_tokens: count("output_tokens"),
+        cache_read_tokens: usage
+            .and_then(|u| u.pointer("/input_tokens_details/cached_tokens"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+        ..Default::default()
+    }
+}
+
+fn str_field(v: &Value, key: &str) -> String {
+    v.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 
+use super::formatter::responses_usage;
 use crate::protocol::types::*;
 use crate::protocol::{SseEvent, StreamFormatter};
 
@@ -13,6 +14,14 @@ struct PendingFunctionCall {
     arguments: String,
 }
 
+/// A withheld-reasoning block, sent as a reasoning item that carries only
+/// `encrypted_content`.
+struct RedactedItem {
+    output_index: usize,
+    item_id: String,
+    data: String,
+}
+
 pub struct ResponsesStreamFormatter {
     resp_id: String,
     msg_id: String,
@@ -25,6 +34,8 @@ pub struct ResponsesStreamFormatter {
     next_output_index: usize,
     reasoning_item_id: Option<String>,
     reasoning_output_index: Option<usize>,
+    reasoning_signature: Option<String>,
+    redacted: Vec<RedactedItem>,
     tool_index_map: HashMap<usize, usize>,
     tool_calls: Vec<PendingFunctionCall>,
 }
@@ -43,6 +54,8 @@ impl ResponsesStreamFormatter {
             next_output_index: 1,
             reasoning_item_id: None,
             reasoning_output_index: None,
+            reasoning_signature: None,
+            redacted: Vec::new(),
             tool_index_map: HashMap::new(),
             tool_calls: Vec::new(),
         }
@@ -125,6 +138,50 @@ impl ResponsesStreamFormatter {
         events
     }
 
+    fn ensure_reasoning_item(&mut self, events: &mut Vec<SseEvent>) {
+        if self.reasoning_item_id.is_some() {
+            return;
+        }
+        let item_id = format!("rs_{}", Uuid::new_v4().simple());
+        let output_index = self.next_output_index;
+        self.next_output_index += 1;
+        self.reasoning_item_id = Some(item_id.clone());
+        self.reasoning_output_index = Some(output_index);
+        let added = serde_json::json!({
+            "type": "response.output_item.added",
+            "output_index": output_index,
+            "item": {
+                "type": "reasoning",
+                "id": item_id,
+                "summary": []
+            }
+        });
+        events.push(SseEvent::new(
+            Some("response.output_item.added"),
+            added.to_string(),
+        ));
+    }
+
+    fn reasoning_item(&self, item_id: &str) -> serde_json::Value {
+        let summary = if self.accumulated_reasoning.is_empty() {
+            serde_json::json!([])
+        } else {
+            serde_json::json!([{
+                "type": "summary_text",
+                "text": self.accumulated_reasoning
+            }])
+        };
+        let mut item = serde_json::json!({
+            "type": "reasoning",
+            "id": item_id,
+            "summary": summary
+        });
+        if let Some(signature) = &self.reasoning_signature {
+            item["encrypted_content"] = serde_json::json!(signature);
+        }
+        item
+    }
+
     fn emit_completed(&mut self) -> Vec<SseEvent> {
         let mut events = Vec::new();
 
@@ -133,14 +190,7 @@ impl ResponsesStreamFormatter {
             let reasoning_done = serde_json::json!({
                 "type": "response.output_item.done",
                 "output_index": output_index,
-                "item": {
-                    "type": "reasoning",
-                    "id": item_id,
-                    "summary": [{
-                        "type": "summary_text",
-                        "text": self.accumulated_reasoning
-                    }]
-                }
+                "item": self.reasoning_item(item_id)
             });
             events.push(SseEvent::new(
                 Some("response.output_item.done"),
@@ -217,15 +267,9 @@ impl ResponsesStreamFormatter {
 
         let mut output: Vec<serde_json::Value> = Vec::new();
         if let Some(item_id) = &self.reasoning_item_id {
-            output.push(serde_json::json!({
-                "type": "reasoning",
-                "id": item_id,
-                "summary": [{
-                    "type": "summary_text",
-                    "text": self.accumulated_reasoning
-                }]
-            }));
+            output.push(self.reasoning_item(item_id));
         }
+        output.extend(self.redacted.iter().map(redacted_item));
         for call in &self.tool_calls {
             output.push(serde_json::json!({
                 "type": "function_call",
@@ -257,11 +301,7 @@ impl ResponsesStreamFormatter {
                 "model": self.model,
                 "output": output,
                 "output_text": self.accumulated_text,
-                "usage": {
-                    "input_tokens": self.usage.input_tokens,
-                    "output_tokens": self.usage.output_tokens,
-                    "total_tokens": self.usage.input_tokens + self.usage.output_tokens
-                }
+                "usage": responses_usage(&self.usage)
             }
         });
         events.push(SseEvent::new(
@@ -273,6 +313,15 @@ impl ResponsesStreamFormatter {
     }
 }
 
+fn redacted_item(redacted: &RedactedItem) -> serde_json::Value {
+    serde_json::json!({
+        "type": "reasoning",
+        "id": redacted.item_id,
+        "summary": [],
+        "encrypted_content": redacted.data
+    })
+}
+
 impl StreamFormatter for ResponsesStreamFormatter {
     fn format_deltas(&mut self, deltas: &[StreamDelta]) -> Vec<SseEvent> {
         let mut events = Vec::new();
@@ -288,26 +337,7 @@ impl StreamFormatter for ResponsesStreamFormatter {
                 }
                 StreamDelta::ReasoningDelta(text) => {
                     self.ensure_started(&mut events);
-                    if self.reasoning_item_id.is_none() {
-                        let item_id = format!("rs_{}", Uuid::new_v4().simple());
-                        let output_index = self.next_output_index;
-                        self.next_output_index += 1;
-                        self.reasoning_item_id = Some(item_id.clone());
-                        self.reasoning_output_index = Some(output_index);
-                        let added = serde_json::json!({
-                            "type": "response.output_item.added",
-                            "output_index": output_index,
-                            "item": {
-                                "type": "reasoning",
-                                "id": item_id,
-                                "summary": []
-                            }
-                        });
-                        events.push(SseEvent::new(
-                            Some("response.output_item.added"),
-                            added.to_string(),
-                        ));
-                    }
+                    self.ensure_reasoning_item(&mut events);
                     self.accumulated_reasoning.push_str(text);
                     let ev = serde_json::json!({
                         "type": "response.reasoning_summary_text.delta",
@@ -321,6 +351,29 @@ impl StreamFormatter for ResponsesStreamFormatter {
                         ev.to_string(),
                     ));
                 }
+                StreamDelta::ReasoningSignature(signature) => {
+                    self.ensure_started(&mut events);
+                    self.ensure_reasoning_item(&mut events);
+                    self.reasoning_signature = Some(signature.clone());
+                }
+                StreamDelta::RedactedReasoning(data) => {
+                    self.ensure_started(&mut events);
+                    let redacted = RedactedItem {
+                        output_index: self.next_output_index,
+                        item_id: format!("rs_{}", Uuid::new_v4().simple()),
+                        data: data.clone(),
+                    };
+                    self.next_output_index += 1;
+                    for kind in ["response.output_item.added", "response.output_item.done"] {
+                        let ev = serde_json::json!({
+                            "type": kind,
+                            "output_index": redacted.output_index,
+                            "item": redacted_item(&redacted)
+                        });
+                        events.push(SseEvent::new(Some(kind), ev.to_string()));
+                    }
+                    self.redacted.push(redacted);
+                }
                 StreamDelta::TextDelta(text) => {
                     self.ensure_started(&mut events);
                     self.accumulated_text.push_str(text);
@@ -69,6 +69,8 @@ impl ResponseParser for OpenAIResponseParser {
             model,
             content,
             reasoning_content,
+            reasoning_signature: None,
+            redacted_reasoning: Vec::new(),
             tool_calls,
             response_items: None,
             stop_reason,
@@ -123,11 +125,7 @@ impl ResponseFormatter for OpenAIResponseFormatter {
                 "message": message,
                 "finish_reason": finish_reason,
             }],
-            "usage": {
-                "prompt_tokens": resp.usage.input_tokens,
-                "completion_tokens": resp.usage.output_tokens,
-                "total_tokens": resp.usage.input_tokens + resp.usage.output_tokens,
-            }
+            "usage": openai_usage(&resp.usage),
         })
     }
 }
@@ -383,6 +381,8 @@ impl StreamFormatter for OpenAIStreamFormatter {
                     });
                     events.push(SseEvent::new(None, chunk.to_string()));
                 }
+                // Signed and withheld reasoning has no form in this dialect.
+                StreamDelta::ReasoningSignature(_) | StreamDelta::RedactedReasoning(_) => {}
                 StreamDelta::TextDelta(text) => {
                     let chunk = serde_json::json!({
                         "id": self.id,
@@ -430,11 +430,7 @@ impl StreamFormatter for OpenAIStreamFormatter {
                         "object": "chat.completion.chunk",
                         "model": self.model,
                         "choices": [{"index": 0, "delta": {}, "finish_reason": final_reason}],
-                        "usage": {
-                            "prompt_tokens": self.usage.input_tokens,
-                            "completion_tokens": self.usage.output_tokens,
-                            "total_tokens": self.usage.input_tokens + self.usage.output_tokens,
-                        }
+                        "usage": openai_usage(&self.usage),
                     });
                     events.push(SseEvent::new(None, chunk.to_string()));
                     events.push(SseEvent::new(None, "[DONE]"));
@@ -479,13 +475,31 @@ fn extract_usage(v: &Value) -> TokenUsage {
         ],
     )
     .unwrap_or(0);
+    // DeepSeek reports cache hits as `prompt_cache_hit_tokens`.
+    let cached = u
+        .pointer("/prompt_tokens_details/cached_tokens")
+        .or_else(|| u.pointer("/input_tokens_details/cached_tokens"))
+        .or_else(|| u.get("prompt_cache_hit_tokens"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
 
     TokenUsage {
         input_tokens: input as u32,
         output_tokens: output as u32,
+        cache_read_tokens: cached as u32,
+        ..Default::default()
     }
 }
 
+fn openai_usage(usage: &TokenUsage) -> Value {
+    serde_json::json!({
+        "prompt_tokens": usage.input_tokens,
+        "completion_tokens": usage.output_tokens,
+        "total_tokens": usage.input_tokens + usage.output_tokens,
+        "prompt_tokens_details": {"cached_tokens": usage.cache_read_tokens},
+    })
+}
+
 fn first_u64(obj: &Value, keys: &[&str]) -> Option<u64> {
     keys.iter()
         .find_map(|k| obj.get(*k).and_then(|v| v.as_u64()))
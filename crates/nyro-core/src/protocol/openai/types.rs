@@ -43,6 +43,18 @@ pub enum OpenAIContentPart {
     Text { text: String },
     #[serde(rename = "image_url")]
     ImageUrl { image_url: ImageUrl },
+    #[serde(rename = "file")]
+    File { file: OpenAIFile },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OpenAIFile {
+    #[serde(default)]
+    pub file_data: Option<String>,
+    #[serde(default)]
+    pub file_id: Option<String>,
+    #[serde(default)]
+    pub filename: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -1,3 +1,5 @@
+pub mod multimodal;
 pub mod reasoning;
 pub mod response_items;
+pub mod token_count;
 pub mod tool_correlation;
@@ -0,0 +1,637 @@
+//! Image and document normalization between ingress and egress.
+//!
+//! Decoders keep an image or document the way it arrived: inline base64 with
+//! a declared media type, or a reference whose media type is
+//! [`URL_MEDIA_TYPE`]. [`normalize_request_media`] runs once the egress is
+//! known. It turns `data:` URIs into inline data, trusts the bytes over the
+//! declared type, and downloads remote media for upstreams that only accept
+//! inline data when a [`MediaFetcher`] is configured; it refuses private
+//! and loopback addresses. Documents the egress
+//! cannot take are replaced by their extracted text when
+//! [`MediaOptions::document_text`] is set, and rejected otherwise.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+use futures::StreamExt;
+
+use crate::config::GatewayConfig;
+use crate::protocol::Protocol;
+use crate::protocol::types::{ContentBlock, ImageSource, InternalRequest, MessageContent};
+
+/// Pseudo media type marking `ImageSource::data` as a URL.
+pub const URL_MEDIA_TYPE: &str = "image/url";
+
+pub const PDF_MEDIA_TYPE: &str = "application/pdf";
+
+/// An image given as a URL or `data:` URI.
+pub fn image_from_url(url: &str) -> ImageSource {
+    parse_data_uri(url).unwrap_or_else(|| ImageSource {
+        media_type: URL_MEDIA_TYPE.to_string(),
+        data: url.to_string(),
+    })
+}
+
+/// A `file_data` field: normally a `data:` URI, though some clients send the
+/// bare base64.
+pub fn file_data_source(file_data: &str) -> ImageSource {
+    if file_data.starts_with("data:") {
+        return image_from_url(file_data);
+    }
+    ImageSource {
+        media_type: sniff_base64(file_data).unwrap_or(PDF_MEDIA_TYPE).to_string(),
+        data: file_data.to_string(),
+    }
+}
+
+/// The remote URL of an image that is not held inline.
+pub fn remote_url(source: &ImageSource) -> Option<&str> {
+    (source.media_type == URL_MEDIA_TYPE && !source.data.starts_with("data:"))
+        .then_some(source.data.as_str())
+}
+
+/// `(media_type, base64)` for an inline image, including one still held as a
+/// `data:` URI.
+pub fn inline_data(source: &ImageSource) -> Option<(&str, &str)> {
+    if source.media_type != URL_MEDIA_TYPE {
+        return Some((source.media_type.as_str(), source.data.as_str()));
+    }
+    let (meta, data) = source.data.strip_prefix("data:")?.split_once(',')?;
+    let media_type = meta.strip_suffix(";base64")?;
+    Some((media_type.split(';').next().unwrap_or(media_type), data))
+}
+
+/// The form OpenAI-style `image_url` fields take: the URL itself, or a
+/// `data:` URI for inline bytes.
+pub fn image_url(source: &ImageSource) -> String {
+    if source.media_type == URL_MEDIA_TYPE {
+        source.data.clone()
+    } else {
+        format!("data:{};base64,{}", source.media_type, source.data)
+    }
+}
+
+fn parse_data_uri(uri: &str) -> Option<ImageSource> {
+    let probe = ImageSource {
+        media_type: URL_MEDIA_TYPE.to_string(),
+        data: uri.to_string(),
+    };
+    let (declared, data) = inline_data(&probe)?;
+    Some(ImageSource {
+        media_type: sniff_base64(data).unwrap_or(declared).to_string(),
+        data: data.to_string(),
+    })
+}
+
+/// Media type from the magic bytes at the start of a base64 payload.
+pub fn sniff_base64(data: &str) -> Option<&'static str> {
+    // 16 base64 characters decode to the 12 bytes the signatures need.
+    let head: String = data.chars().filter(|c| !c.is_whitespace()).take(16).collect();
+    let bytes = STANDARD
+        .decode(&head)
+        .or_else(|_| URL_SAFE.decode(&head))
+        .ok()?;
+    sniff_bytes(&bytes)
+}
+
+pub fn sniff_bytes(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'h', b'e', b'i', b'c' | b'x', ..] => Some("image/heic"),
+        [b'%', b'P', b'D', b'F', ..] => Some("application/pdf"),
+        _ => None,
+    }
+}
+
+/// Media type implied by a URL's file extension.
+pub fn guess_from_url(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "heic" => Some("image/heic"),
+        "pdf" => Some(PDF_MEDIA_TYPE),
+        _ => None,
+    }
+}
+
+/// File extension for a document media type. The set matches the document
+/// formats Bedrock Converse names by extension.
+pub fn document_extension(media_type: &str) -> Option<&'static str> {
+    match media_type {
+        PDF_MEDIA_TYPE => Some("pdf"),
+        "text/plain" => Some("txt"),
+        "text/markdown" => Some("md"),
+        "text/csv" => Some("csv"),
+        "text/html" => Some("html"),
+        "application/msword" => Some("doc"),
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => Some("docx"),
+        "application/vnd.ms-excel" => Some("xls"),
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => Some("xlsx"),
+        _ => None,
+    }
+}
+
+/// The client's file name, or a generic one with the right extension for
+/// upstreams that require a name.
+pub fn document_name(name: Option<&str>, media_type: &str) -> String {
+    match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => format!("document.{}", document_extension(media_type).unwrap_or("bin")),
+    }
+}
+
+/// Redirects followed, each re-checked, before a media download gives up.
+const MAX_MEDIA_REDIRECTS: usize = 5;
+
+/// Downloads remote media for upstreams that cannot reference URLs. Off
+/// unless enabled, since it makes the gateway fetch client-chosen URLs.
+///
+/// Every hop is resolved and checked before it is requested, so a client
+/// cannot point the gateway at loopback, private or link-local hosts (cloud
+/// metadata endpoints included) directly or through a redirect. One fetcher
+/// serves one request and remembers its downloads, so fallback attempts do
+/// not fetch the same media again.
+pub struct MediaFetcher {
+    max_bytes: usize,
+    timeout: Duration,
+    allow_private: bool,
+    fetched: Mutex<HashMap<String, Option<ImageSource>>>,
+}
+
+impl MediaFetcher {
+    pub fn new(max_bytes: usize, timeout: Duration) -> Self {
+        Self {
+            max_bytes,
+            timeout,
+            allow_private: false,
+            fetched: Mutex::default(),
+        }
+    }
+
+    /// Also fetch from non-public addresses, for media hosted on the
+    /// gateway's own network.
+    pub fn allow_private(mut self, allow: bool) -> Self {
+        self.allow_private = allow;
+        self
+    }
+
+    pub fn from_config(config: &GatewayConfig) -> Option<Self> {
+        config.fetch_remote_media.then(|| {
+            Self::new(
+                config.media_fetch_max_bytes as usize,
+                Duration::from_secs(config.media_fetch_timeout_secs),
+            )
+            .allow_private(config.media_fetch_allow_private)
+        })
+    }
+
+    /// Download `url`, or return this request's earlier result for it. A
+    /// failed download is remembered as `None` and not retried.
+    pub async fn fetch(&self, url: &str) -> Option<ImageSource> {
+        if let Some(cached) = self.cache().get(url) {
+            return cached.clone();
+        }
+        let fetched = match self.download(url).await {
+            Ok(source) => Some(source),
+            Err(e) => {
+                tracing::warn!("failed to fetch {url}: {e:#}");
+                None
+            }
+        };
+        self.cache().insert(url.to_string(), fetched.clone());
+        fetched
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, Option<ImageSource>>> {
+        self.fetched.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn download(&self, url: &str) -> Result<ImageSource> {
+        let mut target = reqwest::Url::parse(url).context("invalid media URL")?;
+        let mut redirects = 0;
+        let resp = loop {
+            let resp = self.get(&target).await?;
+            if !resp.status().is_redirection() {
+                break resp.error_for_status()?;
+            }
+            redirects += 1;
+            if redirects > MAX_MEDIA_REDIRECTS {
+                anyhow::bail!("too many redirects");
+            }
+            let location = resp
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .context("redirect without a location")?;
+            target = target.join(location).context("invalid redirect location")?;
+        };
+        if resp.content_length().is_some_and(|len| len > self.max_bytes as u64) {
+            anyhow::bail!("media larger than {} bytes", self.max_bytes);
+        }
+        let declared = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty() && v != "application/octet-stream");
+
+        let mut bytes = Vec::new();
+        let mut body = resp.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            bytes.extend_from_slice(&chunk?);
+            if bytes.len() > self.max_bytes {
+                anyhow::bail!("media larger than {} bytes", self.max_bytes);
+            }
+        }
+
+        let media_type = sniff_bytes(&bytes)
+            .map(str::to_string)
+            .or(declared)
+            .or_else(|| guess_from_url(url).map(str::to_string))
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        Ok(ImageSource {
+            media_type,
+            data: STANDARD.encode(bytes),
+        })
+    }
+
+    /// One hop of a download, without following redirects. The connection is
+    /// pinned to the addresses that were checked, so a second DNS answer
+    /// cannot swap in a private one.
+    async fn get(&self, url: &reqwest::Url) -> Result<reqwest::Response> {
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("unsupported media URL scheme: {}", url.scheme());
+        }
+        let host = url.host_str().context("media URL has no host")?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .with_context(|| format!("cannot resolve {host}"))?
+            .collect();
+        if addrs.is_empty() {
+            anyhow::bail!("cannot resolve {host}");
+        }
+        if !self.allow_private
+            && let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip()))
+        {
+            anyhow::bail!("{host} resolves to non-public address {}", addr.ip());
+        }
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(host, &addrs)
+            .timeout(self.timeout)
+            .build()?;
+        Ok(client.get(url.clone()).send().await?)
+    }
+}
+
+/// Whether `ip` is a routable internet address rather than loopback,
+/// private, link-local, shared (CGNAT) or otherwise reserved.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_unspecified()
+                || v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_unspecified()
+                || v6.is_loopback()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// What [`normalize_request_media`] may do besides reshaping: download
+/// remote media, and extract text from documents the egress cannot take.
+#[derive(Default)]
+pub struct MediaOptions {
+    pub fetcher: Option<MediaFetcher>,
+    pub document_text: bool,
+}
+
+impl MediaOptions {
+    pub fn from_config(config: &GatewayConfig) -> Self {
+        Self {
+            fetcher: MediaFetcher::from_config(config),
+            document_text: config.document_text_fallback,
+        }
+    }
+}
+
+/// Whether `egress` needs the bytes rather than a link to `url`. Chat
+/// Completions takes image links but documents only inline.
+fn needs_inline(egress: Protocol, url: &str, document: bool) -> bool {
+    match egress {
+        Protocol::OpenAI => document,
+        Protocol::ResponsesAPI | Protocol::Anthropic => false,
+        // `fileData` takes Cloud Storage and Files API URIs as they are.
+        Protocol::Gemini => !url.starts_with("gs://") && !url.contains("generativelanguage.googleapis.com/"),
+        Protocol::Ollama | Protocol::OllamaGenerate | Protocol::Bedrock => true,
+    }
+}
+
+/// Whether `egress` can reference an image by URL at all. Ollama and
+/// Bedrock only take inline bytes.
+pub fn image_url_supported(egress: Protocol) -> bool {
+    !matches!(egress, Protocol::Ollama | Protocol::OllamaGenerate | Protocol::Bedrock)
+}
+
+/// Whether `egress` takes `source` as a document block of its own.
+pub fn document_supported(egress: Protocol, source: &ImageSource) -> bool {
+    // `None` is a remote URL, whose type is only known upstream.
+    let media_type = inline_data(source).map(|(media_type, _)| media_type);
+    match egress {
+        Protocol::Anthropic => media_type.is_none_or(|t| t == PDF_MEDIA_TYPE || t == "text/plain"),
+        Protocol::OpenAI => media_type == Some(PDF_MEDIA_TYPE),
+        Protocol::ResponsesAPI => media_type.is_none_or(|t| t == PDF_MEDIA_TYPE),
+        // Gemini reads any file it is handed, audio and video included.
+        Protocol::Gemini => true,
+        Protocol::Bedrock => media_type.and_then(document_extension).is_some(),
+        Protocol::Ollama | Protocol::OllamaGenerate => false,
+    }
+}
+
+/// Bring every image and document in `req` into a form `egress` accepts.
+/// Fails, naming the document, when one can be neither sent nor replaced by
+/// its text, so the caller can try another target or reject the request.
+pub async fn normalize_request_media(
+    req: &mut InternalRequest,
+    egress: Protocol,
+    options: &MediaOptions,
+) -> Result<()> {
+    for msg in &mut req.messages {
+        let MessageContent::Blocks(blocks) = &mut msg.content else {
+            continue;
+        };
+        let mut normalized = Vec::with_capacity(blocks.len());
+        for block in std::mem::take(blocks) {
+            normalized.push(normalize_block(block, egress, options).await?);
+        }
+        *blocks = normalized;
+    }
+    Ok(())
+}
+
+async fn normalize_block(block: ContentBlock, egress: Protocol, options: &MediaOptions) -> Result<ContentBlock> {
+    let fetcher = options.fetcher.as_ref();
+    match block {
+        ContentBlock::Image { mut source } => {
+            normalize_source(&mut source, egress, false, fetcher).await;
+            if let Some(url) = remote_url(&source)
+                && !image_url_supported(egress)
+            {
+                if fetcher.is_some() {
+                    anyhow::bail!("image {url} could not be downloaded for {egress} upstreams");
+                }
+                anyhow::bail!(
+                    "image {url} is a remote URL, which {egress} upstreams cannot take; \
+                     enable --fetch-remote-media to send its bytes instead"
+                );
+            }
+            Ok(ContentBlock::Image { source })
+        }
+        ContentBlock::Document { mut source, name } => {
+            normalize_source(&mut source, egress, true, fetcher).await;
+            if document_supported(egress, &source) {
+                return Ok(ContentBlock::Document { source, name });
+            }
+            let label = name.as_deref().unwrap_or("document");
+            if !options.document_text {
+                let kind = inline_data(&source).map_or("remote file", |(media_type, _)| media_type);
+                anyhow::bail!(
+                    "document {label} ({kind}) is not supported by {egress} upstreams; \
+                     enable --document-text-fallback to send its text instead"
+                );
+            }
+            let text = extract_text(&source)
+                .await
+                .with_context(|| format!("document {label} is not supported by {egress} upstreams"))?;
+            Ok(ContentBlock::Text {
+                text: format!("<document name=\"{label}\">\n{}\n</document>", text.trim()),
+            })
+        }
+        other => Ok(other),
+    }
+}
+
+async fn normalize_source(
+    source: &mut ImageSource,
+    egress: Protocol,
+    document: bool,
+    fetcher: Option<&MediaFetcher>,
+) {
+    if source.media_type == URL_MEDIA_TYPE {
+        if let Some(inline) = parse_data_uri(&source.data) {
+            *source = inline;
+        } else if needs_inline(egress, &source.data, document)
+            && let Some(fetcher) = fetcher
+            && let Some(fetched) = fetcher.fetch(&source.data).await
+        {
+            *source = fetched;
+        }
+        return;
+    }
+    if let Some(sniffed) = sniff_base64(&source.data) {
+        source.media_type = sniffed.to_string();
+    }
+}
+
+/// Plain text of an inline PDF or text document.
+async fn extract_text(source: &ImageSource) -> Result<String> {
+    let (media_type, data) = inline_data(source).context("document was not fetched")?;
+    let bytes = STANDARD.decode(data.trim()).context("invalid base64 document")?;
+    if media_type.starts_with("text/") {
+        return Ok(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    if media_type != PDF_MEDIA_TYPE {
+        anyhow::bail!("cannot extract text from {media_type}");
+    }
+    // The PDF parser panics on some malformed files; a blocking task
+    // contains that as a join error.
+    tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .context("PDF text extraction panicked")?
+        .context("PDF text extraction failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+
+    #[test]
+    fn data_uri_becomes_inline_with_sniffed_type() {
+        let source = image_from_url(&format!("data:image/jpeg;base64,{PNG}"));
+        assert_eq!(source.media_type, "image/png");
+        assert_eq!(source.data, PNG);
+        assert_eq!(image_url(&source), format!("data:image/png;base64,{PNG}"));
+    }
+
+    #[test]
+    fn remote_urls_stay_references() {
+        let source = image_from_url("https://example.com/cat.webp?size=large");
+        assert_eq!(remote_url(&source), Some("https://example.com/cat.webp?size=large"));
+        assert_eq!(inline_data(&source), None);
+        assert_eq!(guess_from_url(&source.data), Some("image/webp"));
+    }
+
+    #[tokio::test]
+    async fn corrects_mislabeled_inline_images() {
+        let mut req = crate::protocol::types::InternalRequest {
+            messages: vec![crate::protocol::types::InternalMessage {
+                role: crate::protocol::types::Role::User,
+                content: MessageContent::Blocks(vec![ContentBlock::Image {
+                    source: ImageSource {
+                        media_type: "image/jpeg".to_string(),
+                        data: PNG.to_string(),
+                    },
+                }]),
+                tool_calls: None,
+                tool_call_id: None,
+                cache_control: None,
+            }],
+            model: "m".to_string(),
+            stream: false,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            tools: None,
+            tool_choice: None,
+            reasoning: None,
+            source_protocol: Protocol::Anthropic,
+            extra: Default::default(),
+        };
+        normalize_request_media(&mut req, Protocol::Gemini, &MediaOptions::default())
+            .await
+            .unwrap();
+        let MessageContent::Blocks(blocks) = &req.messages[0].content else {
+            unreachable!()
+        };
+        assert!(matches!(&blocks[0], ContentBlock::Image { source } if source.media_type == "image/png"));
+    }
+
+    #[tokio::test]
+    async fn unsupported_documents_fall_back_to_text_or_fail() {
+        let document = ContentBlock::Document {
+            source: ImageSource {
+                media_type: "text/plain".to_string(),
+                data: STANDARD.encode("design notes"),
+            },
+            name: Some("notes.txt".to_string()),
+        };
+        let with_text = MediaOptions {
+            fetcher: None,
+            document_text: true,
+        };
+        let block = normalize_block(document.clone(), Protocol::Ollama, &with_text).await;
+        assert!(matches!(
+            block,
+            Ok(ContentBlock::Text { text }) if text == "<document name=\"notes.txt\">\ndesign notes\n</document>"
+        ));
+        let rejected = normalize_block(document.clone(), Protocol::Ollama, &MediaOptions::default())
+            .await
+            .unwrap_err();
+        assert!(rejected.to_string().contains("notes.txt (text/plain)"), "{rejected}");
+        assert!(matches!(
+            normalize_block(document, Protocol::Bedrock, &MediaOptions::default()).await,
+            Ok(ContentBlock::Document { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn unfetched_image_urls_fail_for_inline_only_upstreams() {
+        let image = ContentBlock::Image {
+            source: image_from_url("https://example.com/cat.png"),
+        };
+        for egress in [Protocol::Bedrock, Protocol::Ollama] {
+            let rejected = normalize_block(image.clone(), egress, &MediaOptions::default())
+                .await
+                .unwrap_err();
+            assert!(rejected.to_string().contains("--fetch-remote-media"), "{rejected}");
+        }
+        assert!(matches!(
+            normalize_block(image, Protocol::Anthropic, &MediaOptions::default()).await,
+            Ok(ContentBlock::Image { .. })
+        ));
+    }
+
+    #[test]
+    fn only_public_addresses_are_fetchable() {
+        for private in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(private.parse().unwrap()), "{private}");
+        }
+        for public in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{public}");
+        }
+    }
+
+    #[tokio::test]
+    async fn fetcher_refuses_loopback_unless_allowed() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/pixel.png", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let _ = socket.read(&mut buf).await;
+                let raw = STANDARD.decode(PNG).unwrap();
+                let head = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n", raw.len());
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&raw).await;
+            }
+        });
+
+        let guarded = MediaFetcher::new(1 << 20, Duration::from_secs(2));
+        assert!(guarded.fetch(&url).await.is_none());
+        assert!(guarded.fetch(&url.replace("127.0.0.1", "localhost")).await.is_none());
+
+        let open = MediaFetcher::new(1 << 20, Duration::from_secs(2)).allow_private(true);
+        let fetched = open.fetch(&url).await.expect("private fetch allowed");
+        assert_eq!(fetched.media_type, "image/png");
+        assert_eq!(fetched.data, PNG);
+    }
+}
@@ -1,4 +1,82 @@
-use crate::protocol::types::InternalResponse;
+use serde_json::{Value, json};
+
+use crate::protocol::types::{InternalResponse, ReasoningConfig, ReasoningEffort};
+
+/// Smallest thinking budget Anthropic accepts.
+pub const MIN_THINKING_BUDGET: u32 = 1024;
+
+/// Thinking budget standing in for an effort level.
+pub fn budget_for_effort(effort: ReasoningEffort) -> u32 {
+    match effort {
+        ReasoningEffort::Minimal => MIN_THINKING_BUDGET,
+        ReasoningEffort::Low => 4096,
+        ReasoningEffort::Medium => 16384,
+        ReasoningEffort::High => 32768,
+    }
+}
+
+/// Effort level for a thinking budget, inverse of [`budget_for_effort`].
+/// Never `Minimal`, which only some OpenAI models accept.
+pub fn effort_for_budget(budget: u32) -> ReasoningEffort {
+    match budget {
+        0..=4096 => ReasoningEffort::Low,
+        4097..=16384 => ReasoningEffort::Medium,
+        _ => ReasoningEffort::High,
+    }
+}
+
+/// Budget to ask for, or `None` to leave it to the upstream.
+pub fn thinking_budget(config: &ReasoningConfig) -> Option<u32> {
+    config.budget_tokens.or_else(|| config.effort.map(budget_for_effort))
+}
+
+pub fn reasoning_effort(config: &ReasoningConfig) -> ReasoningEffort {
+    config
+        .effort
+        .or_else(|| config.budget_tokens.map(effort_for_budget))
+        .unwrap_or(ReasoningEffort::Medium)
+}
+
+/// Decode an effort-style knob: OpenAI `reasoning_effort`, Responses
+/// `reasoning.effort` or Ollama `think` (a bool or a level).
+pub fn config_from_effort(value: &Value) -> Option<ReasoningConfig> {
+    let effort = match value {
+        Value::Bool(enabled) => {
+            return Some(ReasoningConfig {
+                enabled: *enabled,
+                ..Default::default()
+            });
+        }
+        Value::String(level) => match level.to_ascii_lowercase().as_str() {
+            "none" => return Some(ReasoningConfig::default()),
+            "minimal" => ReasoningEffort::Minimal,
+            "low" => ReasoningEffort::Low,
+            "medium" => ReasoningEffort::Medium,
+            "high" | "xhigh" => ReasoningEffort::High,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(ReasoningConfig {
+        enabled: true,
+        effort: Some(effort),
+        budget_tokens: None,
+    })
+}
+
+/// Anthropic `thinking` object, shared by Anthropic and Bedrock Claude
+/// egress, and the `max_tokens` to send with it. `max_tokens` counts
+/// thinking too, so a limit at or below the budget is raised by it.
+pub fn anthropic_thinking(config: &ReasoningConfig, max_tokens: u32) -> (Value, u32) {
+    if !config.enabled {
+        return (json!({ "type": "disabled" }), max_tokens);
+    }
+    let budget = thinking_budget(config)
+        .unwrap_or_else(|| budget_for_effort(ReasoningEffort::Medium))
+        .max(MIN_THINKING_BUDGET);
+    let max_tokens = if max_tokens > budget { max_tokens } else { budget + max_tokens };
+    (json!({ "type": "enabled", "budget_tokens": budget }), max_tokens)
+}
 
 pub fn normalize_response_reasoning(resp: &mut InternalResponse) {
     if resp.reasoning_content.is_some() {
@@ -7,9 +7,10 @@ pub fn populate_response_items(resp: &mut InternalResponse) {
 
     let mut items: Vec<ResponseItem> = Vec::new();
 
-    if let Some(reasoning) = resp.reasoning_content.as_ref().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+    let reasoning = resp.reasoning_content.as_ref().map(|v| v.trim()).filter(|v| !v.is_empty());
+    if reasoning.is_some() || resp.reasoning_signature.is_some() {
         items.push(ResponseItem::Reasoning {
-            text: reasoning.to_string(),
+            text: reasoning.unwrap_or_default().to_string(),
         });
     }
 
@@ -0,0 +1,143 @@
+use crate::protocol::types::{ContentBlock, InternalRequest, MessageContent};
+
+/// Fixed per-message framing overhead (role markers, separators).
+const MESSAGE_OVERHEAD: u32 = 3;
+/// Flat charge for an image whose dimensions are unknown (~1.15 MP).
+const IMAGE_TOKENS: u32 = 1_600;
+/// Flat charge for a document whose page count is unknown (about one page of
+/// text plus its rendered image).
+const DOCUMENT_TOKENS: u32 = 3_000;
+
+/// Local token estimation used when no upstream can count for us.
+///
+/// Implementations only need `count_text`; `count_request` walks the
+/// protocol-neutral request and can be overridden for model-aware tokenizers.
+pub trait TokenEstimator: Send + Sync {
+    fn count_text(&self, text: &str) -> u32;
+
+    fn count_request(&self, req: &InternalRequest) -> u32 {
+        let mut total = 0;
+        for msg in &req.messages {
+            total += MESSAGE_OVERHEAD;
+            match &msg.content {
+                MessageContent::Text(text) => total += self.count_text(text),
+                MessageContent::Blocks(blocks) => {
+                    for block in blocks {
+                        total += match block {
+                            ContentBlock::Text { text } => self.count_text(text),
+                            ContentBlock::Image { .. } => IMAGE_TOKENS,
+                            ContentBlock::Document { .. } => DOCUMENT_TOKENS,
+                            ContentBlock::Thinking { thinking, .. } => self.count_text(thinking),
+                            ContentBlock::RedactedThinking { .. } => 0,
+                            ContentBlock::ToolUse { name, input, .. } => {
+                                self.count_text(name) + self.count_text(&input.to_string())
+                            }
+                            ContentBlock::ToolResult { content, .. } => match content.as_str() {
+                                Some(text) => self.count_text(text),
+                                None => self.count_text(&content.to_string()),
+                            },
+                        };
+                    }
+                }
+            }
+            for call in msg.tool_calls.iter().flatten() {
+                total += self.count_text(&call.name) + self.count_text(&call.arguments);
+            }
+        }
+        for tool in req.tools.iter().flatten() {
+            total += self.count_text(&tool.name)
+                + self.count_text(tool.description.as_deref().unwrap_or_default())
+                + self.count_text(&tool.parameters.to_string());
+        }
+        total
+    }
+}
+
+/// Character-class heuristic: ~4 ASCII characters per token, one token per
+/// CJK character and two per token for other non-ASCII text. Good enough for
+/// context budgeting, not for billing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeuristicEstimator;
+
+impl TokenEstimator for HeuristicEstimator {
+    fn count_text(&self, text: &str) -> u32 {
+        let (mut ascii, mut cjk, mut other) = (0u32, 0u32, 0u32);
+        for c in text.chars() {
+            if c.is_ascii() {
+                ascii += 1;
+            } else if is_cjk(c) {
+                cjk += 1;
+            } else {
+                other += 1;
+            }
+        }
+        ascii.div_ceil(4) + cjk + other.div_ceil(2)
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Protocol;
+    use crate::protocol::types::{InternalMessage, Role, ToolDef};
+    use std::collections::HashMap;
+
+    #[test]
+    fn counts_ascii_and_cjk_text() {
+        let est = HeuristicEstimator;
+        assert_eq!(est.count_text(""), 0);
+        assert_eq!(est.count_text("hello world!"), 3);
+        assert_eq!(est.count_text("你好世界"), 4);
+    }
+
+    #[test]
+    fn request_includes_messages_images_and_tools() {
+        let req = InternalRequest {
+            messages: vec![InternalMessage {
+                role: Role::User,
+                content: MessageContent::Blocks(vec![
+                    ContentBlock::Text {
+                        text: "describe this".to_string(),
+                    },
+                    ContentBlock::Image {
+                        source: crate::protocol::types::ImageSource {
+                            media_type: "image/png".to_string(),
+                            data: String::new(),
+                        },
+                    },
+                ]),
+                tool_calls: None,
+                tool_call_id: None,
+                cache_control: None,
+            }],
+            model: "m".to_string(),
+            stream: false,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            tools: Some(vec![ToolDef {
+                name: "lookup".to_string(),
+                description: None,
+                parameters: serde_json::json!({}),
+                cache_control: None,
+            }]),
+            tool_choice: None,
+            reasoning: None,
+            source_protocol: Protocol::Anthropic,
+            extra: HashMap::new(),
+        };
+        // 3 overhead + 4 text + 1600 image + 2 tool name + 1 schema
+        assert_eq!(HeuristicEstimator.count_request(&req), 1610);
+    }
+}
@@ -17,16 +17,50 @@ pub struct InternalRequest {
     pub top_p: Option<f64>,
     pub tools: Option<Vec<ToolDef>>,
     pub tool_choice: Option<Value>,
+    pub reasoning: Option<ReasoningConfig>,
     pub source_protocol: Protocol,
     pub extra: HashMap<String, Value>,
 }
 
+/// Reasoning controls from whichever knob the client used: Anthropic
+/// `thinking`, OpenAI `reasoning_effort`, Responses `reasoning.effort`,
+/// Gemini `thinkingConfig` or Ollama `think`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReasoningConfig {
+    /// `false` when the client explicitly turned reasoning off.
+    pub enabled: bool,
+    pub effort: Option<ReasoningEffort>,
+    pub budget_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReasoningEffort::Minimal => "minimal",
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InternalMessage {
     pub role: Role,
     pub content: MessageContent,
     pub tool_calls: Option<Vec<ToolCall>>,
     pub tool_call_id: Option<String>,
+    /// Anthropic `cache_control` breakpoint closing this message, kept so
+    /// the cached prompt prefix survives the trip to an Anthropic upstream.
+    pub cache_control: Option<Value>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -68,6 +102,24 @@ pub enum ContentBlock {
     Image {
         source: ImageSource,
     },
+    /// A file attachment such as a PDF. `name` is the client's file name or
+    /// title, when it gave one.
+    Document {
+        source: ImageSource,
+        name: Option<String>,
+    },
+    /// Model reasoning from an earlier assistant turn. `signature` is the
+    /// upstream's proof of authenticity (Anthropic `signature`, Responses
+    /// `encrypted_content`) and must go back unchanged.
+    Thinking {
+        thinking: String,
+        signature: Option<String>,
+    },
+    /// Reasoning the upstream withheld, as opaque data (Anthropic
+    /// `redacted_thinking`).
+    RedactedThinking {
+        data: String,
+    },
     ToolUse {
         id: String,
         name: String,
@@ -79,6 +131,7 @@ pub enum ContentBlock {
     },
 }
 
+/// Inline base64 bytes or a URL; shared by images and documents.
 #[derive(Debug, Clone)]
 pub struct ImageSource {
     pub media_type: String,
@@ -89,8 +142,38 @@ pub struct ImageSource {
 
 #[derive(Debug, Clone, Default)]
 pub struct TokenUsage {
+    /// Every prompt token, cached or not. Anthropic and Bedrock report only
+    /// the uncached rest, so their parsers add the cache counts back.
     pub input_tokens: u32,
     pub output_tokens: u32,
+    /// Prompt tokens served from the upstream's prompt cache.
+    pub cache_read_tokens: u32,
+    /// Prompt tokens written to the prompt cache.
+    pub cache_creation_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Prompt tokens that neither hit nor filled the cache; Anthropic's
+    /// `input_tokens`.
+    pub fn uncached_input_tokens(&self) -> u32 {
+        self.input_tokens
+            .saturating_sub(self.cache_read_tokens + self.cache_creation_tokens)
+    }
+
+    /// Fold in a streamed usage update, keeping earlier counts the update
+    /// leaves at zero.
+    pub fn merge(&mut self, update: &TokenUsage) {
+        for (count, new) in [
+            (&mut self.input_tokens, update.input_tokens),
+            (&mut self.output_tokens, update.output_tokens),
+            (&mut self.cache_read_tokens, update.cache_read_tokens),
+            (&mut self.cache_creation_tokens, update.cache_creation_tokens),
+        ] {
+            if new > 0 {
+                *count = new;
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +182,10 @@ pub struct InternalResponse {
     pub model: String,
     pub content: String,
     pub reasoning_content: Option<String>,
+    /// Signature over `reasoning_content`, see [`ContentBlock::Thinking`].
+    pub reasoning_signature: Option<String>,
+    /// Opaque withheld reasoning, see [`ContentBlock::RedactedThinking`].
+    pub redacted_reasoning: Vec<String>,
     pub tool_calls: Vec<ToolCall>,
     pub response_items: Option<Vec<ResponseItem>>,
     pub stop_reason: Option<String>,
@@ -117,6 +204,8 @@ pub struct ToolDef {
     pub name: String,
     pub description: Option<String>,
     pub parameters: Value,
+    /// Anthropic `cache_control` breakpoint after this tool definition.
+    pub cache_control: Option<Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -132,6 +221,29 @@ pub enum ResponseItem {
     Message {
         text: String,
     },
+    /// A Responses output item kept verbatim (reasoning with
+    /// `encrypted_content`, hosted tool calls) from a Responses upstream.
+    Native(Value),
+}
+
+// ── Embeddings ──
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    /// One entry per vector to produce, in order.
+    pub input: Vec<String>,
+    pub dimensions: Option<u32>,
+    pub source_protocol: Protocol,
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingResponse {
+    pub model: String,
+    /// Vectors in the same order as `EmbeddingRequest::input`.
+    pub embeddings: Vec<Vec<f64>>,
+    pub usage: TokenUsage,
 }
 
 // ── Streaming ──
@@ -140,6 +252,10 @@ pub enum ResponseItem {
 pub enum StreamDelta {
     MessageStart { id: String, model: String },
     ReasoningDelta(String),
+    /// Signature closing the current reasoning block.
+    ReasoningSignature(String),
+    /// A complete block of withheld reasoning.
+    RedactedReasoning(String),
     TextDelta(String),
     ToolCallStart { index: usize, id: String, name: String },
     ToolCallDelta { index: usize, arguments: String },
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Snapshot of one provider's breaker, as exposed to the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitStatus {
+    pub provider_id: String,
+    pub provider_name: Option<String>,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub total_successes: u64,
+    pub last_error: Option<String>,
+    /// Seconds until an open breaker lets a probe request through.
+    pub retry_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    total_failures: u64,
+    total_successes: u64,
+    last_error: Option<String>,
+    opened_at: Option<Instant>,
+    probe_started_at: Option<Instant>,
+}
+
+impl Breaker {
+    fn state(&self, cooldown: Duration) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(at) if at.elapsed() < cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+}
+
+/// Passive per-provider circuit breakers fed by proxy attempts.
+///
+/// A breaker opens after `failure_threshold` consecutive upstream failures and
+/// rejects traffic for `cooldown`. After that it half-opens and admits a single
+/// probe: success closes it, failure re-opens it for another cooldown.
+pub struct CircuitBreakers {
+    failure_threshold: u32,
+    cooldown: Duration,
+    breakers: Mutex<HashMap<String, Breaker>>,
+}
+
+impl CircuitBreakers {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a request may be sent to the provider now. In the half-open
+    /// state only one probe is admitted per cooldown window.
+    pub fn allow(&self, provider_id: &str) -> bool {
+        let mut breakers = self.lock();
+        let Some(breaker) = breakers.get_mut(provider_id) else {
+            return true;
+        };
+        match breaker.state(self.cooldown) {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                let probe_in_flight = breaker
+                    .probe_started_at
+                    .is_some_and(|at| at.elapsed() < self.cooldown);
+                if probe_in_flight {
+                    false
+                } else {
+                    breaker.probe_started_at = Some(Instant::now());
+                    true
+                }
+            }
+        }
+    }
+
+    /// Hand back a half-open probe slot taken by `allow` when the request
+    /// never reached the provider, so the next request can probe instead of
+    /// waiting out another cooldown.
+    pub fn release_probe(&self, provider_id: &str) {
+        if let Some(breaker) = self.lock().get_mut(provider_id) {
+            breaker.probe_started_at = None;
+        }
+    }
+
+    pub fn record_success(&self, provider_id: &str) {
+        let mut breakers = self.lock();
+        let breaker = breakers.entry(provider_id.to_string()).or_default();
+        if breaker.opened_at.is_some() {
+            tracing::info!("circuit closed for provider {provider_id}");
+        }
+        breaker.consecutive_failures = 0;
+        breaker.total_successes += 1;
+        breaker.opened_at = None;
+        breaker.probe_started_at = None;
+    }
+
+    pub fn record_failure(&self, provider_id: &str, error: &str) {
+        let mut breakers = self.lock();
+        let breaker = breakers.entry(provider_id.to_string()).or_default();
+        breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+        breaker.total_failures += 1;
+        breaker.last_error = Some(error.chars().take(300).collect());
+
+        let failed_probe = breaker.state(self.cooldown) == CircuitState::HalfOpen;
+        if failed_probe || breaker.consecutive_failures >= self.failure_threshold {
+            if breaker.state(self.cooldown) != CircuitState::Open {
+                tracing::warn!(
+                    "circuit opened for provider {provider_id} after {} consecutive failures",
+                    breaker.consecutive_failures
+                );
+            }
+            breaker.opened_at = Some(Instant::now());
+            breaker.probe_started_at = None;
+        }
+    }
+
+    /// Force a provider's breaker back to closed, e.g. after fixing its config.
+    pub fn reset(&self, provider_id: &str) {
+        self.lock().remove(provider_id);
+    }
+
+    pub fn state(&self, provider_id: &str) -> CircuitState {
+        self.lock()
+            .get(provider_id)
+            .map(|b| b.state(self.cooldown))
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    pub fn snapshot(&self) -> Vec<CircuitStatus> {
+        let breakers = self.lock();
+        let mut out: Vec<CircuitStatus> = breakers
+            .iter()
+            .map(|(id, b)| CircuitStatus {
+                provider_id: id.clone(),
+                provider_name: None,
+                state: b.state(self.cooldown),
+                consecutive_failures: b.consecutive_failures,
+                total_failures: b.total_failures,
+                total_successes: b.total_successes,
+                last_error: b.last_error.clone(),
+                retry_in_secs: b
+                    .opened_at
+                    .map(|at| self.cooldown.saturating_sub(at.elapsed()).as_secs())
+                    .filter(|secs| *secs > 0),
+            })
+            .collect();
+        out.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
+        out
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Breaker>> {
+        self.breakers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_half_opens_after_cooldown() {
+        let breakers = CircuitBreakers::new(2, Duration::from_millis(50));
+        assert!(breakers.allow("p"));
+        breakers.record_failure("p", "boom");
+        assert_eq!(breakers.state("p"), CircuitState::Closed);
+        breakers.record_failure("p", "boom");
+        assert_eq!(breakers.state("p"), CircuitState::Open);
+        assert!(!breakers.allow("p"));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(breakers.state("p"), CircuitState::HalfOpen);
+        assert!(breakers.allow("p"));
+        assert!(!breakers.allow("p"), "only one half-open probe at a time");
+        breakers.release_probe("p");
+        assert!(breakers.allow("p"), "a released probe slot can be retaken");
+
+        breakers.record_success("p");
+        assert_eq!(breakers.state("p"), CircuitState::Closed);
+        assert!(breakers.allow("p"));
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let breakers = CircuitBreakers::new(1, Duration::from_millis(30));
+        breakers.record_failure("p", "down");
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(breakers.allow("p"));
+        breakers.record_failure("p", "still down");
+        assert_eq!(breakers.state("p"), CircuitState::Open);
+        assert_eq!(breakers.snapshot()[0].last_error.as_deref(), Some("still down"));
+    }
+
+    #[test]
+    fn success_resets_failure_streak() {
+        let breakers = CircuitBreakers::new(2, Duration::from_secs(30));
+        breakers.record_failure("p", "x");
+        breakers.record_success("p");
+        breakers.record_failure("p", "x");
+        assert_eq!(breakers.state("p"), CircuitState::Closed);
+    }
+}
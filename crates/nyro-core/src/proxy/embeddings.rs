@@ -0,0 +1,219 @@
+use std::time::Instant;
+
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::Value;
+
+use crate::protocol::types::{EmbeddingRequest, EmbeddingResponse, TokenUsage};
+use crate::protocol::Protocol;
+use crate::proxy::client::{is_vertex_provider, ProxyClient, UpstreamFlavor};
+use crate::proxy::handler::{
+    authorize_route_access, check_key_quota, emit_log, get_provider, ingress_error_response,
+    is_ollama_provider, is_retryable_status, rate_limited_response,
+    record_circuit_status, too_many_requests, total_tokens, with_rate_limit_headers,
+};
+use crate::proxy::retry::RetryPolicy;
+use crate::Gateway;
+
+/// Route an embedding request through the same route matching, access control,
+/// quotas, provider limits and fallback as chat traffic. `format` renders the
+/// result in the ingress protocol's response shape. Upstreams that report no
+/// usage are charged a local estimate so token quotas still apply.
+pub(crate) async fn embeddings_pipeline(
+    gw: Gateway,
+    headers: HeaderMap,
+    req: EmbeddingRequest,
+    ingress: Protocol,
+    format: impl FnOnce(&EmbeddingResponse) -> Value,
+) -> Response {
+    let start = Instant::now();
+    let request_model = req.model.clone();
+    let ingress_str = ingress.to_string();
+
+    let matched = {
+        let cache = gw.route_cache.read().await;
+        cache
+            .match_route(ingress.route_protocol(), &request_model)
+            .map(|r| (r.clone(), cache.plan_attempts(r, &request_model, &gw.latency_tracker)))
+    };
+    let Some((route, targets)) = matched else {
+        return ingress_error_response(ingress, 404, &format!("no route for model: {request_model}"));
+    };
+
+    let auth_key = match authorize_route_access(&gw, &route, &headers, ingress).await {
+        Ok(v) => v,
+        Err(resp) => return resp,
+    };
+    let rate_limit = match check_key_quota(&gw, &auth_key) {
+        Ok(v) => v,
+        Err(limited) => return rate_limited_response(ingress, &limited),
+    };
+    let api_key_id = auth_key.id.as_deref();
+
+    let client = ProxyClient::new(gw.http_client.clone(), gw.vertex_tokens.clone());
+
+    let mut last_failure = None;
+    for (provider_id, actual_model) in &targets {
+        if !gw.circuit_breakers.allow(provider_id) {
+            last_failure = Some(ingress_error_response(
+                ingress,
+                503,
+                &format!("provider {provider_id} temporarily unavailable (circuit open)"),
+            ));
+            continue;
+        }
+
+        let provider = match get_provider(&gw, provider_id).await {
+            Ok(p) => p,
+            Err(e) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                last_failure = Some(ingress_error_response(ingress, 502, &format!("provider error: {e}")));
+                continue;
+            }
+        };
+
+        // Vertex serves embeddings through `:predict` with `instances`, which
+        // none of the embedding encoders speak; skip to the next target.
+        if is_vertex_provider(&provider) {
+            gw.circuit_breakers.release_probe(provider_id);
+            last_failure = Some(ingress_error_response(
+                ingress,
+                400,
+                &format!("embeddings not supported on Vertex AI provider {}", provider.name),
+            ));
+            continue;
+        }
+
+        // Ollama behind the OpenAI shim still embeds through the native API.
+        let egress = if is_ollama_provider(&provider) {
+            Protocol::Ollama
+        } else {
+            provider.protocol.parse().unwrap_or(Protocol::OpenAI)
+        };
+        let Some(encoder) = crate::protocol::get_embedding_encoder(egress) else {
+            gw.circuit_breakers.release_probe(provider_id);
+            last_failure = Some(ingress_error_response(
+                ingress,
+                400,
+                &format!("provider {} does not support embeddings", provider.name),
+            ));
+            continue;
+        };
+
+        let _permit = match gw.provider_limiter.acquire(&provider).await {
+            Ok(p) => p,
+            Err(rejected) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                last_failure = Some(too_many_requests(
+                    ingress,
+                    &rejected.message,
+                    rejected.retry_after_secs,
+                ));
+                continue;
+            }
+        };
+
+        let mut attempt_req = req.clone();
+        attempt_req.model = actual_model.clone();
+        let body = match encoder.encode_embedding_request(&attempt_req) {
+            Ok(b) => b,
+            Err(e) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                last_failure = Some(ingress_error_response(ingress, 500, &format!("encode error: {e}")));
+                continue;
+            }
+        };
+        let egress_str = egress.to_string();
+        let attempt_start = Instant::now();
+
+        let result = client
+            .clone()
+            .with_retry(RetryPolicy::for_provider(&provider))
+            .with_flavor(UpstreamFlavor::for_target(&provider, actual_model))
+            .call_non_stream(
+                &provider.base_url,
+                &encoder.embedding_path(actual_model),
+                &provider.api_key,
+                egress,
+                body,
+                reqwest::header::HeaderMap::new(),
+            )
+            .await;
+
+        let (resp, status) = match result {
+            Ok(r) => r,
+            Err(e) => {
+                record_circuit_status(&gw, &provider.id, StatusCode::BAD_GATEWAY);
+                gw.latency_tracker.record_failure(
+                    &provider.id,
+                    actual_model,
+                    attempt_start.elapsed().as_millis() as f64,
+                );
+                emit_log(
+                    &gw, &ingress_str, &egress_str, &request_model, actual_model,
+                    api_key_id,
+                    &provider.name, 502, start.elapsed().as_millis() as f64,
+                    TokenUsage::default(), false, false,
+                    Some(e.to_string()), None, None,
+                );
+                last_failure = Some(ingress_error_response(ingress, 502, &format!("upstream error: {e}")));
+                continue;
+            }
+        };
+        let status_code = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+        record_circuit_status(&gw, &provider.id, status_code);
+
+        if status_code.is_server_error() {
+            gw.latency_tracker.record_failure(
+                &provider.id,
+                actual_model,
+                attempt_start.elapsed().as_millis() as f64,
+            );
+        }
+
+        if status >= 400 {
+            let preview = serde_json::to_string(&resp).ok().map(|s| s.chars().take(500).collect());
+            emit_log(
+                &gw, &ingress_str, &egress_str, &request_model, actual_model,
+                api_key_id,
+                &provider.name, status as i32, start.elapsed().as_millis() as f64,
+                TokenUsage::default(), false, false,
+                preview, None, None,
+            );
+            let resp = (status_code, Json(resp)).into_response();
+            if is_retryable_status(status) {
+                last_failure = Some(resp);
+                continue;
+            }
+            return with_rate_limit_headers(resp, &rate_limit);
+        }
+
+        let mut parsed = match encoder.parse_embedding_response(resp) {
+            Ok(r) => r,
+            Err(e) => return ingress_error_response(ingress, 500, &format!("parse error: {e}")),
+        };
+        if parsed.usage.input_tokens == 0 {
+            parsed.usage.input_tokens =
+                req.input.iter().map(|text| gw.token_estimator.count_text(text)).sum();
+        }
+        gw.latency_tracker.record(
+            &provider.id,
+            actual_model,
+            attempt_start.elapsed().as_millis() as f64,
+        );
+        gw.provider_limiter.record_tokens(&provider.id, total_tokens(&parsed.usage));
+        emit_log(
+            &gw, &ingress_str, &egress_str, &request_model, actual_model,
+            api_key_id,
+            &provider.name, status as i32, start.elapsed().as_millis() as f64,
+            parsed.usage.clone(), false, false, None, None, None,
+        );
+
+        return with_rate_limit_headers(Json(format(&parsed)).into_response(), &rate_limit);
+    }
+
+    let resp = last_failure
+        .unwrap_or_else(|| ingress_error_response(ingress, 502, "no upstream target available"));
+    with_rate_limit_headers(resp, &rate_limit)
+}
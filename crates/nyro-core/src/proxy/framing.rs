@@ -0,0 +1,256 @@
+use serde_json::{Value, json};
+
+use crate::protocol::Protocol;
+
+/// How an upstream stream is cut into text records for its `StreamParser`.
+pub enum UpstreamFramer {
+    Lines(LineFramer),
+    EventStream(EventStreamFramer),
+}
+
+impl UpstreamFramer {
+    pub fn for_protocol(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::Bedrock => Self::EventStream(EventStreamFramer::default()),
+            _ => Self::Lines(LineFramer::default()),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) -> Option<String> {
+        match self {
+            Self::Lines(f) => f.push(bytes),
+            Self::EventStream(f) => f.push(bytes),
+        }
+    }
+
+    pub fn finish(&mut self) -> Option<String> {
+        match self {
+            Self::Lines(f) => f.finish(),
+            // A truncated binary frame cannot be decoded.
+            Self::EventStream(_) => None,
+        }
+    }
+}
+
+/// Re-frames an upstream byte stream on line boundaries before it reaches a
+/// `StreamParser`.
+///
+/// SSE events and NDJSON records both end with a newline, so releasing only
+/// complete lines means parsers never see a record — or a multi-byte UTF-8
+/// sequence — split across network chunks, whatever the upstream framing.
+#[derive(Debug, Default)]
+pub struct LineFramer {
+    pending: Vec<u8>,
+}
+
+impl LineFramer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer `bytes` and return every complete line received so far.
+    pub fn push(&mut self, bytes: &[u8]) -> Option<String> {
+        self.pending.extend_from_slice(bytes);
+        let end = self.pending.iter().rposition(|b| *b == b'\n')? + 1;
+        let lines: Vec<u8> = self.pending.drain(..end).collect();
+        Some(String::from_utf8_lossy(&lines).into_owned())
+    }
+
+    /// Flush a trailing record the upstream did not newline-terminate.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let rest = std::mem::take(&mut self.pending);
+        Some(String::from_utf8_lossy(&rest).into_owned())
+    }
+}
+
+/// Decodes AWS `application/vnd.amazon.eventstream` frames into one JSON
+/// line per message: `{"event": <:event-type>, "payload": {...}}`, or
+/// `{"exception": <:exception-type>, "payload": {...}}` for error messages.
+///
+/// Frame layout: total length (u32 BE), headers length (u32 BE), prelude
+/// CRC32, headers, payload, message CRC32.
+#[derive(Debug, Default)]
+pub struct EventStreamFramer {
+    pending: Vec<u8>,
+}
+
+impl EventStreamFramer {
+    pub fn push(&mut self, bytes: &[u8]) -> Option<String> {
+        self.pending.extend_from_slice(bytes);
+        let mut out = String::new();
+        while self.pending.len() >= 12 {
+            let total = be_u32(&self.pending[0..4]) as usize;
+            if total < 16 {
+                tracing::warn!("invalid event-stream frame length {total}; dropping stream data");
+                self.pending.clear();
+                break;
+            }
+            if self.pending.len() < total {
+                break;
+            }
+            let frame: Vec<u8> = self.pending.drain(..total).collect();
+            match decode_frame(&frame) {
+                Ok(Some(record)) => {
+                    out.push_str(&record.to_string());
+                    out.push('\n');
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("skipping event-stream frame: {e}"),
+            }
+        }
+        (!out.is_empty()).then_some(out)
+    }
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn decode_frame(frame: &[u8]) -> anyhow::Result<Option<Value>> {
+    let total = frame.len();
+    let headers_len = be_u32(&frame[4..8]) as usize;
+    if be_u32(&frame[8..12]) != crc32fast::hash(&frame[..8]) {
+        anyhow::bail!("prelude checksum mismatch");
+    }
+    if be_u32(&frame[total - 4..]) != crc32fast::hash(&frame[..total - 4]) {
+        anyhow::bail!("message checksum mismatch");
+    }
+    if 12 + headers_len > total - 4 {
+        anyhow::bail!("headers overrun frame");
+    }
+
+    let headers = parse_headers(&frame[12..12 + headers_len])?;
+    let payload_bytes = &frame[12 + headers_len..total - 4];
+    let payload: Value = if payload_bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(payload_bytes)
+            .unwrap_or_else(|_| json!({ "message": String::from_utf8_lossy(payload_bytes) }))
+    };
+
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    };
+    Ok(match header(":message-type") {
+        Some("event") | None => header(":event-type")
+            .map(|event| json!({ "event": event, "payload": payload })),
+        Some(_) => {
+            let kind = header(":exception-type")
+                .or_else(|| header(":error-code"))
+                .unwrap_or("error");
+            Some(json!({ "exception": kind, "payload": payload }))
+        }
+    })
+}
+
+/// String-valued headers only; other value types are skipped over.
+fn parse_headers(mut b: &[u8]) -> anyhow::Result<Vec<(String, String)>> {
+    let mut out = Vec::new();
+    let take = |b: &mut &[u8], n: usize| -> anyhow::Result<Vec<u8>> {
+        if b.len() < n {
+            anyhow::bail!("truncated header");
+        }
+        let (head, rest) = b.split_at(n);
+        *b = rest;
+        Ok(head.to_vec())
+    };
+    while !b.is_empty() {
+        let name_len = take(&mut b, 1)?[0] as usize;
+        let name = String::from_utf8_lossy(&take(&mut b, name_len)?).into_owned();
+        let value_type = take(&mut b, 1)?[0];
+        let value_len = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let len = take(&mut b, 2)?;
+                u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            other => anyhow::bail!("unknown header value type {other}"),
+        };
+        let value = take(&mut b, value_len)?;
+        if value_type == 7 {
+            out.push((name, String::from_utf8_lossy(&value).into_owned()));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn releases_whole_lines_and_keeps_utf8_intact() {
+        let mut framer = LineFramer::new();
+        let text = "{\"content\":\"你好\"}\n{\"done\":true}";
+        let bytes = text.as_bytes();
+        // Split inside the first CJK character.
+        let split = text.find('你').unwrap() + 1;
+
+        assert_eq!(framer.push(&bytes[..split]), None);
+        assert_eq!(
+            framer.push(&bytes[split..]).as_deref(),
+            Some("{\"content\":\"你好\"}\n")
+        );
+        assert_eq!(framer.finish().as_deref(), Some("{\"done\":true}"));
+        assert_eq!(framer.finish(), None);
+    }
+
+    fn frame(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut h = Vec::new();
+        for (name, value) in headers {
+            h.push(name.len() as u8);
+            h.extend_from_slice(name.as_bytes());
+            h.push(7);
+            h.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            h.extend_from_slice(value.as_bytes());
+        }
+        let total = 12 + h.len() + payload.len() + 4;
+        let mut out = Vec::new();
+        out.extend_from_slice(&(total as u32).to_be_bytes());
+        out.extend_from_slice(&(h.len() as u32).to_be_bytes());
+        let prelude_crc = crc32fast::hash(&out);
+        out.extend_from_slice(&prelude_crc.to_be_bytes());
+        out.extend_from_slice(&h);
+        out.extend_from_slice(payload);
+        let crc = crc32fast::hash(&out);
+        out.extend_from_slice(&crc.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn decodes_event_stream_frames_across_chunks() {
+        let mut bytes = frame(
+            &[(":message-type", "event"), (":event-type", "contentBlockDelta")],
+            br#"{"contentBlockIndex":0,"delta":{"text":"hi"}}"#,
+        );
+        bytes.extend(frame(
+            &[(":message-type", "exception"), (":exception-type", "throttlingException")],
+            br#"{"message":"slow down"}"#,
+        ));
+
+        let mut framer = EventStreamFramer::default();
+        assert_eq!(framer.push(&bytes[..10]), None);
+        let out = framer.push(&bytes[10..]).unwrap();
+        let records: Vec<Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records[0]["event"], "contentBlockDelta");
+        assert_eq!(records[0]["payload"]["delta"]["text"], "hi");
+        assert_eq!(records[1]["exception"], "throttlingException");
+
+        // A corrupted frame is skipped rather than misparsed.
+        let mut bad = frame(&[(":event-type", "messageStop")], b"{}");
+        let last = bad.len() - 1;
+        bad[last] ^= 0xff;
+        assert_eq!(framer.push(&bad), None);
+    }
+}
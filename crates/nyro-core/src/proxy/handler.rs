@@ -14,9 +14,18 @@ use tokio_stream::wrappers::ReceiverStream;
 use crate::db::models::{Provider, Route};
 use crate::logging::LogEntry;
 use crate::protocol::gemini::decoder::GeminiDecoder;
+use crate::protocol::gemini::embeddings as gemini_embeddings;
+use crate::protocol::openai::embeddings as openai_embeddings;
+use crate::protocol::semantic::multimodal::{MediaOptions, normalize_request_media};
 use crate::protocol::types::*;
-use crate::protocol::Protocol;
-use crate::proxy::client::ProxyClient;
+use crate::protocol::{Protocol, SseEvent};
+use crate::proxy::client::{ProxyClient, UpstreamFlavor, ollama_native_base};
+use crate::proxy::embeddings::embeddings_pipeline;
+use crate::proxy::framing::UpstreamFramer;
+use crate::proxy::provider_limit::ProviderPermit;
+use crate::proxy::responses::ResponseRecorder;
+use crate::proxy::retry::RetryPolicy;
+use crate::proxy::rate_limit::{KeyLimits, RateLimitInfo, RateLimited};
 use crate::Gateway;
 
 const OLLAMA_CAPABILITY_CACHE_TTL_SECS: u64 = 3600;
@@ -51,6 +60,202 @@ pub async fn anthropic_proxy(
     universal_proxy(gw, headers, body, Protocol::Anthropic).await
 }
 
+// ── Ollama ingress: POST /api/chat, POST /api/generate ──
+
+pub async fn ollama_chat(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    universal_proxy(gw, headers, body, Protocol::Ollama).await
+}
+
+pub async fn ollama_generate(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    universal_proxy(gw, headers, body, Protocol::OllamaGenerate).await
+}
+
+// ── OpenAI embeddings: POST /v1/embeddings ──
+
+pub async fn openai_embeddings(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    let ingress = Protocol::OpenAI;
+    let base64 = body.get("encoding_format").and_then(Value::as_str) == Some("base64");
+    let req = match openai_embeddings::decode_embedding_request(body) {
+        Ok(r) => r,
+        Err(e) => return ingress_error_response(ingress, 400, &format!("invalid request: {e}")),
+    };
+    let model = req.model.clone();
+    embeddings_pipeline(gw, headers, req, ingress, move |resp| {
+        openai_embeddings::format_embedding_response(resp, &model, base64)
+    })
+    .await
+}
+
+// ── Anthropic token counting: POST /v1/messages/count_tokens ──
+
+pub async fn anthropic_count_tokens(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(mut body): Json<Value>,
+) -> Response {
+    let ingress = Protocol::Anthropic;
+    let request_model = body
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let native = match count_tokens_target(&gw, &headers, ingress, &request_model).await {
+        Ok(v) => v,
+        Err(resp) => return resp,
+    };
+
+    if let Some((provider, actual_model)) = native {
+        let mut extra_headers = reqwest::header::HeaderMap::new();
+        if let Some(beta) = headers.get("anthropic-beta") {
+            extra_headers.insert("anthropic-beta", beta.clone());
+        }
+        let upstream_body = override_model(body.clone(), &actual_model, ingress);
+        if let Some(resp) = forward_count_tokens(
+            &gw,
+            &provider,
+            &actual_model,
+            "/v1/messages/count_tokens",
+            upstream_body,
+            extra_headers,
+        )
+        .await
+        {
+            return resp;
+        }
+    }
+
+    // `max_tokens` is required by the messages schema but not by count_tokens.
+    if let Some(obj) = body.as_object_mut() {
+        obj.entry("max_tokens").or_insert(Value::from(1));
+    }
+    let internal = match crate::protocol::get_decoder(ingress).decode_request(body) {
+        Ok(r) => r,
+        Err(e) => return ingress_error_response(ingress, 400, &format!("invalid request: {e}")),
+    };
+    let input_tokens = gw.token_estimator.count_request(&internal);
+    Json(serde_json::json!({ "input_tokens": input_tokens })).into_response()
+}
+
+// ── Gemini token counting: POST /v1beta/models/{model}:countTokens ──
+
+async fn gemini_count_tokens(gw: Gateway, headers: HeaderMap, model: &str, body: Value) -> Response {
+    let ingress = Protocol::Gemini;
+    let native = match count_tokens_target(&gw, &headers, ingress, model).await {
+        Ok(v) => v,
+        Err(resp) => return resp,
+    };
+
+    if let Some((provider, actual_model)) = native {
+        let mut upstream_body = body.clone();
+        if let Some(inner) = upstream_body
+            .get_mut("generateContentRequest")
+            .and_then(Value::as_object_mut)
+        {
+            inner.insert("model".into(), Value::String(format!("models/{actual_model}")));
+        }
+        let path = format!("/v1beta/models/{actual_model}:countTokens");
+        if let Some(resp) = forward_count_tokens(
+            &gw,
+            &provider,
+            &actual_model,
+            &path,
+            upstream_body,
+            reqwest::header::HeaderMap::new(),
+        )
+        .await
+        {
+            return resp;
+        }
+    }
+
+    // countTokens accepts either bare `contents` or a full `generateContentRequest`.
+    let request = match body.get("generateContentRequest") {
+        Some(inner) => inner.clone(),
+        None => body,
+    };
+    let internal = match GeminiDecoder.decode_with_model(request, model, false) {
+        Ok(r) => r,
+        Err(e) => return ingress_error_response(ingress, 400, &format!("invalid Gemini request: {e}")),
+    };
+    let total_tokens = gw.token_estimator.count_request(&internal);
+    Json(serde_json::json!({ "totalTokens": total_tokens })).into_response()
+}
+
+/// Resolve and authorize the route for a token-count call. Returns the
+/// route's primary target when its provider speaks the ingress protocol
+/// natively, so the count can be delegated upstream. Counting is not a
+/// generation, so it neither consumes the key's quota nor advances the
+/// route's balancing.
+async fn count_tokens_target(
+    gw: &Gateway,
+    headers: &HeaderMap,
+    ingress: Protocol,
+    request_model: &str,
+) -> Result<Option<(Provider, String)>, Response> {
+    let matched = {
+        let cache = gw.route_cache.read().await;
+        cache
+            .match_route(ingress.route_protocol(), request_model)
+            .map(|r| (r.clone(), cache.primary_target(r, request_model)))
+    };
+    let Some((route, (provider_id, actual_model))) = matched else {
+        return Err(ingress_error_response(ingress, 404, &format!("no route for model: {request_model}")));
+    };
+    authorize_route_access(gw, &route, headers, ingress).await?;
+
+    Ok(get_provider(gw, &provider_id)
+        .await
+        .ok()
+        .filter(|p| p.protocol.parse::<Protocol>().ok() == Some(ingress))
+        .map(|p| (p, actual_model)))
+}
+
+/// Pass a token-count call through to a native upstream. Returns `None` when
+/// the upstream cannot count (a flavored provider, unreachable, or the
+/// endpoint is missing on a compatible service) so the caller can estimate
+/// locally.
+async fn forward_count_tokens(
+    gw: &Gateway,
+    provider: &Provider,
+    model: &str,
+    path: &str,
+    body: Value,
+    extra_headers: reqwest::header::HeaderMap,
+) -> Option<Response> {
+    let egress = provider.protocol.parse().unwrap_or(Protocol::OpenAI);
+    let client = ProxyClient::new(gw.http_client.clone(), gw.vertex_tokens.clone())
+        .for_count_tokens(provider, model)?;
+    match client
+        .call_non_stream(&provider.base_url, path, &provider.api_key, egress, body, extra_headers)
+        .await
+    {
+        Ok((_, 404 | 405 | 501)) => None,
+        Ok((json, status)) => Some(
+            (
+                StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY),
+                Json(json),
+            )
+                .into_response(),
+        ),
+        Err(e) => {
+            tracing::warn!("count tokens upstream {} failed, estimating locally: {e}", provider.name);
+            None
+        }
+    }
+}
+
 // ── Gemini ingress: POST /v1beta/models/:model_action ──
 
 pub async fn gemini_proxy(
@@ -63,27 +268,91 @@ pub async fn gemini_proxy(
         Some((m, a)) => (m.to_string(), a.to_string()),
         None => (model_action.clone(), "generateContent".to_string()),
     };
-    let is_stream = action == "streamGenerateContent";
-
-    let decoder = GeminiDecoder;
-    let internal = match decoder.decode_with_model(body, &model, is_stream) {
-        Ok(r) => r,
-        Err(e) => return error_response(400, &format!("invalid Gemini request: {e}")),
-    };
-
-    proxy_pipeline(gw, headers, internal, Protocol::Gemini).await
+    let ingress = Protocol::Gemini;
+
+    match action.as_str() {
+        "generateContent" | "streamGenerateContent" => {
+            let is_stream = action == "streamGenerateContent";
+            let internal = match GeminiDecoder.decode_with_model(body, &model, is_stream) {
+                Ok(r) => r,
+                Err(e) => return error_response(400, &format!("invalid Gemini request: {e}")),
+            };
+            proxy_pipeline(gw, headers, internal, ingress, None).await
+        }
+        "countTokens" => gemini_count_tokens(gw, headers, &model, body).await,
+        "embedContent" => match gemini_embeddings::decode_embed_content(&body, &model) {
+            Ok(req) => {
+                embeddings_pipeline(gw, headers, req, ingress, gemini_embeddings::format_embed_content)
+                    .await
+            }
+            Err(e) => ingress_error_response(ingress, 400, &format!("invalid Gemini request: {e}")),
+        },
+        "batchEmbedContents" => match gemini_embeddings::decode_batch_embed_contents(&body, &model) {
+            Ok(req) => {
+                embeddings_pipeline(
+                    gw,
+                    headers,
+                    req,
+                    ingress,
+                    gemini_embeddings::format_batch_embed_contents,
+                )
+                .await
+            }
+            Err(e) => ingress_error_response(ingress, 400, &format!("invalid Gemini request: {e}")),
+        },
+        other => ingress_error_response(ingress, 400, &format!("unsupported Gemini action: {other}")),
+    }
 }
 
 // ── Universal proxy pipeline ──
 
-async fn universal_proxy(gw: Gateway, headers: HeaderMap, body: Value, ingress: Protocol) -> Response {
+async fn universal_proxy(gw: Gateway, headers: HeaderMap, mut body: Value, ingress: Protocol) -> Response {
+    let recorder = if ingress == Protocol::ResponsesAPI {
+        let api_key_id = match responses_key_id(&gw, &headers, &body, ingress).await {
+            Ok(id) => id,
+            Err(resp) => return resp,
+        };
+        match ResponseRecorder::prepare(&gw.db, api_key_id, &mut body).await {
+            Ok(r) => r,
+            Err(resp) => return resp,
+        }
+    } else {
+        None
+    };
+
     let decoder = crate::protocol::get_decoder(ingress);
     let internal = match decoder.decode_request(body) {
         Ok(r) => r,
         Err(e) => return error_response(400, &format!("invalid request: {e}")),
     };
 
-    proxy_pipeline(gw, headers, internal, ingress).await
+    proxy_pipeline(gw, headers, internal, ingress, recorder).await
+}
+
+/// Key that owns stored responses for a `/v1/responses` request, decided by
+/// the matched route before any history is loaded: the admitted key on
+/// access-controlled routes, or the caller's Nyro key (if any) on open ones.
+/// Without a matching route the pipeline answers 404 later.
+async fn responses_key_id(
+    gw: &Gateway,
+    headers: &HeaderMap,
+    body: &Value,
+    ingress: Protocol,
+) -> Result<Option<String>, Response> {
+    let model = body.get("model").and_then(Value::as_str).unwrap_or_default();
+    let route = gw
+        .route_cache
+        .read()
+        .await
+        .match_route(ingress.route_protocol(), model)
+        .cloned();
+    match route {
+        Some(route) if route.access_control => {
+            authorize_route_access(gw, &route, headers, ingress).await.map(|key| key.id)
+        }
+        Some(_) => crate::proxy::responses::open_route_key_id(&gw.db, headers).await,
+        None => Ok(None),
+    }
 }
 
 async fn proxy_pipeline(
@@ -91,6 +360,7 @@ async fn proxy_pipeline(
     headers: HeaderMap,
     mut internal: InternalRequest,
     ingress: Protocol,
+    recorder: Option<ResponseRecorder>,
 ) -> Response {
     let start = Instant::now();
     let request_model = internal.model.clone();
@@ -98,86 +368,223 @@ async fn proxy_pipeline(
 
     let ingress_str = ingress.to_string();
     let route_protocol = ingress.route_protocol();
-    let route = {
+    let matched = {
         let cache = gw.route_cache.read().await;
-        cache.match_route(route_protocol, &request_model).cloned()
+        cache
+            .match_route(route_protocol, &request_model)
+            .map(|r| (r.clone(), cache.plan_attempts(r, &request_model, &gw.latency_tracker)))
     };
-    let route = match route {
-        Some(r) => r,
+    let (route, targets) = match matched {
+        Some(v) => v,
         None => return error_response(404, &format!("no route for model: {request_model}")),
     };
 
-    let auth_key = match authorize_route_access(&gw, &route, &headers).await {
+    let auth_key = match authorize_route_access(&gw, &route, &headers, ingress).await {
         Ok(v) => v,
         Err(resp) => return resp,
     };
-
-    let provider = match get_provider(&gw, &route.target_provider).await {
-        Ok(p) => p,
-        Err(e) => return error_response(502, &format!("provider error: {e}")),
-    };
-
-    let actual_model = if route.target_model.is_empty() || route.target_model == "*" {
-        request_model.clone()
-    } else {
-        route.target_model.clone()
+    let rate_limit = match check_key_quota(&gw, &auth_key) {
+        Ok(v) => v,
+        Err(limited) => return rate_limited_response(ingress, &limited),
     };
 
     crate::protocol::semantic::tool_correlation::normalize_request_tool_results(&mut internal);
-    maybe_strip_ollama_tools(&gw, &provider, &actual_model, &mut internal).await;
 
-    let egress: Protocol = provider.protocol.parse().unwrap_or(Protocol::OpenAI);
+    let client = ProxyClient::new(gw.http_client.clone(), gw.vertex_tokens.clone());
+    let media_options = MediaOptions::from_config(&gw.config);
 
-    let encoder = crate::protocol::get_encoder(egress);
-    let (egress_body, extra_headers) = match encoder.encode_request(&internal) {
-        Ok(r) => r,
-        Err(e) => return error_response(500, &format!("encode error: {e}")),
-    };
+    let mut last_failure = None;
+    for (attempt, (provider_id, actual_model)) in targets.iter().enumerate() {
+        if attempt > 0 {
+            tracing::warn!(
+                "route {} falling back to provider {} (model {})",
+                route.name,
+                provider_id,
+                actual_model
+            );
+        }
 
-    let egress_body = override_model(egress_body, &actual_model, egress);
-    let egress_path = encoder.egress_path(&actual_model, is_stream);
-    let client = ProxyClient::new(gw.http_client.clone());
-    let egress_str = egress.to_string();
+        if !gw.circuit_breakers.allow(provider_id) {
+            tracing::warn!("skipping provider {provider_id}: circuit open");
+            last_failure = Some(ingress_error_response(
+                ingress,
+                503,
+                &format!("provider {provider_id} temporarily unavailable (circuit open)"),
+            ));
+            continue;
+        }
 
-    if is_stream {
-        handle_stream(
-            gw,
-            client,
-            &provider,
-            egress,
-            ingress,
-            &egress_path,
-            egress_body,
-            extra_headers,
-            &ingress_str,
-            &egress_str,
-            &request_model,
-            &actual_model,
-            auth_key.id.as_deref(),
-            start,
-        )
-        .await
+        let provider = match get_provider(&gw, provider_id).await {
+            Ok(p) => p,
+            Err(e) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                last_failure = Some(ingress_error_response(ingress, 502, &format!("provider error: {e}")));
+                continue;
+            }
+        };
+
+        let permit = match gw.provider_limiter.acquire(&provider).await {
+            Ok(p) => p,
+            Err(rejected) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                tracing::warn!("skipping provider {}: {}", provider.name, rejected.message);
+                last_failure = Some(too_many_requests(
+                    ingress,
+                    &rejected.message,
+                    rejected.retry_after_secs,
+                ));
+                continue;
+            }
+        };
+
+        let egress = provider.egress_protocol();
+
+        let mut attempt_req = internal.clone();
+        maybe_strip_ollama_tools(&gw, &provider, actual_model, &mut attempt_req).await;
+        if let Err(e) = normalize_request_media(&mut attempt_req, egress, &media_options).await {
+            gw.circuit_breakers.release_probe(provider_id);
+            tracing::warn!("skipping provider {}: {e:#}", provider.name);
+            last_failure = Some(ingress_error_response(ingress, 400, &format!("{e:#}")));
+            continue;
+        }
+
+        let encoder = crate::protocol::get_encoder(egress);
+        let (egress_body, extra_headers) = match encoder.encode_request(&attempt_req) {
+            Ok(r) => r,
+            Err(e) => {
+                gw.circuit_breakers.release_probe(provider_id);
+                tracing::warn!("skipping provider {}: encode error: {e}", provider.name);
+                last_failure = Some(ingress_error_response(ingress, 500, &format!("encode error: {e}")));
+                continue;
+            }
+        };
+
+        let egress_body = override_model(egress_body, actual_model, egress);
+        let egress_path = encoder.egress_path(actual_model, is_stream);
+        let egress_str = egress.to_string();
+        let attempt_start = Instant::now();
+
+        let outcome = if is_stream {
+            handle_stream(
+                gw.clone(),
+                client
+                    .clone()
+                    .with_retry(RetryPolicy::for_provider(&provider))
+                    .with_flavor(UpstreamFlavor::for_target(&provider, actual_model)),
+                &provider,
+                egress,
+                ingress,
+                &egress_path,
+                egress_body,
+                extra_headers,
+                &ingress_str,
+                &egress_str,
+                &request_model,
+                actual_model,
+                auth_key.id.as_deref(),
+                start,
+                permit,
+                recorder.clone(),
+            )
+            .await
+        } else {
+            handle_non_stream(
+                gw.clone(),
+                client
+                    .clone()
+                    .with_retry(RetryPolicy::for_provider(&provider))
+                    .with_flavor(UpstreamFlavor::for_target(&provider, actual_model)),
+                &provider,
+                egress,
+                ingress,
+                &egress_path,
+                egress_body,
+                extra_headers,
+                &ingress_str,
+                &egress_str,
+                &request_model,
+                actual_model,
+                auth_key.id.as_deref(),
+                start,
+                permit,
+                recorder.clone(),
+            )
+            .await
+        };
+
+        record_circuit(&gw, &provider.id, &outcome);
+
+        match outcome {
+            AttemptOutcome::GatewayError(resp) => return with_rate_limit_headers(resp, &rate_limit),
+            AttemptOutcome::Done(resp) => {
+                let elapsed_ms = attempt_start.elapsed().as_millis() as f64;
+                if resp.status().is_success() {
+                    gw.latency_tracker.record(&provider.id, actual_model, elapsed_ms);
+                } else if resp.status().is_server_error() {
+                    gw.latency_tracker.record_failure(&provider.id, actual_model, elapsed_ms);
+                }
+                return with_rate_limit_headers(resp, &rate_limit);
+            }
+            AttemptOutcome::Retryable(resp) => {
+                if resp.status().is_server_error() {
+                    gw.latency_tracker.record_failure(
+                        &provider.id,
+                        actual_model,
+                        attempt_start.elapsed().as_millis() as f64,
+                    );
+                }
+                last_failure = Some(resp);
+            }
+        }
+    }
+
+    let resp = last_failure.unwrap_or_else(|| error_response(502, "no upstream target available"));
+    with_rate_limit_headers(resp, &rate_limit)
+}
+
+/// Result of a single upstream attempt. `Retryable` means the upstream failed
+/// before anything was sent to the client, so the request can be replayed on
+/// the route's fallback target. `GatewayError` is a failure on Nyro's side
+/// (such as a response it could not decode) that says nothing about the
+/// provider's health.
+enum AttemptOutcome {
+    Done(Response),
+    Retryable(Response),
+    GatewayError(Response),
+}
+
+/// Feed an attempt result into the provider's circuit breaker. Server errors
+/// and connection failures count against the provider; rate limits (429) are
+/// left to fallback and do not trip the breaker. Gateway-side errors are not
+/// recorded at all.
+fn record_circuit(gw: &Gateway, provider_id: &str, outcome: &AttemptOutcome) {
+    let status = match outcome {
+        AttemptOutcome::Done(resp) | AttemptOutcome::Retryable(resp) => resp.status(),
+        AttemptOutcome::GatewayError(_) => {
+            gw.circuit_breakers.release_probe(provider_id);
+            return;
+        }
+    };
+    record_circuit_status(gw, provider_id, status);
+}
+
+/// A 429 neither proves nor disproves the provider's health, so a half-open
+/// probe that ends in one hands its slot back instead of holding it for
+/// another cooldown.
+pub(crate) fn record_circuit_status(gw: &Gateway, provider_id: &str, status: StatusCode) {
+    if status.is_server_error() {
+        gw.circuit_breakers
+            .record_failure(provider_id, &format!("upstream status {}", status.as_u16()));
+    } else if status == StatusCode::TOO_MANY_REQUESTS {
+        gw.circuit_breakers.release_probe(provider_id);
     } else {
-        handle_non_stream(
-            gw,
-            client,
-            &provider,
-            egress,
-            ingress,
-            &egress_path,
-            egress_body,
-            extra_headers,
-            &ingress_str,
-            &egress_str,
-            &request_model,
-            &actual_model,
-            auth_key.id.as_deref(),
-            start,
-        )
-        .await
+        gw.circuit_breakers.record_success(provider_id);
     }
 }
 
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
 
 async fn maybe_strip_ollama_tools(
     gw: &Gateway,
@@ -270,30 +677,17 @@ async fn fetch_ollama_capabilities(
 }
 
 fn build_ollama_show_url(base_url: &str) -> anyhow::Result<Url> {
-    let mut url = Url::parse(base_url)?;
-    let raw_path = url.path().trim_end_matches('/');
-    let path = if raw_path.is_empty() {
-        "/api/show".to_string()
-    } else if raw_path.ends_with("/v1") {
-        let prefix = raw_path.trim_end_matches("/v1");
-        if prefix.is_empty() {
-            "/api/show".to_string()
-        } else {
-            format!("{prefix}/api/show")
-        }
-    } else {
-        format!("{raw_path}/api/show")
-    };
-    url.set_path(&path);
+    let mut url = Url::parse(&format!("{}/api/show", ollama_native_base(base_url)))?;
     url.set_query(None);
     Ok(url)
 }
 
-fn is_ollama_provider(provider: &Provider) -> bool {
-    provider
-        .vendor
-        .as_deref()
-        .is_some_and(|v| v.eq_ignore_ascii_case("ollama"))
+pub(crate) fn is_ollama_provider(provider: &Provider) -> bool {
+    provider.protocol.eq_ignore_ascii_case("ollama")
+        || provider
+            .vendor
+            .as_deref()
+            .is_some_and(|v| v.eq_ignore_ascii_case("ollama"))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -312,7 +706,9 @@ async fn handle_non_stream(
     actual_model: &str,
     api_key_id: Option<&str>,
     start: Instant,
-) -> Response {
+    permit: ProviderPermit,
+    recorder: Option<ResponseRecorder>,
+) -> AttemptOutcome {
     let (resp, status) = match client
         .call_non_stream(
             &provider.base_url,
@@ -333,7 +729,7 @@ async fn handle_non_stream(
                 TokenUsage::default(), false, false,
                 Some(e.to_string()), None, None,
             );
-            return error_response(502, &format!("upstream error: {e}"));
+            return AttemptOutcome::Retryable(error_response(502, &format!("upstream error: {e}")));
         }
     };
 
@@ -346,11 +742,16 @@ async fn handle_non_stream(
             TokenUsage::default(), false, false,
             preview.clone(), None, None,
         );
-        return (
+        let resp = (
             StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY),
             Json(resp),
         )
             .into_response();
+        return if is_retryable_status(status) {
+            AttemptOutcome::Retryable(resp)
+        } else {
+            AttemptOutcome::Done(resp)
+        };
     }
 
     let parser = crate::protocol::get_response_parser(egress);
@@ -358,14 +759,21 @@ async fn handle_non_stream(
 
     let mut internal_resp = match parser.parse_response(resp) {
         Ok(r) => r,
-        Err(e) => return error_response(500, &format!("parse error: {e}")),
+        Err(e) => {
+            return AttemptOutcome::GatewayError(error_response(502, &format!("parse error: {e}")));
+        }
     };
     crate::protocol::semantic::reasoning::normalize_response_reasoning(&mut internal_resp);
     crate::protocol::semantic::response_items::populate_response_items(&mut internal_resp);
 
     let is_tool = !internal_resp.tool_calls.is_empty();
     let usage = internal_resp.usage.clone();
+    gw.provider_limiter.record_tokens(&provider.id, total_tokens(&usage));
+    drop(permit);
     let output = formatter.format_response(&internal_resp);
+    if let Some(recorder) = &recorder {
+        recorder.save(&output).await;
+    }
 
     let response_preview = serde_json::to_string(&output)
         .ok()
@@ -378,11 +786,13 @@ async fn handle_non_stream(
         usage, false, is_tool, None, None, response_preview,
     );
 
-    (
-        StatusCode::from_u16(status).unwrap_or(StatusCode::OK),
-        Json(output),
+    AttemptOutcome::Done(
+        (
+            StatusCode::from_u16(status).unwrap_or(StatusCode::OK),
+            Json(output),
+        )
+            .into_response(),
     )
-        .into_response()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -401,7 +811,9 @@ async fn handle_stream(
     actual_model: &str,
     api_key_id: Option<&str>,
     start: Instant,
-) -> Response {
+    permit: ProviderPermit,
+    recorder: Option<ResponseRecorder>,
+) -> AttemptOutcome {
     let (resp, status) = match client
         .call_stream(
             &provider.base_url,
@@ -422,7 +834,7 @@ async fn handle_stream(
                 TokenUsage::default(), true, false,
                 Some(e.to_string()), None, None,
             );
-            return error_response(502, &format!("upstream error: {e}"));
+            return AttemptOutcome::Retryable(error_response(502, &format!("upstream error: {e}")));
         }
     };
 
@@ -438,11 +850,16 @@ async fn handle_stream(
             TokenUsage::default(), true, false,
             Some(err_body.to_string()), None, None,
         );
-        return (
+        let resp = (
             StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY),
             Json(err_body),
         )
             .into_response();
+        return if is_retryable_status(status) {
+            AttemptOutcome::Retryable(resp)
+        } else {
+            AttemptOutcome::Done(resp)
+        };
     }
 
     let mut stream_parser = crate::protocol::get_stream_parser(egress);
@@ -452,6 +869,7 @@ async fn handle_stream(
     let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, Infallible>>(64);
 
     let gw_log = gw.clone();
+    let provider_id = provider.id.clone();
     let provider_name = provider.name.clone();
     let ingress_s = ingress_str.to_string();
     let egress_s = egress_str.to_string();
@@ -460,35 +878,44 @@ async fn handle_stream(
     let key_id = api_key_id.map(ToString::to_string);
 
     tokio::spawn(async move {
+        // Keep the provider's concurrency slot until the stream is drained.
+        let _permit = permit;
+        let mut framer = UpstreamFramer::for_protocol(egress);
         while let Some(chunk) = byte_stream.next().await {
             let bytes = match chunk {
                 Ok(b) => b,
                 Err(_) => break,
             };
-            let text = String::from_utf8_lossy(&bytes);
+            let Some(text) = framer.push(&bytes) else {
+                continue;
+            };
             if let Ok(deltas) = stream_parser.parse_chunk(&text) {
                 let events = stream_formatter.format_deltas(&deltas);
-                for ev in events {
-                    if tx.send(Ok(ev.to_sse_string())).await.is_err() {
-                        return;
-                    }
+                if !send_events(&tx, ingress, events, recorder.as_ref()).await {
+                    return;
                 }
             }
         }
 
+        if let Some(text) = framer.finish()
+            && let Ok(deltas) = stream_parser.parse_chunk(&text)
+        {
+            let events = stream_formatter.format_deltas(&deltas);
+            send_events(&tx, ingress, events, recorder.as_ref()).await;
+        }
+
         if let Ok(deltas) = stream_parser.finish() {
             let events = stream_formatter.format_deltas(&deltas);
-            for ev in events {
-                let _ = tx.send(Ok(ev.to_sse_string())).await;
-            }
+            send_events(&tx, ingress, events, recorder.as_ref()).await;
         }
 
         let done_events = stream_formatter.format_done();
-        for ev in done_events {
-            let _ = tx.send(Ok(ev.to_sse_string())).await;
-        }
+        send_events(&tx, ingress, done_events, recorder.as_ref()).await;
 
         let usage = stream_formatter.usage();
+        gw_log
+            .provider_limiter
+            .record_tokens(&provider_id, total_tokens(&usage));
         emit_log(
             &gw_log, &ingress_s, &egress_s, &req_model, &act_model,
             key_id.as_deref(),
@@ -500,28 +927,73 @@ async fn handle_stream(
     let stream = ReceiverStream::new(rx);
     let body = Body::from_stream(stream);
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "text/event-stream")
-        .header(header::CACHE_CONTROL, "no-cache")
-        .header(header::CONNECTION, "keep-alive")
-        .body(body)
-        .unwrap()
+    AttemptOutcome::Done(
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, stream_content_type(ingress))
+            .header(header::CACHE_CONTROL, "no-cache")
+            .header(header::CONNECTION, "keep-alive")
+            .body(body)
+            .unwrap(),
+    )
 }
 
 // ── Helpers ──
 
-struct AuthenticatedKey {
-    id: Option<String>,
+/// Forward formatted events to the client, storing a completed Responses API
+/// response on the way. Returns `false` once the client has gone away.
+async fn send_events(
+    tx: &tokio::sync::mpsc::Sender<Result<String, Infallible>>,
+    ingress: Protocol,
+    events: Vec<SseEvent>,
+    recorder: Option<&ResponseRecorder>,
+) -> bool {
+    for ev in events {
+        if let Some(recorder) = recorder {
+            recorder.observe(&ev).await;
+        }
+        if tx.send(Ok(frame_event(ingress, &ev))).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Ollama clients read newline-delimited JSON; everyone else gets SSE.
+fn frame_event(ingress: Protocol, ev: &SseEvent) -> String {
+    match ingress {
+        Protocol::Ollama | Protocol::OllamaGenerate => format!("{}\n", ev.data),
+        _ => ev.to_sse_string(),
+    }
 }
 
-async fn authorize_route_access(gw: &Gateway, route: &Route, headers: &HeaderMap) -> Result<AuthenticatedKey, Response> {
+fn stream_content_type(ingress: Protocol) -> &'static str {
+    match ingress {
+        Protocol::Ollama | Protocol::OllamaGenerate => "application/x-ndjson",
+        _ => "text/event-stream",
+    }
+}
+
+pub(crate) struct AuthenticatedKey {
+    pub(crate) id: Option<String>,
+    pub(crate) limits: KeyLimits,
+}
+
+pub(crate) async fn authorize_route_access(
+    gw: &Gateway,
+    route: &Route,
+    headers: &HeaderMap,
+    ingress: Protocol,
+) -> Result<AuthenticatedKey, Response> {
     if !route.access_control {
-        return Ok(AuthenticatedKey { id: None });
+        return Ok(AuthenticatedKey {
+            id: None,
+            limits: KeyLimits::default(),
+        });
     }
 
     let Some(raw_key) = extract_api_key(headers) else {
-        return Err(error_response(401, "missing api key"));
+        return Err(ingress_error_response(ingress, 401, "missing api key"));
     };
 
     let key_row = sqlx::query_as::<_, (String, String, Option<String>, Option<i32>, Option<i32>, Option<i32>, Option<i32>)>(
@@ -530,14 +1002,14 @@ async fn authorize_route_access(gw: &Gateway, route: &Route, headers: &HeaderMap
     .bind(&raw_key)
     .fetch_optional(&gw.db)
     .await
-    .map_err(|e| error_response(500, &format!("auth db error: {e}")))?;
+    .map_err(|e| ingress_error_response(ingress, 500, &format!("auth db error: {e}")))?;
 
     let Some((api_key_id, status, expires_at, rpm, rpd, tpm, tpd)) = key_row else {
-        return Err(error_response(401, "invalid api key"));
+        return Err(ingress_error_response(ingress, 401, "invalid api key"));
     };
 
     if status != "active" {
-        return Err(error_response(403, "api key revoked"));
+        return Err(ingress_error_response(ingress, 403, "api key revoked"));
     }
 
     if let Some(expires) = expires_at.as_ref() {
@@ -550,7 +1022,7 @@ async fn authorize_route_access(gw: &Gateway, route: &Route, headers: &HeaderMap
         .map(|v| v > 0)
         .unwrap_or(false);
         if is_expired {
-            return Err(error_response(403, "api key expired"));
+            return Err(ingress_error_response(ingress, 403, "api key expired"));
         }
     }
 
@@ -561,69 +1033,26 @@ async fn authorize_route_access(gw: &Gateway, route: &Route, headers: &HeaderMap
     .bind(&route.id)
     .fetch_one(&gw.db)
     .await
-    .map_err(|e| error_response(500, &format!("auth db error: {e}")))?;
+    .map_err(|e| ingress_error_response(ingress, 500, &format!("auth db error: {e}")))?;
     if allowed == 0 {
-        return Err(error_response(403, "api key not allowed for this route"));
-    }
-
-    if let Some(limit) = rpm.filter(|v| *v > 0) {
-        let req_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM request_logs WHERE api_key_id = ? AND created_at >= datetime('now', '-1 minute')",
-        )
-        .bind(&api_key_id)
-        .fetch_one(&gw.db)
-        .await
-        .map_err(|e| error_response(500, &format!("quota db error: {e}")))?;
-        if req_count >= i64::from(limit) {
-            return Err(error_response(429, "api key rpm quota exceeded"));
-        }
-    }
-
-    if let Some(limit) = rpd.filter(|v| *v > 0) {
-        let req_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM request_logs WHERE api_key_id = ? AND created_at >= datetime('now', '-1 day')",
-        )
-        .bind(&api_key_id)
-        .fetch_one(&gw.db)
-        .await
-        .map_err(|e| error_response(500, &format!("quota db error: {e}")))?;
-        if req_count >= i64::from(limit) {
-            return Err(error_response(429, "api key rpd quota exceeded"));
-        }
-    }
-
-    if let Some(limit) = tpm.filter(|v| *v > 0) {
-        let token_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COALESCE(SUM(input_tokens + output_tokens), 0) FROM request_logs WHERE api_key_id = ? AND created_at >= datetime('now', '-1 minute')",
-        )
-        .bind(&api_key_id)
-        .fetch_one(&gw.db)
-        .await
-        .map_err(|e| error_response(500, &format!("quota db error: {e}")))?;
-        if token_count >= i64::from(limit) {
-            return Err(error_response(429, "api key tpm quota exceeded"));
-        }
-    }
-
-    if let Some(limit) = tpd.filter(|v| *v > 0) {
-        let token_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COALESCE(SUM(input_tokens + output_tokens), 0) FROM request_logs WHERE api_key_id = ? AND created_at >= datetime('now', '-1 day')",
-        )
-        .bind(&api_key_id)
-        .fetch_one(&gw.db)
-        .await
-        .map_err(|e| error_response(500, &format!("quota db error: {e}")))?;
-        if token_count >= i64::from(limit) {
-            return Err(error_response(429, "api key tpd quota exceeded"));
-        }
+        return Err(ingress_error_response(ingress, 403, "api key not allowed for this route"));
     }
 
     Ok(AuthenticatedKey {
         id: Some(api_key_id),
+        limits: KeyLimits::from_db(rpm, rpd, tpm, tpd),
     })
 }
 
-fn extract_api_key(headers: &HeaderMap) -> Option<String> {
+/// Count one request against the key's quotas.
+pub(crate) fn check_key_quota(gw: &Gateway, key: &AuthenticatedKey) -> Result<RateLimitInfo, RateLimited> {
+    match key.id.as_deref() {
+        Some(id) if !key.limits.is_unlimited() => gw.rate_limiter.check(id, &key.limits),
+        _ => Ok(RateLimitInfo::default()),
+    }
+}
+
+pub(crate) fn extract_api_key(headers: &HeaderMap) -> Option<String> {
     if let Some(value) = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
         if let Some(token) = value.strip_prefix("Bearer ") {
             let token = token.trim();
@@ -641,20 +1070,22 @@ fn extract_api_key(headers: &HeaderMap) -> Option<String> {
         .map(ToString::to_string)
 }
 
-async fn get_provider(gw: &Gateway, id: &str) -> anyhow::Result<Provider> {
+pub(crate) async fn get_provider(gw: &Gateway, id: &str) -> anyhow::Result<Provider> {
     sqlx::query_as::<_, Provider>(
-        "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, last_test_success, last_test_at, is_active, created_at, updated_at \
+        "SELECT id, name, vendor, protocol, base_url, preset_key, COALESCE(channel, region) AS channel, models_endpoint, COALESCE(models_source, models_endpoint) AS models_source, capabilities_source, static_models, api_key, max_concurrency, rpm, tpm, retry_max_attempts, retry_base_delay_ms, retry_max_delay_ms, api_version, access_key_id, cloud_region, api_dialect, last_test_success, last_test_at, is_active, created_at, updated_at \
          FROM providers WHERE id = ? AND is_active = 1",
     )
     .bind(id)
     .fetch_optional(&gw.db)
     .await?
-    .ok_or_else(|| anyhow::anyhow!("provider not found or inactive: {id}"))
+    .ok_or_else(|| anyhow::anyhow!("provider not found or inactive: {id}"))?
+    .decrypted()
 }
 
 fn override_model(mut body: Value, model: &str, protocol: Protocol) -> Value {
     match protocol {
-        Protocol::Gemini => body,
+        // Both carry the model in the URL path.
+        Protocol::Gemini | Protocol::Bedrock => body,
         _ => {
             if let Some(obj) = body.as_object_mut() {
                 obj.insert("model".into(), Value::String(model.to_string()));
@@ -679,7 +1110,95 @@ fn error_response(status: u16, message: &str) -> Response {
         .into_response()
 }
 
-fn emit_log(
+/// Error body in the shape clients of the ingress protocol expect, so SDKs
+/// surface the gateway's own rejections (auth, quota) like upstream errors.
+pub(crate) fn ingress_error_response(ingress: Protocol, status: u16, message: &str) -> Response {
+    let code = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = match ingress {
+        Protocol::Anthropic => {
+            let error_type = match status {
+                400 => "invalid_request_error",
+                401 => "authentication_error",
+                403 => "permission_error",
+                404 => "not_found_error",
+                429 => "rate_limit_error",
+                529 => "overloaded_error",
+                _ => "api_error",
+            };
+            serde_json::json!({
+                "type": "error",
+                "error": { "type": error_type, "message": message }
+            })
+        }
+        Protocol::Gemini => {
+            let grpc_status = match status {
+                400 => "INVALID_ARGUMENT",
+                401 => "UNAUTHENTICATED",
+                403 => "PERMISSION_DENIED",
+                404 => "NOT_FOUND",
+                429 => "RESOURCE_EXHAUSTED",
+                503 => "UNAVAILABLE",
+                _ => "INTERNAL",
+            };
+            serde_json::json!({
+                "error": { "code": status, "message": message, "status": grpc_status }
+            })
+        }
+        Protocol::Ollama | Protocol::OllamaGenerate => serde_json::json!({ "error": message }),
+        Protocol::OpenAI | Protocol::ResponsesAPI | Protocol::Bedrock => {
+            let error_type = if status == 429 { "rate_limit_error" } else { "gateway_error" };
+            serde_json::json!({
+                "error": { "message": message, "type": error_type, "code": status }
+            })
+        }
+    };
+    (code, Json(body)).into_response()
+}
+
+pub(crate) fn too_many_requests(ingress: Protocol, message: &str, retry_after_secs: u64) -> Response {
+    let mut resp = ingress_error_response(ingress, 429, message);
+    resp.headers_mut()
+        .insert(header::RETRY_AFTER, header::HeaderValue::from(retry_after_secs));
+    resp
+}
+
+pub(crate) fn rate_limited_response(ingress: Protocol, limited: &RateLimited) -> Response {
+    let message = format!("api key {} quota exceeded", limited.quota);
+    let resp = too_many_requests(ingress, &message, limited.retry_after_secs);
+    with_rate_limit_headers(resp, &limited.info)
+}
+
+/// Attach OpenAI-style `x-ratelimit-*` headers; reset values are in seconds.
+pub(crate) fn with_rate_limit_headers(mut resp: Response, info: &RateLimitInfo) -> Response {
+    let dimensions = [("requests", info.requests), ("tokens", info.tokens)];
+    for (name, state) in dimensions {
+        let Some(state) = state else { continue };
+        let headers = resp.headers_mut();
+        for (kind, value) in [
+            ("limit", state.limit),
+            ("remaining", state.remaining),
+            ("reset", state.reset_secs),
+        ] {
+            if let (Ok(key), Ok(value)) = (
+                header::HeaderName::from_bytes(format!("x-ratelimit-{kind}-{name}").as_bytes()),
+                header::HeaderValue::from_str(&value.to_string()),
+            ) {
+                headers.insert(key, value);
+            }
+        }
+    }
+    resp
+}
+
+/// Tokens charged against TPM limits. Cache reads and writes are left out,
+/// so a cached session is limited on the same footing as before caching was
+/// reported.
+pub(crate) fn total_tokens(usage: &TokenUsage) -> u64 {
+    u64::from(usage.uncached_input_tokens()) + u64::from(usage.output_tokens)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn emit_log(
     gw: &Gateway,
     ingress: &str,
     egress: &str,
@@ -696,6 +1215,9 @@ fn emit_log(
     request_preview: Option<String>,
     response_preview: Option<String>,
 ) {
+    if let Some(key_id) = api_key_id {
+        gw.rate_limiter.record_tokens(key_id, total_tokens(&usage));
+    }
     let _ = gw.log_tx.try_send(LogEntry {
         api_key_id: api_key_id.map(ToString::to_string),
         ingress_protocol: ingress.to_string(),
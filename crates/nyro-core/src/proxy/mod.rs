@@ -2,3 +2,13 @@ pub mod server;
 pub mod handler;
 pub mod client;
 pub mod auth;
+pub mod circuit;
+pub mod embeddings;
+pub mod framing;
+pub mod models;
+pub mod provider_limit;
+pub mod responses;
+pub mod rate_limit;
+pub mod retry;
+pub mod sigv4;
+pub mod vertex;
@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+use crate::db::models::{ModelCapabilities, Route};
+use crate::protocol::Protocol;
+use crate::proxy::handler::{extract_api_key, ingress_error_response};
+use crate::Gateway;
+
+/// Capability lookups may hit a provider's HTTP endpoint; never let one stall the listing.
+const CAPABILITY_LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a resolved capability set is reused across listings.
+const CAPABILITY_CACHE_TTL: Duration = Duration::from_secs(600);
+
+// ── GET /v1/models (OpenAI, or Anthropic when `anthropic-version` is sent) ──
+
+pub async fn list_models(State(gw): State<Gateway>, headers: HeaderMap) -> Response {
+    let ingress = if headers.contains_key("anthropic-version") {
+        Protocol::Anthropic
+    } else {
+        Protocol::OpenAI
+    };
+    models_response(gw, headers, ingress).await
+}
+
+// ── GET /v1beta/models (Gemini) ──
+
+pub async fn gemini_list_models(State(gw): State<Gateway>, headers: HeaderMap) -> Response {
+    models_response(gw, headers, Protocol::Gemini).await
+}
+
+// ── GET /api/tags (Ollama) ──
+
+pub async fn ollama_list_models(State(gw): State<Gateway>, headers: HeaderMap) -> Response {
+    models_response(gw, headers, Protocol::Ollama).await
+}
+
+async fn models_response(gw: Gateway, headers: HeaderMap, ingress: Protocol) -> Response {
+    let allowed = match allowed_route_ids(&gw.db, &headers).await {
+        Ok(v) => v,
+        Err(message) => return ingress_error_response(ingress, 500, &message),
+    };
+
+    // Each route paired with the provider model that actually serves it.
+    let routes: Vec<(Route, (String, String))> = {
+        let cache = gw.route_cache.read().await;
+        cache
+            .listed_routes(ingress.route_protocol())
+            .into_iter()
+            .filter(|route| !route.access_control || allowed.contains(&route.id))
+            .map(|route| (route.clone(), cache.listing_target(route)))
+            .collect()
+    };
+
+    // Routes often share a target; resolve each provider model once.
+    let targets: HashSet<(&str, &str)> = routes
+        .iter()
+        .map(|(_, (provider_id, model))| (provider_id.as_str(), model.as_str()))
+        .collect();
+    let lookups = targets.into_iter().map(|(provider_id, model)| {
+        let gw = &gw;
+        async move { ((provider_id, model), model_capabilities(gw, provider_id, model).await) }
+    });
+    let capabilities: HashMap<(&str, &str), Option<ModelCapabilities>> =
+        futures::future::join_all(lookups).await.into_iter().collect();
+
+    let entries: Vec<(&Route, Option<ModelCapabilities>)> = routes
+        .iter()
+        .map(|(route, (provider_id, model))| {
+            let target = (provider_id.as_str(), model.as_str());
+            (route, capabilities.get(&target).cloned().flatten())
+        })
+        .collect();
+    Json(format_models(ingress, &entries)).into_response()
+}
+
+/// Capabilities of one provider model, cached on the gateway. A lookup that
+/// times out is not cached, so the next listing tries again.
+async fn model_capabilities(gw: &Gateway, provider_id: &str, model: &str) -> Option<ModelCapabilities> {
+    if let Some(cached) = gw
+        .get_model_capabilities_cached(provider_id, model, CAPABILITY_CACHE_TTL)
+        .await
+    {
+        return cached;
+    }
+    let lookup = tokio::time::timeout(
+        CAPABILITY_LOOKUP_TIMEOUT,
+        gw.admin().get_model_capabilities(provider_id, model),
+    )
+    .await
+    .ok()?;
+    let capabilities = lookup.ok();
+    gw.set_model_capabilities_cache(provider_id, model, capabilities.clone())
+        .await;
+    capabilities
+}
+
+/// Access-controlled routes the caller's API key is bound to. Like
+/// `authorize_route_access`, keys only matter for access-controlled routes:
+/// no key, or one that is unknown, revoked or expired (such as a client's own
+/// upstream key), lists public routes only.
+async fn allowed_route_ids(db: &SqlitePool, headers: &HeaderMap) -> Result<HashSet<String>, String> {
+    let Some(raw_key) = extract_api_key(headers) else {
+        return Ok(HashSet::new());
+    };
+
+    let key_id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM api_keys WHERE key = ? AND status = 'active' \
+         AND (expires_at IS NULL OR datetime(expires_at) > datetime('now'))",
+    )
+    .bind(&raw_key)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| format!("auth db error: {e}"))?;
+    let Some(key_id) = key_id else {
+        return Ok(HashSet::new());
+    };
+
+    let route_ids = sqlx::query_scalar::<_, String>(
+        "SELECT route_id FROM api_key_routes WHERE api_key_id = ?",
+    )
+    .bind(&key_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| format!("auth db error: {e}"))?;
+    Ok(route_ids.into_iter().collect())
+}
+
+fn format_models(ingress: Protocol, entries: &[(&Route, Option<ModelCapabilities>)]) -> Value {
+    match ingress {
+        Protocol::Anthropic => {
+            let data: Vec<Value> = entries
+                .iter()
+                .map(|(route, caps)| {
+                    let mut model = json!({
+                        "type": "model",
+                        "id": route.virtual_model,
+                        "display_name": route.virtual_model,
+                        "created_at": created_at(route).to_rfc3339(),
+                    });
+                    add_capabilities(&mut model, caps.as_ref());
+                    model
+                })
+                .collect();
+            json!({
+                "data": data,
+                "has_more": false,
+                "first_id": entries.first().map(|(r, _)| r.virtual_model.clone()),
+                "last_id": entries.last().map(|(r, _)| r.virtual_model.clone()),
+            })
+        }
+        Protocol::Gemini => {
+            let models: Vec<Value> = entries
+                .iter()
+                .map(|(route, caps)| {
+                    let mut model = json!({
+                        "name": format!("models/{}", route.virtual_model),
+                        "displayName": route.virtual_model,
+                        "supportedGenerationMethods": ["generateContent", "streamGenerateContent"],
+                    });
+                    if let Some(caps) = caps {
+                        model["inputTokenLimit"] = json!(caps.context_window);
+                        if let Some(max) = caps.output_max_tokens {
+                            model["outputTokenLimit"] = json!(max);
+                        }
+                        model["thinking"] = json!(caps.reasoning);
+                    }
+                    model
+                })
+                .collect();
+            json!({ "models": models })
+        }
+        Protocol::Ollama | Protocol::OllamaGenerate => {
+            let models: Vec<Value> = entries
+                .iter()
+                .map(|(route, caps)| {
+                    let mut model = json!({
+                        "name": route.virtual_model,
+                        "model": route.virtual_model,
+                        "modified_at": created_at(route).to_rfc3339(),
+                        "size": 0,
+                        "digest": "",
+                        "details": {
+                            "format": "",
+                            "family": "",
+                            "parameter_size": "",
+                            "quantization_level": "",
+                        },
+                    });
+                    if let Some(caps) = caps {
+                        model["context_length"] = json!(caps.context_window);
+                    }
+                    model
+                })
+                .collect();
+            json!({ "models": models })
+        }
+        Protocol::OpenAI | Protocol::ResponsesAPI | Protocol::Bedrock => {
+            let data: Vec<Value> = entries
+                .iter()
+                .map(|(route, caps)| {
+                    let mut model = json!({
+                        "id": route.virtual_model,
+                        "object": "model",
+                        "created": created_at(route).timestamp(),
+                        "owned_by": "nyro",
+                    });
+                    add_capabilities(&mut model, caps.as_ref());
+                    model
+                })
+                .collect();
+            json!({ "object": "list", "data": data })
+        }
+    }
+}
+
+/// Non-standard but widely read fields, only emitted when known.
+fn add_capabilities(model: &mut Value, caps: Option<&ModelCapabilities>) {
+    let Some(caps) = caps else { return };
+    model["context_window"] = json!(caps.context_window);
+    if let Some(max) = caps.output_max_tokens {
+        model["max_output_tokens"] = json!(max);
+    }
+    model["input_modalities"] = json!(caps.input_modalities);
+    model["output_modalities"] = json!(caps.output_modalities);
+    model["tool_call"] = json!(caps.tool_call);
+    model["reasoning"] = json!(caps.reasoning);
+}
+
+fn created_at(route: &Route) -> chrono::DateTime<Utc> {
+    NaiveDateTime::parse_from_str(&route.created_at, "%Y-%m-%d %H:%M:%S")
+        .map(|dt| Utc.from_utc_datetime(&dt))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(virtual_model: &str) -> Route {
+        Route {
+            id: virtual_model.to_string(),
+            name: virtual_model.to_string(),
+            ingress_protocol: "openai".to_string(),
+            virtual_model: virtual_model.to_string(),
+            target_provider: "p".to_string(),
+            target_model: "m".to_string(),
+            fallback_provider: None,
+            fallback_model: None,
+            strategy: "round_robin".to_string(),
+            priority: 0,
+            access_control: false,
+            is_active: true,
+            created_at: "2025-01-02 03:04:05".to_string(),
+        }
+    }
+
+    fn caps() -> ModelCapabilities {
+        ModelCapabilities {
+            provider: "p".to_string(),
+            model_id: "m".to_string(),
+            context_window: 200_000,
+            output_max_tokens: Some(8192),
+            tool_call: true,
+            reasoning: false,
+            input_modalities: vec!["text".to_string(), "image".to_string()],
+            output_modalities: vec!["text".to_string()],
+            input_cost: None,
+            output_cost: None,
+        }
+    }
+
+    #[test]
+    fn formats_each_ingress_natively() {
+        let a = route("chat-a");
+        let b = route("chat-b");
+        let entries = vec![(&a, Some(caps())), (&b, None)];
+
+        let openai = format_models(Protocol::OpenAI, &entries);
+        assert_eq!(openai["object"], "list");
+        assert_eq!(openai["data"][0]["created"], 1735787045);
+        assert_eq!(openai["data"][0]["context_window"], 200_000);
+        assert!(openai["data"][1].get("context_window").is_none());
+
+        let anthropic = format_models(Protocol::Anthropic, &entries);
+        assert_eq!(anthropic["data"][0]["type"], "model");
+        assert_eq!(anthropic["last_id"], "chat-b");
+
+        let gemini = format_models(Protocol::Gemini, &entries);
+        assert_eq!(gemini["models"][0]["name"], "models/chat-a");
+        assert_eq!(gemini["models"][0]["outputTokenLimit"], 8192);
+
+        let ollama = format_models(Protocol::Ollama, &entries);
+        assert_eq!(ollama["models"][1]["name"], "chat-b");
+        assert_eq!(ollama["models"][0]["context_length"], 200_000);
+    }
+
+    #[tokio::test]
+    async fn unknown_keys_list_public_routes_only() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::migrate(&db).await.unwrap();
+        for sql in [
+            "INSERT INTO providers (id, name, protocol, base_url, api_key) VALUES ('p', 'p', 'openai', 'http://p', '')",
+            "INSERT INTO routes (id, name, match_pattern, target_provider, target_model) VALUES ('private', 'private', 'm', 'p', 'm')",
+            "INSERT INTO api_keys (id, key, name, status) VALUES ('key-a', 'sk-a', 'a', 'active')",
+        ] {
+            sqlx::query(sql).execute(&db).await.unwrap();
+        }
+        sqlx::query("INSERT INTO api_key_routes (api_key_id, route_id) VALUES ('key-a', 'private')")
+            .execute(&db)
+            .await
+            .unwrap();
+        let bearer = |key: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("authorization", format!("Bearer {key}").parse().unwrap());
+            headers
+        };
+
+        let foreign = allowed_route_ids(&db, &bearer("sk-proj-upstream")).await.unwrap();
+        assert!(foreign.is_empty());
+        let bound = allowed_route_ids(&db, &bearer("sk-a")).await.unwrap();
+        assert!(bound.contains("private"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::db::models::Provider;
+use crate::proxy::rate_limit::{KeyLimits, RateLimiter};
+
+/// Why an attempt was refused before reaching the upstream.
+#[derive(Debug, Clone)]
+pub struct ProviderRejected {
+    pub message: String,
+    pub retry_after_secs: u64,
+}
+
+/// Concurrency slot held for the whole upstream attempt, including a
+/// streamed response body. Dropping it frees the slot.
+pub struct ProviderPermit {
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// Per-provider upstream limits configured on `Provider`: max in-flight
+/// requests, RPM and TPM.
+///
+/// A request over the concurrency limit waits up to `queue_timeout` for a
+/// free slot; RPM/TPM overruns are rejected immediately.
+pub struct ProviderLimiter {
+    queue_timeout: Duration,
+    slots: Mutex<HashMap<String, (usize, Arc<Semaphore>)>>,
+    rates: RateLimiter,
+}
+
+impl ProviderLimiter {
+    pub fn new(queue_timeout: Duration) -> Self {
+        Self {
+            queue_timeout,
+            slots: Mutex::new(HashMap::new()),
+            rates: RateLimiter::default(),
+        }
+    }
+
+    pub async fn acquire(&self, provider: &Provider) -> Result<ProviderPermit, ProviderRejected> {
+        let slot = match provider.max_concurrency.filter(|v| *v > 0) {
+            Some(max) => Some(self.acquire_slot(provider, max as usize).await?),
+            None => None,
+        };
+
+        let limits = KeyLimits::from_db(provider.rpm, None, provider.tpm, None);
+        if !limits.is_unlimited() {
+            self.rates
+                .check(&provider.id, &limits)
+                .map_err(|limited| ProviderRejected {
+                    message: format!("provider {} {} limit reached", provider.name, limited.quota),
+                    retry_after_secs: limited.retry_after_secs,
+                })?;
+        }
+
+        Ok(ProviderPermit { _slot: slot })
+    }
+
+    async fn acquire_slot(
+        &self,
+        provider: &Provider,
+        max: usize,
+    ) -> Result<OwnedSemaphorePermit, ProviderRejected> {
+        let semaphore = self.semaphore(&provider.id, max);
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        let rejected = || ProviderRejected {
+            message: format!(
+                "provider {} concurrency limit reached ({max} in flight)",
+                provider.name
+            ),
+            retry_after_secs: 1,
+        };
+        if self.queue_timeout.is_zero() {
+            return Err(rejected());
+        }
+        match tokio::time::timeout(self.queue_timeout, semaphore.acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(rejected()),
+        }
+    }
+
+    /// Semaphore for the provider, replaced when its configured size changes.
+    /// Requests still holding permits on a replaced semaphore finish normally.
+    fn semaphore(&self, provider_id: &str, max: usize) -> Arc<Semaphore> {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        match slots.get(provider_id) {
+            Some((size, semaphore)) if *size == max => semaphore.clone(),
+            _ => {
+                let semaphore = Arc::new(Semaphore::new(max));
+                slots.insert(provider_id.to_string(), (max, semaphore.clone()));
+                semaphore
+            }
+        }
+    }
+
+    pub fn record_tokens(&self, provider_id: &str, tokens: u64) {
+        self.rates.record_tokens(provider_id, tokens);
+    }
+
+    pub fn forget(&self, provider_id: &str) {
+        self.slots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(provider_id);
+        self.rates.forget(provider_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(max_concurrency: Option<i32>, rpm: Option<i32>) -> Provider {
+        Provider {
+            id: "p".to_string(),
+            name: "mock".to_string(),
+            vendor: None,
+            protocol: "openai".to_string(),
+            base_url: "http://localhost".to_string(),
+            preset_key: None,
+            channel: None,
+            models_endpoint: None,
+            models_source: None,
+            capabilities_source: None,
+            static_models: None,
+            api_key: String::new(),
+            max_concurrency,
+            rpm,
+            tpm: None,
+            retry_max_attempts: None,
+            retry_base_delay_ms: None,
+            retry_max_delay_ms: None,
+            api_version: None,
+            access_key_id: None,
+            cloud_region: None,
+            api_dialect: None,
+            last_test_success: None,
+            last_test_at: None,
+            is_active: true,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn queued_request_gets_slot_when_released() {
+        let limiter = Arc::new(ProviderLimiter::new(Duration::from_secs(2)));
+        let p = provider(Some(1), None);
+        let first = limiter.acquire(&p).await.unwrap();
+
+        let waiter = {
+            let limiter = limiter.clone();
+            let p = p.clone();
+            tokio::spawn(async move { limiter.acquire(&p).await.is_ok() })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(first);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_when_queue_wait_expires() {
+        let limiter = ProviderLimiter::new(Duration::from_millis(20));
+        let p = provider(Some(1), None);
+        let _held = limiter.acquire(&p).await.unwrap();
+        let err = limiter.acquire(&p).await.err().unwrap();
+        assert!(err.message.contains("concurrency limit"));
+    }
+
+    #[tokio::test]
+    async fn rpm_rejects_immediately() {
+        let limiter = ProviderLimiter::new(Duration::from_secs(5));
+        let p = provider(None, Some(1));
+        drop(limiter.acquire(&p).await.unwrap());
+        let err = limiter.acquire(&p).await.err().unwrap();
+        assert_eq!(err.message, "provider mock rpm limit reached");
+        assert!(err.retry_after_secs >= 1);
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sqlx::SqlitePool;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const DAY: Duration = Duration::from_secs(86_400);
+
+/// Quotas configured on an API key. `None` (or a non-positive DB value) means unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyLimits {
+    pub rpm: Option<u64>,
+    pub rpd: Option<u64>,
+    pub tpm: Option<u64>,
+    pub tpd: Option<u64>,
+}
+
+impl KeyLimits {
+    pub fn from_db(rpm: Option<i32>, rpd: Option<i32>, tpm: Option<i32>, tpd: Option<i32>) -> Self {
+        let positive = |v: Option<i32>| v.filter(|v| *v > 0).map(|v| v as u64);
+        Self {
+            rpm: positive(rpm),
+            rpd: positive(rpd),
+            tpm: positive(tpm),
+            tpd: positive(tpd),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.rpm.is_none() && self.rpd.is_none() && self.tpm.is_none() && self.tpd.is_none()
+    }
+}
+
+/// Limit, remaining budget and seconds until the budget fully resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitState {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset_secs: u64,
+}
+
+/// Per-request view of a key's quota, surfaced as `x-ratelimit-*` headers.
+/// Each dimension reports the tightest configured window (minute before day).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitInfo {
+    pub requests: Option<LimitState>,
+    pub tokens: Option<LimitState>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    /// Exhausted quota: `rpm`, `rpd`, `tpm` or `tpd`.
+    pub quota: &'static str,
+    pub retry_after_secs: u64,
+    pub info: RateLimitInfo,
+}
+
+/// Sliding-window counter: the previous fixed window is weighted by how much
+/// of it still overlaps the sliding window ending now.
+#[derive(Debug, Clone)]
+struct Window {
+    span: Duration,
+    start: Instant,
+    current: u64,
+    previous: u64,
+}
+
+impl Window {
+    fn new(span: Duration, now: Instant) -> Self {
+        Self {
+            span,
+            start: now,
+            current: 0,
+            previous: 0,
+        }
+    }
+
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.start);
+        if elapsed >= self.span * 2 {
+            self.previous = 0;
+            self.current = 0;
+            self.start = now;
+        } else if elapsed >= self.span {
+            self.previous = self.current;
+            self.current = 0;
+            self.start += self.span;
+        }
+    }
+
+    fn overlap(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f64();
+        (1.0 - elapsed / self.span.as_secs_f64()).clamp(0.0, 1.0)
+    }
+
+    fn estimate(&self, now: Instant) -> f64 {
+        self.previous as f64 * self.overlap(now) + self.current as f64
+    }
+
+    /// Seconds until `cost` more units fit under `limit`.
+    fn retry_after(&self, now: Instant, limit: u64, cost: u64) -> u64 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f64();
+        let span = self.span.as_secs_f64();
+        let headroom = limit as f64 - self.current as f64 - cost as f64;
+        let secs = if headroom < 0.0 || self.previous == 0 {
+            // Only the next window rollover frees budget.
+            span - elapsed
+        } else {
+            // Wait until the previous window's weighted share drops below the headroom.
+            span * (1.0 - headroom / self.previous as f64) - elapsed
+        };
+        secs.ceil().max(1.0) as u64
+    }
+
+    /// Load `count` units from history whose oldest entry is `age` old, as if
+    /// the current window had opened when that entry was recorded.
+    fn seed(&mut self, now: Instant, count: i64, age: Duration) {
+        self.current = count.max(0) as u64;
+        self.previous = 0;
+        self.start = now.checked_sub(age.min(self.span)).unwrap_or(now);
+    }
+
+    fn state(&self, now: Instant, limit: u64) -> LimitState {
+        let used = self.estimate(now).ceil() as u64;
+        let elapsed = now.saturating_duration_since(self.start);
+        LimitState {
+            limit,
+            remaining: limit.saturating_sub(used),
+            reset_secs: self.span.saturating_sub(elapsed).as_secs().max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KeyWindows {
+    rpm: Window,
+    rpd: Window,
+    tpm: Window,
+    tpd: Window,
+}
+
+impl KeyWindows {
+    fn new(now: Instant) -> Self {
+        Self {
+            rpm: Window::new(MINUTE, now),
+            rpd: Window::new(DAY, now),
+            tpm: Window::new(MINUTE, now),
+            tpd: Window::new(DAY, now),
+        }
+    }
+
+    fn roll(&mut self, now: Instant) {
+        self.rpm.roll(now);
+        self.rpd.roll(now);
+        self.tpm.roll(now);
+        self.tpd.roll(now);
+    }
+
+    fn info(&self, now: Instant, limits: &KeyLimits) -> RateLimitInfo {
+        let requests = limits
+            .rpm
+            .map(|l| self.rpm.state(now, l))
+            .or_else(|| limits.rpd.map(|l| self.rpd.state(now, l)));
+        let tokens = limits
+            .tpm
+            .map(|l| self.tpm.state(now, l))
+            .or_else(|| limits.tpd.map(|l| self.tpd.state(now, l)));
+        RateLimitInfo { requests, tokens }
+    }
+}
+
+/// In-process quota enforcement keyed by API key (or provider) id.
+///
+/// Requests are counted when admitted, so bursts cannot slip past the limit
+/// while request logs are still waiting to be flushed. Token usage is only
+/// known afterwards and is recorded when the request is logged.
+#[derive(Default)]
+pub struct RateLimiter {
+    keys: Mutex<HashMap<String, KeyWindows>>,
+}
+
+impl RateLimiter {
+    /// Seed counters from the last minute/day of `request_logs` so a restart
+    /// does not hand every key a fresh budget. Tokens are counted like
+    /// `record_tokens` is fed: uncached input plus output, since logged
+    /// `input_tokens` include cache reads and writes. Each window is backdated
+    /// to its oldest counted row, so seeded usage expires on the same schedule
+    /// it would have without the restart.
+    pub async fn seed(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let rows = sqlx::query_as::<_, (String, i64, i64, i64, i64, f64, f64)>(
+            "SELECT api_key_id, \
+                    SUM(CASE WHEN created_at >= datetime('now', '-1 minute') THEN 1 ELSE 0 END), \
+                    COUNT(*), \
+                    COALESCE(SUM(CASE WHEN created_at >= datetime('now', '-1 minute') THEN MAX(input_tokens - COALESCE(cache_read_tokens, 0) - COALESCE(cache_creation_tokens, 0), 0) + output_tokens ELSE 0 END), 0), \
+                    COALESCE(SUM(MAX(input_tokens - COALESCE(cache_read_tokens, 0) - COALESCE(cache_creation_tokens, 0), 0) + output_tokens), 0), \
+                    COALESCE(MAX(CASE WHEN created_at >= datetime('now', '-1 minute') THEN (julianday('now') - julianday(created_at)) * 86400.0 END), 0.0), \
+                    COALESCE(MAX((julianday('now') - julianday(created_at)) * 86400.0), 0.0) \
+             FROM request_logs \
+             WHERE api_key_id IS NOT NULL AND created_at >= datetime('now', '-1 day') \
+             GROUP BY api_key_id",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let now = Instant::now();
+        let mut keys = self.lock();
+        for (key_id, req_minute, req_day, tok_minute, tok_day, age_minute, age_day) in rows {
+            let age_minute = Duration::from_secs_f64(age_minute.max(0.0));
+            let age_day = Duration::from_secs_f64(age_day.max(0.0));
+            let windows = keys.entry(key_id).or_insert_with(|| KeyWindows::new(now));
+            windows.rpm.seed(now, req_minute, age_minute);
+            windows.rpd.seed(now, req_day, age_day);
+            windows.tpm.seed(now, tok_minute, age_minute);
+            windows.tpd.seed(now, tok_day, age_day);
+        }
+        Ok(())
+    }
+
+    /// Admit one request for `key_id`, counting it against the request quotas.
+    pub fn check(&self, key_id: &str, limits: &KeyLimits) -> Result<RateLimitInfo, RateLimited> {
+        self.check_at(key_id, limits, Instant::now())
+    }
+
+    fn check_at(&self, key_id: &str, limits: &KeyLimits, now: Instant) -> Result<RateLimitInfo, RateLimited> {
+        let mut keys = self.lock();
+        let windows = keys
+            .entry(key_id.to_string())
+            .or_insert_with(|| KeyWindows::new(now));
+        windows.roll(now);
+
+        let checks = [
+            (limits.rpm, &windows.rpm, 1, "rpm"),
+            (limits.rpd, &windows.rpd, 1, "rpd"),
+            (limits.tpm, &windows.tpm, 0, "tpm"),
+            (limits.tpd, &windows.tpd, 0, "tpd"),
+        ];
+        for (limit, window, cost, quota) in checks {
+            let Some(limit) = limit else { continue };
+            // Token windows reject once the budget is used up; request windows
+            // reject when admitting one more request would exceed it.
+            let over = if cost == 0 {
+                window.estimate(now) >= limit as f64
+            } else {
+                window.estimate(now) + cost as f64 > limit as f64
+            };
+            if over {
+                return Err(RateLimited {
+                    quota,
+                    retry_after_secs: window.retry_after(now, limit, cost),
+                    info: windows.info(now, limits),
+                });
+            }
+        }
+
+        windows.rpm.current += 1;
+        windows.rpd.current += 1;
+        Ok(windows.info(now, limits))
+    }
+
+    pub fn record_tokens(&self, key_id: &str, tokens: u64) {
+        if tokens == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let mut keys = self.lock();
+        let windows = keys
+            .entry(key_id.to_string())
+            .or_insert_with(|| KeyWindows::new(now));
+        windows.roll(now);
+        windows.tpm.current += tokens;
+        windows.tpd.current += tokens;
+    }
+
+    pub fn forget(&self, key_id: &str) {
+        self.lock().remove(key_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, KeyWindows>> {
+        self.keys.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(rpm: u64) -> KeyLimits {
+        KeyLimits {
+            rpm: Some(rpm),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejects_burst_beyond_rpm() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at("k", &limits(3), now).is_ok());
+        }
+        let err = limiter.check_at("k", &limits(3), now).unwrap_err();
+        assert_eq!(err.quota, "rpm");
+        assert_eq!(err.retry_after_secs, 60);
+        assert_eq!(err.info.requests.unwrap().remaining, 0);
+    }
+
+    #[test]
+    fn sliding_window_releases_budget_gradually() {
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.check_at("k", &limits(4), start).unwrap();
+        }
+        // Half way into the next window, half of the previous window still counts.
+        let later = start + Duration::from_secs(90);
+        let info = limiter.check_at("k", &limits(4), later).unwrap();
+        assert_eq!(info.requests.unwrap().remaining, 1);
+        limiter.check_at("k", &limits(4), later).unwrap();
+        assert!(limiter.check_at("k", &limits(4), later).is_err());
+    }
+
+    #[test]
+    fn token_quota_applies_after_usage_is_recorded() {
+        let limiter = RateLimiter::default();
+        let limits = KeyLimits {
+            tpm: Some(100),
+            ..Default::default()
+        };
+        assert!(limiter.check("k", &limits).is_ok());
+        limiter.record_tokens("k", 150);
+        let err = limiter.check("k", &limits).unwrap_err();
+        assert_eq!(err.quota, "tpm");
+        assert!(err.retry_after_secs >= 1);
+    }
+
+    #[tokio::test]
+    async fn seed_counts_uncached_input_tokens() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::migrate(&pool).await.unwrap();
+        // 10_000 input tokens of which 9_000 were read from and 500 written to the cache.
+        sqlx::query(
+            "INSERT INTO request_logs (id, api_key_id, input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens) \
+             VALUES ('log-1', 'k', 10000, 100, 9000, 500)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let limiter = RateLimiter::default();
+        limiter.seed(&pool).await.unwrap();
+        let limits = KeyLimits {
+            tpm: Some(1_000),
+            tpd: Some(1_000),
+            ..Default::default()
+        };
+        let info = limiter.check("k", &limits).unwrap();
+        assert_eq!(info.tokens.unwrap().remaining, 400);
+    }
+
+    #[tokio::test]
+    async fn seed_backdates_windows_to_the_oldest_row() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::migrate(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO request_logs (id, api_key_id, created_at, input_tokens, output_tokens) \
+             VALUES ('log-1', 'k', datetime('now', '-50 seconds'), 10, 0), \
+                    ('log-2', 'k', datetime('now', '-2 hours'), 10, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let limiter = RateLimiter::default();
+        limiter.seed(&pool).await.unwrap();
+        let now = Instant::now();
+        let minute = limiter.check_at("k", &limits(1), now).unwrap_err();
+        assert!(minute.retry_after_secs <= 11, "{}", minute.retry_after_secs);
+
+        let day = KeyLimits {
+            rpd: Some(3),
+            ..Default::default()
+        };
+        let info = limiter.check_at("k", &day, now).unwrap();
+        let reset = info.requests.unwrap().reset_secs;
+        assert!((DAY.as_secs() - 7_202..=DAY.as_secs() - 7_199).contains(&reset), "{reset}");
+        // Two minutes after the row was logged it no longer counts at all.
+        assert!(limiter.check_at("k", &limits(1), now + Duration::from_secs(71)).is_ok());
+    }
+
+    #[test]
+    fn db_limits_ignore_non_positive_values() {
+        let limits = KeyLimits::from_db(Some(0), None, Some(-1), Some(10));
+        assert!(limits.rpm.is_none() && limits.tpm.is_none());
+        assert_eq!(limits.tpd, Some(10));
+    }
+}
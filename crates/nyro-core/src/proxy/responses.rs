@@ -0,0 +1,370 @@
+//! Server-side state for the Responses API. Completed responses are stored so
+//! `previous_response_id` can be expanded for any provider, not only OpenAI,
+//! and so clients can fetch or delete them by id.
+
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+use crate::protocol::{Protocol, SseEvent};
+use crate::proxy::handler::{extract_api_key, ingress_error_response};
+use crate::Gateway;
+
+/// A stored response: the full conversation input that produced it plus the
+/// response object returned to the client.
+struct StoredResponse {
+    api_key_id: Option<String>,
+    input: Vec<Value>,
+    response: Value,
+}
+
+impl StoredResponse {
+    /// Input items that continue this conversation: everything sent so far
+    /// followed by what the model answered.
+    fn conversation(self) -> Vec<Value> {
+        let mut items = self.input;
+        if let Some(Value::Array(output)) = self.response.get("output") {
+            items.extend(output.iter().cloned());
+        }
+        items
+    }
+}
+
+/// Persists the response to a `/v1/responses` request once it completes.
+#[derive(Clone)]
+pub(crate) struct ResponseRecorder {
+    db: SqlitePool,
+    api_key_id: Option<String>,
+    input: Vec<Value>,
+}
+
+impl ResponseRecorder {
+    /// Expand `previous_response_id` into the stored conversation, rewriting
+    /// `body.input` in place. `api_key_id` is the key the request was admitted
+    /// with, which scopes both the lookup and the stored result. Returns a
+    /// recorder unless the request opted out with `store: false`.
+    pub(crate) async fn prepare(
+        db: &SqlitePool,
+        api_key_id: Option<String>,
+        body: &mut Value,
+    ) -> Result<Option<Self>, Response> {
+        let Some(obj) = body.as_object_mut() else {
+            return Ok(None);
+        };
+
+        let mut input = match obj.get("input") {
+            Some(Value::String(text)) => vec![json!({ "role": "user", "content": text })],
+            Some(Value::Array(items)) => items.clone(),
+            _ => Vec::new(),
+        };
+        if let Some(previous_id) = obj
+            .remove("previous_response_id")
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|id| !id.is_empty())
+        {
+            let previous = load(db, &previous_id)
+                .await
+                .map_err(|e| ingress_error_response(Protocol::ResponsesAPI, 500, &format!("response store error: {e}")))?
+                .filter(|stored| visible_to(stored, api_key_id.as_deref()));
+            let Some(previous) = previous else {
+                return Err(ingress_error_response(
+                    Protocol::ResponsesAPI,
+                    404,
+                    &format!("previous response not found: {previous_id}"),
+                ));
+            };
+            let mut history = previous.conversation();
+            history.append(&mut input);
+            input = history;
+            obj.insert("input".into(), Value::Array(input.clone()));
+        }
+
+        if obj.get("store").and_then(Value::as_bool) == Some(false) {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            db: db.clone(),
+            api_key_id,
+            input,
+        }))
+    }
+
+    pub(crate) async fn save(&self, response: &Value) {
+        let Some(id) = response.get("id").and_then(Value::as_str) else {
+            return;
+        };
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO responses (id, api_key_id, model, input, response) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(&self.api_key_id)
+        .bind(response.get("model").and_then(Value::as_str))
+        .bind(Value::Array(self.input.clone()).to_string())
+        .bind(response.to_string())
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("failed to store response {id}: {e}");
+        }
+    }
+
+    /// Store the final response carried by a `response.completed` stream
+    /// event. Called before the event is forwarded, so a client chaining on
+    /// the id right away finds it.
+    pub(crate) async fn observe(&self, ev: &SseEvent) {
+        if ev.event.as_deref() != Some("response.completed") {
+            return;
+        }
+        if let Ok(mut data) = serde_json::from_str::<Value>(&ev.data)
+            && let Some(response) = data.get_mut("response").map(Value::take)
+        {
+            self.save(&response).await;
+        }
+    }
+}
+
+// ── GET /v1/responses/:id ──
+
+pub async fn get_response(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    match visible_response(&gw, &headers, &id).await {
+        Ok(stored) => Json(stored.response).into_response(),
+        Err(resp) => resp,
+    }
+}
+
+// ── DELETE /v1/responses/:id ──
+
+pub async fn delete_response(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(resp) = visible_response(&gw, &headers, &id).await {
+        return resp;
+    }
+    match sqlx::query("DELETE FROM responses WHERE id = ?")
+        .bind(&id)
+        .execute(&gw.db)
+        .await
+    {
+        Ok(_) => Json(json!({ "id": id, "object": "response", "deleted": true })).into_response(),
+        Err(e) => ingress_error_response(Protocol::ResponsesAPI, 500, &format!("response store error: {e}")),
+    }
+}
+
+async fn visible_response(gw: &Gateway, headers: &HeaderMap, id: &str) -> Result<StoredResponse, Response> {
+    let api_key_id = caller_key_id(&gw.db, headers).await?;
+    load(&gw.db, id)
+        .await
+        .map_err(|e| ingress_error_response(Protocol::ResponsesAPI, 500, &format!("response store error: {e}")))?
+        .filter(|stored| visible_to(stored, api_key_id.as_deref()))
+        .ok_or_else(|| ingress_error_response(Protocol::ResponsesAPI, 404, &format!("response not found: {id}")))
+}
+
+/// Responses created with an API key are only visible to that key; others
+/// answer 404 rather than revealing that the id exists.
+fn visible_to(stored: &StoredResponse, api_key_id: Option<&str>) -> bool {
+    stored.api_key_id.is_none() || stored.api_key_id.as_deref() == api_key_id
+}
+
+/// Owner of responses created on a route without access control: the
+/// caller's key when it is an active Nyro key, anonymous otherwise. Open
+/// routes ignore foreign keys, such as a client forwarding its own upstream
+/// key, just like `authorize_route_access` does.
+pub(crate) async fn open_route_key_id(db: &SqlitePool, headers: &HeaderMap) -> Result<Option<String>, Response> {
+    match extract_api_key(headers) {
+        Some(raw_key) => active_key_id(db, &raw_key).await,
+        None => Ok(None),
+    }
+}
+
+/// The caller's API key id. No key means anonymous; a key that is unknown,
+/// revoked or expired is rejected rather than treated as anonymous.
+async fn caller_key_id(db: &SqlitePool, headers: &HeaderMap) -> Result<Option<String>, Response> {
+    let Some(raw_key) = extract_api_key(headers) else {
+        return Ok(None);
+    };
+    match active_key_id(db, &raw_key).await? {
+        Some(id) => Ok(Some(id)),
+        None => Err(ingress_error_response(Protocol::ResponsesAPI, 401, "invalid api key")),
+    }
+}
+
+/// Id of the active, unexpired API key `raw_key`, if there is one.
+async fn active_key_id(db: &SqlitePool, raw_key: &str) -> Result<Option<String>, Response> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT id FROM api_keys WHERE key = ? AND status = 'active' \
+         AND (expires_at IS NULL OR datetime(expires_at) > datetime('now'))",
+    )
+    .bind(raw_key)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| ingress_error_response(Protocol::ResponsesAPI, 500, &format!("auth db error: {e}")))
+}
+
+async fn load(db: &SqlitePool, id: &str) -> anyhow::Result<Option<StoredResponse>> {
+    let row = sqlx::query_as::<_, (Option<String>, String, String)>(
+        "SELECT api_key_id, input, response FROM responses WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    let Some((api_key_id, input, response)) = row else {
+        return Ok(None);
+    };
+    Ok(Some(StoredResponse {
+        api_key_id,
+        input: serde_json::from_str(&input)?,
+        response: serde_json::from_str(&response)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    async fn pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::migrate(&pool).await.unwrap();
+        let keys = [
+            ("key-a", "sk-a", "active"),
+            ("key-b", "sk-b", "active"),
+            ("key-r", "sk-r", "revoked"),
+        ];
+        for (id, key, status) in keys {
+            sqlx::query("INSERT INTO api_keys (id, key, name, status) VALUES (?, ?, ?, ?)")
+                .bind(id)
+                .bind(key)
+                .bind(id)
+                .bind(status)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    fn bearer(key: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(key) = key {
+            headers.insert("authorization", format!("Bearer {key}").parse().unwrap());
+        }
+        headers
+    }
+
+    /// Store a response with one user turn and one assistant turn.
+    async fn store(db: &SqlitePool, key: Option<&str>, id: &str, user: &str, answer: &str) {
+        let mut body = json!({ "input": user });
+        let key_id = open_route_key_id(db, &bearer(key)).await.unwrap();
+        let recorder = ResponseRecorder::prepare(db, key_id, &mut body)
+            .await
+            .unwrap()
+            .expect("stored by default");
+        recorder
+            .save(&json!({
+                "id": id,
+                "model": "m",
+                "output": [{ "type": "message", "role": "assistant", "content": answer }],
+            }))
+            .await;
+    }
+
+    async fn chain(db: &SqlitePool, key: Option<&str>, previous: &str) -> Result<Value, StatusCode> {
+        let mut body = json!({ "previous_response_id": previous, "input": "next" });
+        let key_id = open_route_key_id(db, &bearer(key)).await.map_err(|resp| resp.status())?;
+        ResponseRecorder::prepare(db, key_id, &mut body)
+            .await
+            .map(|_| body)
+            .map_err(|resp| resp.status())
+    }
+
+    #[test]
+    fn conversation_is_input_then_output() {
+        let stored = StoredResponse {
+            api_key_id: None,
+            input: vec![json!({ "role": "user", "content": "hi" })],
+            response: json!({ "output": [{ "role": "assistant", "content": "hello" }] }),
+        };
+        assert_eq!(
+            stored.conversation(),
+            vec![
+                json!({ "role": "user", "content": "hi" }),
+                json!({ "role": "assistant", "content": "hello" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn previous_response_expands_history_in_order() {
+        let db = pool().await;
+        store(&db, None, "resp_1", "one", "two").await;
+
+        let mut body = json!({ "previous_response_id": "resp_1", "input": "three" });
+        let recorder = ResponseRecorder::prepare(&db, None, &mut body)
+            .await
+            .unwrap()
+            .unwrap();
+        let expected = json!([
+            { "role": "user", "content": "one" },
+            { "type": "message", "role": "assistant", "content": "two" },
+            { "role": "user", "content": "three" },
+        ]);
+        assert_eq!(body, json!({ "input": expected }));
+        assert_eq!(Value::Array(recorder.input), expected);
+    }
+
+    #[tokio::test]
+    async fn store_false_expands_but_records_nothing() {
+        let db = pool().await;
+        store(&db, None, "resp_1", "one", "two").await;
+
+        let mut body = json!({ "previous_response_id": "resp_1", "input": "three", "store": false });
+        let recorder = ResponseRecorder::prepare(&db, None, &mut body).await.unwrap();
+        assert!(recorder.is_none());
+        assert_eq!(body["input"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn responses_are_scoped_to_the_key_that_created_them() {
+        let db = pool().await;
+        store(&db, Some("sk-a"), "resp_a", "one", "two").await;
+        store(&db, None, "resp_open", "one", "two").await;
+
+        assert!(chain(&db, Some("sk-a"), "resp_a").await.is_ok());
+        assert_eq!(chain(&db, Some("sk-b"), "resp_a").await.unwrap_err(), StatusCode::NOT_FOUND);
+        assert_eq!(chain(&db, None, "resp_a").await.unwrap_err(), StatusCode::NOT_FOUND);
+        assert!(chain(&db, Some("sk-b"), "resp_open").await.is_ok());
+
+        for key in ["sk-unknown", "sk-r"] {
+            let err = caller_key_id(&db, &bearer(Some(key))).await.unwrap_err();
+            assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn open_routes_treat_foreign_keys_as_anonymous() {
+        let db = pool().await;
+        store(&db, Some("sk-a"), "resp_a", "one", "two").await;
+        // e.g. Codex forwarding its own OPENAI_API_KEY.
+        store(&db, Some("sk-proj-upstream"), "resp_foreign", "one", "two").await;
+
+        assert!(chain(&db, None, "resp_foreign").await.is_ok());
+        assert!(chain(&db, Some("sk-proj-upstream"), "resp_foreign").await.is_ok());
+        assert_eq!(
+            chain(&db, Some("sk-proj-upstream"), "resp_a").await.unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+    }
+}
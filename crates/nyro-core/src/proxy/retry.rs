@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::HeaderMap;
+
+use crate::db::models::Provider;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 500;
+const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+
+/// Same-provider retry policy applied by `ProxyClient` before a response is
+/// handed back to the pipeline (and so before any byte reaches the client).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total sends including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// Cap for backoff. An upstream asking to wait longer than this is not
+    /// retried, so the route can fall back instead of stalling.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Policy from the provider's `retry_*` columns; unset values use defaults.
+    pub fn for_provider(provider: &Provider) -> Self {
+        let default = Self::default();
+        let ms = |v: Option<i32>, fallback: Duration| {
+            v.filter(|v| *v >= 0)
+                .map(|v| Duration::from_millis(v as u64))
+                .unwrap_or(fallback)
+        };
+        Self {
+            max_attempts: provider
+                .retry_max_attempts
+                .map(|v| v.max(1) as u32)
+                .unwrap_or(default.max_attempts),
+            base_delay: ms(provider.retry_base_delay_ms, default.base_delay),
+            max_delay: ms(provider.retry_max_delay_ms, default.max_delay),
+        }
+    }
+
+    pub fn is_retryable_status(status: u16) -> bool {
+        matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+    }
+
+    /// Connection-level failures where the request never produced a response.
+    pub fn is_retryable_error(err: &reqwest::Error) -> bool {
+        err.is_connect() || (err.is_request() && !err.is_timeout())
+    }
+
+    /// Exponential backoff for the given retry (0-based), jittered between half
+    /// and the full step so concurrent clients do not retry in lockstep.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let ms = ceiling.as_millis() as u64;
+        if ms == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(ms / 2..=ms))
+    }
+
+    /// Delay before the next send after a retryable response, or `None` when
+    /// the upstream asks for a longer wait than `max_delay`.
+    pub fn delay_after(&self, retry: u32, headers: &HeaderMap) -> Option<Duration> {
+        match upstream_retry_hint(headers, Utc::now()) {
+            Some(hint) if hint > self.max_delay => None,
+            Some(hint) => Some(hint),
+            None => Some(self.backoff(retry)),
+        }
+    }
+}
+
+/// How long the upstream asked us to wait, from `retry-after-ms`,
+/// `Retry-After` (seconds or HTTP date) or, for exhausted Anthropic limits,
+/// the latest `anthropic-ratelimit-*-reset` timestamp.
+pub fn upstream_retry_hint(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return Some(Duration::from_millis(ms.max(0.0) as u64));
+    }
+    if let Some(value) = header("retry-after") {
+        if let Ok(secs) = value.parse::<f64>() {
+            return Some(Duration::from_millis((secs.max(0.0) * 1000.0) as u64));
+        }
+        if let Ok(at) = DateTime::parse_from_rfc2822(value) {
+            return Some(until(at.with_timezone(&Utc), now));
+        }
+    }
+
+    ["requests", "tokens", "input-tokens", "output-tokens"]
+        .iter()
+        .filter(|kind| header(&format!("anthropic-ratelimit-{kind}-remaining")) == Some("0"))
+        .filter_map(|kind| header(&format!("anthropic-ratelimit-{kind}-reset")))
+        .filter_map(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|at| until(at.with_timezone(&Utc), now))
+        .max()
+}
+
+fn until(at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (at - now).to_std().unwrap_or(Duration::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, HeaderValue::from_str(v).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_date() {
+        let now = Utc::now();
+        let hint = upstream_retry_hint(&headers(&[("retry-after", "2")]), now);
+        assert_eq!(hint, Some(Duration::from_secs(2)));
+
+        let at = (now + chrono::Duration::seconds(5)).to_rfc2822();
+        let hint = upstream_retry_hint(&headers(&[("retry-after", &at)]), now).unwrap();
+        assert!(hint <= Duration::from_secs(5) && hint >= Duration::from_secs(4));
+    }
+
+    #[test]
+    fn uses_exhausted_anthropic_reset() {
+        let now = Utc::now();
+        let reset = (now + chrono::Duration::seconds(3)).to_rfc3339();
+        let later = (now + chrono::Duration::seconds(40)).to_rfc3339();
+        let map = headers(&[
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", &reset),
+            ("anthropic-ratelimit-tokens-remaining", "1200"),
+            ("anthropic-ratelimit-tokens-reset", &later),
+        ]);
+        let hint = upstream_retry_hint(&map, now).unwrap();
+        assert!(hint <= Duration::from_secs(3) && hint >= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn long_upstream_wait_is_not_retried() {
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(10),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay_after(0, &headers(&[("retry-after", "60")])), None);
+        assert_eq!(
+            policy.delay_after(0, &headers(&[("retry-after", "1")])),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+        let first = policy.backoff(0);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        for _ in 0..20 {
+            assert!(policy.backoff(6) <= Duration::from_millis(300));
+        }
+        assert!(RetryPolicy::is_retryable_status(429));
+        assert!(!RetryPolicy::is_retryable_status(501));
+    }
+}
@@ -4,18 +4,29 @@ use axum::http::{HeaderValue, Method, header};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
 
-use super::handler;
+use super::{handler, models, responses};
 use crate::Gateway;
 
 pub fn create_router(gateway: Gateway) -> Router {
     let router = Router::new()
         .route("/v1/chat/completions", post(handler::openai_proxy))
         .route("/v1/responses", post(handler::responses_proxy))
+        .route(
+            "/v1/responses/:id",
+            get(responses::get_response).delete(responses::delete_response),
+        )
+        .route("/v1/embeddings", post(handler::openai_embeddings))
         .route("/v1/messages", post(handler::anthropic_proxy))
+        .route("/v1/messages/count_tokens", post(handler::anthropic_count_tokens))
+        .route("/v1/models", get(models::list_models))
+        .route("/v1beta/models", get(models::gemini_list_models))
         .route(
             "/v1beta/models/:model_action",
             post(handler::gemini_proxy),
         )
+        .route("/api/chat", post(handler::ollama_chat))
+        .route("/api/generate", post(handler::ollama_generate))
+        .route("/api/tags", get(models::ollama_list_models))
         .route("/health", get(health));
 
     let cors = build_proxy_cors_layer(&gateway.config.proxy_cors_origins, gateway.config.proxy_port);
@@ -39,13 +50,14 @@ fn build_proxy_cors_layer(origins: &[String], proxy_port: u16) -> CorsLayer {
 
     CorsLayer::new()
         .allow_origin(parse_allow_origin(&source_origins))
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
         .allow_headers([
             header::AUTHORIZATION,
             header::CONTENT_TYPE,
             header::ACCEPT,
             header::HeaderName::from_static("x-api-key"),
             header::HeaderName::from_static("anthropic-version"),
+            header::HeaderName::from_static("anthropic-beta"),
         ])
 }
 
@@ -0,0 +1,272 @@
+//! Google Vertex AI service-account auth: OAuth2 access tokens minted with
+//! the JWT-bearer grant and cached until shortly before they expire.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use ring::rand::SystemRandom;
+use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const JWT_BEARER_GRANT: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+/// Tokens are refreshed this long before Google says they expire.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+/// Upper bound on one token request, well under the proxy's upstream timeout.
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The fields of a downloaded service-account key file that token minting
+/// needs. Stored as the provider's (encrypted) API key.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccount {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// Token endpoint; overridable for private Google endpoints and tests.
+    #[serde(default)]
+    pub token_uri: Option<String>,
+}
+
+impl ServiceAccount {
+    pub fn parse(json: &str) -> Result<Self> {
+        serde_json::from_str(json.trim()).context("invalid service account JSON")
+    }
+
+    fn token_uri(&self) -> &str {
+        self.token_uri
+            .as_deref()
+            .map(str::trim)
+            .filter(|uri| !uri.is_empty())
+            .unwrap_or(DEFAULT_TOKEN_URI)
+    }
+
+    /// RS256-signed JWT asserting this account, valid for one hour from `now`
+    /// (seconds since the epoch).
+    pub fn assertion(&self, now: i64) -> Result<String> {
+        let mut header = json!({ "alg": "RS256", "typ": "JWT" });
+        if let Some(kid) = &self.private_key_id {
+            header["kid"] = json!(kid);
+        }
+        let claims = json!({
+            "iss": self.client_email,
+            "scope": CLOUD_PLATFORM_SCOPE,
+            "aud": self.token_uri(),
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string()),
+        );
+
+        let key_pair = RsaKeyPair::from_pkcs8(&pem_to_der(&self.private_key)?)
+            .map_err(|e| anyhow::anyhow!("invalid service account private key: {e}"))?;
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                signing_input.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|_| anyhow::anyhow!("failed to sign service account assertion"))?;
+        Ok(format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+fn pem_to_der(pem: &str) -> Result<Vec<u8>> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD
+        .decode(body)
+        .context("service account private key is not valid PEM")
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default = "default_expires_in")]
+    expires_in: u64,
+}
+
+fn default_expires_in() -> u64 {
+    3600
+}
+
+struct CachedToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+/// Access tokens keyed by service account and token endpoint.
+#[derive(Default)]
+pub struct TokenCache {
+    // One slot per account; a slot's lock is held across its token request so
+    // concurrent callers for that account share one mint without blocking
+    // other accounts.
+    entries: std::sync::Mutex<HashMap<String, Arc<Mutex<Option<CachedToken>>>>>,
+}
+
+impl std::fmt::Debug for TokenCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenCache").finish_non_exhaustive()
+    }
+}
+
+impl TokenCache {
+    pub async fn access_token(
+        &self,
+        http: &reqwest::Client,
+        account: &ServiceAccount,
+    ) -> Result<String> {
+        let key = format!("{}|{}", account.client_email, account.token_uri());
+        let slot = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_default()
+            .clone();
+        let mut slot = slot.lock().await;
+        if let Some(cached) = slot.as_ref()
+            && Instant::now() < cached.refresh_at
+        {
+            return Ok(cached.access_token.clone());
+        }
+
+        let assertion = account.assertion(chrono::Utc::now().timestamp())?;
+        let resp = http
+            .post(account.token_uri())
+            .form(&[("grant_type", JWT_BEARER_GRANT), ("assertion", &assertion)])
+            .timeout(TOKEN_REQUEST_TIMEOUT)
+            .send()
+            .await
+            .context("service account token request failed")?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("service account token request returned {status}: {body}");
+        }
+        let token: TokenResponse = resp.json().await?;
+
+        let lifetime = Duration::from_secs(token.expires_in).saturating_sub(EXPIRY_MARGIN);
+        *slot = Some(CachedToken {
+            access_token: token.access_token.clone(),
+            refresh_at: Instant::now() + lifetime,
+        });
+        Ok(token.access_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{RSA_PKCS1_2048_8192_SHA256, UnparsedPublicKey};
+
+    const TEST_KEY: &str = include_str!("../../tests/fixtures/vertex_test_key.pem");
+
+    fn account(client_email: &str, token_uri: &str) -> ServiceAccount {
+        ServiceAccount::parse(
+            &json!({
+                "client_email": client_email,
+                "private_key": TEST_KEY,
+                "private_key_id": "key-1",
+                "token_uri": token_uri,
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn signs_jwt_bearer_assertion() {
+        let account = account(
+            "nyro@test-project.iam.gserviceaccount.com",
+            "http://127.0.0.1:9/token",
+        );
+        let jwt = account.assertion(1_700_000_000).unwrap();
+
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        let header: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[0]).unwrap()).unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        assert_eq!(header["alg"], "RS256");
+        assert_eq!(header["kid"], "key-1");
+        assert_eq!(claims["aud"], "http://127.0.0.1:9/token");
+        assert_eq!(claims["exp"], 1_700_003_600);
+
+        let key_pair = RsaKeyPair::from_pkcs8(&pem_to_der(TEST_KEY).unwrap()).unwrap();
+        UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, key_pair.public().as_ref())
+            .verify(
+                format!("{}.{}", parts[0], parts[1]).as_bytes(),
+                &URL_SAFE_NO_PAD.decode(parts[2]).unwrap(),
+            )
+            .expect("signature verifies against the key's public half");
+    }
+
+    #[tokio::test]
+    async fn slow_token_endpoint_does_not_block_other_accounts() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        // Accepts connections and never answers.
+        let stalled = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stalled_uri = format!("http://{}/token", stalled.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = stalled.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let healthy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let healthy_uri = format!("http://{}/token", healthy.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = healthy.accept().await {
+                let mut buf = vec![0; 8192];
+                let _ = socket.read(&mut buf).await;
+                let body = r#"{"access_token":"ya29.ok","expires_in":3600}"#;
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(resp.as_bytes()).await;
+            }
+        });
+
+        let cache = Arc::new(TokenCache::default());
+        let http = reqwest::Client::new();
+        let stuck = {
+            let (cache, http) = (cache.clone(), http.clone());
+            let account = account("slow@test.iam.gserviceaccount.com", &stalled_uri);
+            tokio::spawn(async move { cache.access_token(&http, &account).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let token = tokio::time::timeout(
+            Duration::from_secs(2),
+            cache.access_token(&http, &account("ok@test.iam.gserviceaccount.com", &healthy_uri)),
+        )
+        .await
+        .expect("another account's mint must not wait on the stalled one")
+        .unwrap();
+        assert_eq!(token, "ya29.ok");
+        stuck.abort();
+    }
+}
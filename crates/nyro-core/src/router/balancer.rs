@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use rand::Rng;
+
+use crate::db::models::RouteTarget;
+
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+/// Floor for the sample recorded when an attempt fails, so a target that keeps
+/// erroring quickly never looks faster than one that answers.
+const LATENCY_FAILURE_PENALTY_MS: f64 = 30_000.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BalanceStrategy {
+    #[default]
+    RoundRobin,
+    Weighted,
+    LeastLatency,
+}
+
+impl fmt::Display for BalanceStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RoundRobin => write!(f, "round_robin"),
+            Self::Weighted => write!(f, "weighted"),
+            Self::LeastLatency => write!(f, "least_latency"),
+        }
+    }
+}
+
+impl FromStr for BalanceStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "round_robin" | "roundrobin" => Ok(Self::RoundRobin),
+            "weighted" | "weighted_random" => Ok(Self::Weighted),
+            "least_latency" | "latency" => Ok(Self::LeastLatency),
+            _ => anyhow::bail!("unsupported balance strategy: {s}"),
+        }
+    }
+}
+
+/// Passive latency samples per `provider_id:model`, fed by proxy attempts.
+/// Failed attempts count as a penalty sample.
+#[derive(Default)]
+pub struct LatencyTracker {
+    samples: Mutex<HashMap<String, f64>>,
+}
+
+impl LatencyTracker {
+    pub fn record(&self, provider_id: &str, model: &str, duration_ms: f64) {
+        let key = format!("{provider_id}:{model}");
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples
+            .entry(key)
+            .and_modify(|avg| *avg = LATENCY_EWMA_ALPHA * duration_ms + (1.0 - LATENCY_EWMA_ALPHA) * *avg)
+            .or_insert(duration_ms);
+    }
+
+    pub fn record_failure(&self, provider_id: &str, model: &str, duration_ms: f64) {
+        self.record(provider_id, model, duration_ms.max(LATENCY_FAILURE_PENALTY_MS));
+    }
+
+    pub fn get(&self, provider_id: &str, model: &str) -> Option<f64> {
+        let key = format!("{provider_id}:{model}");
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.get(&key).copied()
+    }
+}
+
+/// Order a route's targets for one request. The first entry is the selected
+/// target; the rest are tried in order if it fails before responding.
+///
+/// `targets` must already be sorted by priority; `cursor` is the route's
+/// round-robin counter value for this request.
+pub fn order_targets(
+    strategy: BalanceStrategy,
+    targets: &[RouteTarget],
+    cursor: usize,
+    latency: &LatencyTracker,
+) -> Vec<RouteTarget> {
+    if targets.len() <= 1 {
+        return targets.to_vec();
+    }
+
+    match strategy {
+        BalanceStrategy::RoundRobin => {
+            let start = cursor % targets.len();
+            targets[start..]
+                .iter()
+                .chain(targets[..start].iter())
+                .cloned()
+                .collect()
+        }
+        BalanceStrategy::Weighted => weighted_shuffle(targets, &mut rand::thread_rng()),
+        BalanceStrategy::LeastLatency => {
+            // Targets without samples sort first so each one gets measured;
+            // failing targets carry a penalty sample and fall behind.
+            let mut ordered = targets.to_vec();
+            ordered.sort_by(|a, b| {
+                let la = latency.get(&a.provider_id, &a.model).unwrap_or(0.0);
+                let lb = latency.get(&b.provider_id, &b.model).unwrap_or(0.0);
+                la.total_cmp(&lb)
+            });
+            ordered
+        }
+    }
+}
+
+/// Weighted random ordering without replacement. Zero-weight targets are kept
+/// at the end as last-resort candidates.
+fn weighted_shuffle(targets: &[RouteTarget], rng: &mut impl Rng) -> Vec<RouteTarget> {
+    let mut pool: Vec<&RouteTarget> = targets.iter().filter(|t| t.weight > 0).collect();
+    let mut ordered = Vec::with_capacity(targets.len());
+
+    while !pool.is_empty() {
+        let total: i64 = pool.iter().map(|t| i64::from(t.weight)).sum();
+        let mut pick = rng.gen_range(0..total);
+        let idx = pool
+            .iter()
+            .position(|t| {
+                if pick < i64::from(t.weight) {
+                    true
+                } else {
+                    pick -= i64::from(t.weight);
+                    false
+                }
+            })
+            .unwrap_or(0);
+        ordered.push(pool.remove(idx).clone());
+    }
+
+    ordered.extend(targets.iter().filter(|t| t.weight <= 0).cloned());
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(provider_id: &str, weight: i32) -> RouteTarget {
+        RouteTarget {
+            id: provider_id.to_string(),
+            route_id: "r".to_string(),
+            provider_id: provider_id.to_string(),
+            model: "m".to_string(),
+            weight,
+            priority: 0,
+            is_active: true,
+            created_at: String::new(),
+        }
+    }
+
+    fn providers(targets: &[RouteTarget]) -> Vec<&str> {
+        targets.iter().map(|t| t.provider_id.as_str()).collect()
+    }
+
+    #[test]
+    fn round_robin_rotates_start() {
+        let targets = vec![target("a", 1), target("b", 1), target("c", 1)];
+        let latency = LatencyTracker::default();
+        let order = order_targets(BalanceStrategy::RoundRobin, &targets, 4, &latency);
+        assert_eq!(providers(&order), vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn weighted_keeps_zero_weight_last() {
+        let targets = vec![target("zero", 0), target("a", 7), target("b", 3)];
+        let latency = LatencyTracker::default();
+        for _ in 0..20 {
+            let order = order_targets(BalanceStrategy::Weighted, &targets, 0, &latency);
+            assert_eq!(order.len(), 3);
+            assert_eq!(order[2].provider_id, "zero");
+        }
+    }
+
+    #[test]
+    fn least_latency_prefers_unmeasured_then_fastest() {
+        let targets = vec![target("slow", 1), target("fast", 1), target("new", 1)];
+        let latency = LatencyTracker::default();
+        latency.record("slow", "m", 900.0);
+        latency.record("fast", "m", 120.0);
+        let order = order_targets(BalanceStrategy::LeastLatency, &targets, 0, &latency);
+        assert_eq!(providers(&order), vec!["new", "fast", "slow"]);
+    }
+
+    #[test]
+    fn least_latency_demotes_failing_target() {
+        let targets = vec![target("broken", 1), target("ok", 1)];
+        let latency = LatencyTracker::default();
+        for _ in 0..3 {
+            let order = order_targets(BalanceStrategy::LeastLatency, &targets, 0, &latency);
+            if order[0].provider_id == "broken" {
+                latency.record_failure("broken", "m", 15.0);
+            } else {
+                latency.record("ok", "m", 800.0);
+            }
+        }
+        let order = order_targets(BalanceStrategy::LeastLatency, &targets, 0, &latency);
+        assert_eq!(providers(&order), vec!["ok", "broken"]);
+    }
+
+    #[test]
+    fn strategy_parses_aliases() {
+        assert_eq!("".parse::<BalanceStrategy>().unwrap(), BalanceStrategy::RoundRobin);
+        assert_eq!("weighted_random".parse::<BalanceStrategy>().unwrap(), BalanceStrategy::Weighted);
+        assert!("fastest".parse::<BalanceStrategy>().is_err());
+    }
+}
@@ -1,9 +1,70 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use regex::Regex;
 use sqlx::SqlitePool;
 
-use crate::db::models::Route;
+use crate::db::models::{Route, RouteTarget};
+
+/// Prefix marking a `virtual_model` as a regular expression, e.g. `re:^gpt-4o(-mini)?$`.
+pub const REGEX_PREFIX: &str = "re:";
+
+/// Compiled form of a route's `virtual_model`.
+#[derive(Debug, Clone)]
+pub enum ModelPattern {
+    Exact(String),
+    Glob(String),
+    Regex(Regex),
+}
+
+impl ModelPattern {
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        let raw = raw.trim();
+        if let Some(expr) = raw.strip_prefix(REGEX_PREFIX) {
+            let re = Regex::new(expr.trim())
+                .map_err(|e| anyhow::anyhow!("invalid virtual_model regex: {e}"))?;
+            return Ok(Self::Regex(re));
+        }
+        if raw.contains(['*', '?', '[', '{']) {
+            return Ok(Self::Glob(raw.to_string()));
+        }
+        Ok(Self::Exact(raw.to_string()))
+    }
+
+    pub fn matches(&self, model: &str) -> bool {
+        match self {
+            Self::Exact(v) => v == model,
+            Self::Glob(pattern) => glob_match::glob_match(pattern, model),
+            Self::Regex(re) => re.is_match(model),
+        }
+    }
+
+    /// Upstream model for a request matched by this pattern. `*` (or an empty
+    /// target) passes the requested name through; regex routes may reference
+    /// capture groups such as `$1` or `${name}`.
+    pub fn resolve_target(&self, target_model: &str, request_model: &str) -> String {
+        if target_model.is_empty() || target_model == "*" {
+            return request_model.to_string();
+        }
+        if let Self::Regex(re) = self
+            && target_model.contains('$')
+            && let Some(caps) = re.captures(request_model)
+        {
+            let mut out = String::new();
+            caps.expand(target_model, &mut out);
+            return out;
+        }
+        target_model.to_string()
+    }
+}
 
 pub struct RouteCache {
+    /// Active routes ordered by `priority` (ascending), then creation time.
     pub routes: Vec<Route>,
+    patterns: HashMap<String, ModelPattern>,
+    /// Active load-balancing targets per route id, sorted by priority.
+    pub targets: HashMap<String, Vec<RouteTarget>>,
+    cursors: HashMap<String, AtomicUsize>,
 }
 
 impl RouteCache {
@@ -13,26 +74,182 @@ impl RouteCache {
                 id, name, COALESCE(ingress_protocol, 'openai') AS ingress_protocol,
                 COALESCE(NULLIF(virtual_model, ''), match_pattern) AS virtual_model,
                 target_provider, target_model,
+                fallback_provider, fallback_model,
+                COALESCE(strategy, 'round_robin') AS strategy,
+                COALESCE(priority, 0) AS priority,
                 COALESCE(access_control, 0) AS access_control,
                 is_active,
                 created_at
             FROM routes
-            WHERE is_active = 1"#,
+            WHERE is_active = 1
+            ORDER BY COALESCE(priority, 0) ASC, created_at ASC, id ASC"#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let rows: Vec<RouteTarget> = sqlx::query_as::<_, RouteTarget>(
+            r#"SELECT id, route_id, provider_id, model, weight, priority, is_active, created_at
+            FROM route_targets
+            WHERE is_active = 1
+            ORDER BY priority ASC, created_at ASC"#,
         )
         .fetch_all(pool)
         .await?;
 
-        Ok(Self { routes })
+        let mut targets: HashMap<String, Vec<RouteTarget>> = HashMap::new();
+        for row in rows {
+            targets.entry(row.route_id.clone()).or_default().push(row);
+        }
+
+        let cursors = routes
+            .iter()
+            .map(|r| (r.id.clone(), AtomicUsize::new(0)))
+            .collect();
+
+        let patterns = routes
+            .iter()
+            .map(|r| {
+                let pattern = ModelPattern::parse(&r.virtual_model).unwrap_or_else(|e| {
+                    tracing::warn!("route {} has an invalid pattern, matching exactly: {e}", r.name);
+                    ModelPattern::Exact(r.virtual_model.clone())
+                });
+                (r.id.clone(), pattern)
+            })
+            .collect();
+
+        Ok(Self {
+            routes,
+            patterns,
+            targets,
+            cursors,
+        })
     }
 
     pub async fn reload(&mut self, pool: &SqlitePool) -> anyhow::Result<()> {
         *self = Self::load(pool).await?;
         Ok(())
     }
+
+    pub(crate) fn pattern(&self, route_id: &str) -> Option<&ModelPattern> {
+        self.patterns.get(route_id)
+    }
+
+    /// Advance and return the round-robin counter for a route.
+    pub(crate) fn next_cursor(&self, route_id: &str) -> usize {
+        self.cursors
+            .get(route_id)
+            .map(|c| c.fetch_add(1, Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+}
+
+/// Exact `virtual_model` matches win; otherwise the first pattern route in
+/// priority order that matches the requested model.
+pub fn match_route<'a>(
+    routes: &'a [Route],
+    patterns: impl Fn(&Route) -> Option<&'a ModelPattern>,
+    ingress_protocol: &str,
+    model: &str,
+) -> Option<&'a Route> {
+    let candidates = || routes.iter().filter(|route| route.ingress_protocol == ingress_protocol);
+
+    candidates()
+        .find(|route| route.virtual_model == model)
+        .or_else(|| {
+            candidates().find(|route| {
+                patterns(route).is_some_and(|p| !matches!(p, ModelPattern::Exact(_)) && p.matches(model))
+            })
+        })
 }
 
-pub fn match_route<'a>(routes: &'a [Route], ingress_protocol: &str, model: &str) -> Option<&'a Route> {
-    routes
-        .iter()
-        .find(|route| route.ingress_protocol == ingress_protocol && route.virtual_model == model)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(id: &str, virtual_model: &str) -> Route {
+        Route {
+            id: id.to_string(),
+            name: id.to_string(),
+            ingress_protocol: "openai".to_string(),
+            virtual_model: virtual_model.to_string(),
+            target_provider: "p".to_string(),
+            target_model: "*".to_string(),
+            fallback_provider: None,
+            fallback_model: None,
+            strategy: "round_robin".to_string(),
+            priority: 0,
+            access_control: false,
+            is_active: true,
+            created_at: String::new(),
+        }
+    }
+
+    fn find<'a>(routes: &'a [Route], patterns: &'a HashMap<String, ModelPattern>, model: &str) -> Option<&'a str> {
+        match_route(routes, |r| patterns.get(&r.id), "openai", model).map(|r| r.id.as_str())
+    }
+
+    fn compile(routes: &[Route]) -> HashMap<String, ModelPattern> {
+        routes
+            .iter()
+            .map(|r| (r.id.clone(), ModelPattern::parse(&r.virtual_model).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn exact_match_beats_earlier_pattern() {
+        let routes = vec![route("glob", "claude-*"), route("exact", "claude-sonnet-4")];
+        let patterns = compile(&routes);
+        assert_eq!(find(&routes, &patterns, "claude-sonnet-4"), Some("exact"));
+        assert_eq!(find(&routes, &patterns, "claude-opus-4"), Some("glob"));
+        assert_eq!(find(&routes, &patterns, "gpt-4o"), None);
+    }
+
+    #[test]
+    fn patterns_follow_route_order() {
+        let routes = vec![route("mini", "gpt-4o-mini*"), route("any", "gpt-4o*")];
+        let patterns = compile(&routes);
+        assert_eq!(find(&routes, &patterns, "gpt-4o-mini-2024"), Some("mini"));
+        assert_eq!(find(&routes, &patterns, "gpt-4o-2024"), Some("any"));
+    }
+
+    #[test]
+    fn regex_pattern_and_capture_expansion() {
+        let pattern = ModelPattern::parse("re:^local/(?<name>.+)$").unwrap();
+        assert!(pattern.matches("local/qwen3"));
+        assert!(!pattern.matches("qwen3"));
+        assert_eq!(pattern.resolve_target("${name}:latest", "local/qwen3"), "qwen3:latest");
+        assert_eq!(pattern.resolve_target("*", "local/qwen3"), "local/qwen3");
+        assert!(ModelPattern::parse("re:(").is_err());
+    }
+
+    #[test]
+    fn listing_target_resolves_pass_through_and_first_weighted_target() {
+        let routes = vec![route("pass", "gpt-4o"), route("multi", "claude-sonnet-4")];
+        let target = |provider_id: &str, model: &str, weight: i32| RouteTarget {
+            id: format!("{provider_id}-{model}"),
+            route_id: "multi".to_string(),
+            provider_id: provider_id.to_string(),
+            model: model.to_string(),
+            weight,
+            priority: 0,
+            is_active: true,
+            created_at: String::new(),
+        };
+        let cache = RouteCache {
+            patterns: compile(&routes),
+            targets: HashMap::from([(
+                "multi".to_string(),
+                vec![target("spare", "claude-3-haiku", 0), target("bedrock", "claude-sonnet-4-v1", 3)],
+            )]),
+            cursors: HashMap::from([("multi".to_string(), AtomicUsize::new(0))]),
+            routes,
+        };
+        assert_eq!(cache.listing_target(&cache.routes[0]), ("p".to_string(), "gpt-4o".to_string()));
+        assert_eq!(
+            cache.listing_target(&cache.routes[1]),
+            ("bedrock".to_string(), "claude-sonnet-4-v1".to_string())
+        );
+        cache.primary_target(&cache.routes[1], "claude-sonnet-4");
+        assert_eq!(cache.next_cursor("multi"), 0, "peeking must not advance round-robin");
+    }
 }
@@ -1,11 +1,112 @@
+mod balancer;
 mod matcher;
 
-pub use matcher::RouteCache;
+pub use balancer::{BalanceStrategy, LatencyTracker};
+pub use matcher::{ModelPattern, RouteCache, REGEX_PREFIX};
 
-use crate::db::models::Route;
+use crate::db::models::{Route, RouteTarget};
 
 impl RouteCache {
     pub fn match_route(&self, ingress_protocol: &str, model: &str) -> Option<&Route> {
-        matcher::match_route(&self.routes, ingress_protocol, model)
+        matcher::match_route(
+            &self.routes,
+            |route| self.pattern(&route.id),
+            ingress_protocol,
+            model,
+        )
+    }
+
+    /// Routes to advertise on the models endpoints of an ingress protocol:
+    /// exact virtual models only (patterns have no concrete name), first
+    /// route per name in priority order.
+    pub fn listed_routes(&self, ingress_protocol: &str) -> Vec<&Route> {
+        let mut seen = std::collections::HashSet::new();
+        self.routes
+            .iter()
+            .filter(|route| route.ingress_protocol == ingress_protocol)
+            .filter(|route| matches!(self.pattern(&route.id), Some(ModelPattern::Exact(_)) | None))
+            .filter(|route| seen.insert(route.virtual_model.clone()))
+            .collect()
+    }
+
+    /// Targets to try for a matched route, selected one first. Routes without
+    /// `route_targets` rows fall back to their single `target_provider`.
+    pub fn select_targets(&self, route: &Route, latency: &LatencyTracker) -> Vec<RouteTarget> {
+        match self.targets.get(&route.id) {
+            Some(targets) if !targets.is_empty() => {
+                let strategy = route.strategy.parse().unwrap_or_default();
+                balancer::order_targets(strategy, targets, self.next_cursor(&route.id), latency)
+            }
+            _ => vec![RouteTarget::primary(route)],
+        }
+    }
+
+    /// The `(provider_id, upstream_model)` a listed route serves its virtual
+    /// model from. See `primary_target`.
+    pub fn listing_target(&self, route: &Route) -> (String, String) {
+        self.primary_target(route, &route.virtual_model)
+    }
+
+    /// The `(provider_id, upstream_model)` that would serve `request_model`
+    /// without balancing: the first target by priority (skipping zero-weight
+    /// last-resort targets) or the route's single target, with `*` resolved
+    /// to the requested model. Unlike `plan_attempts` this leaves round-robin
+    /// state untouched.
+    pub fn primary_target(&self, route: &Route, request_model: &str) -> (String, String) {
+        let target = self
+            .targets
+            .get(&route.id)
+            .and_then(|targets| targets.iter().find(|t| t.weight > 0).or(targets.first()))
+            .cloned()
+            .unwrap_or_else(|| RouteTarget::primary(route));
+        let pattern = self
+            .pattern(&route.id)
+            .cloned()
+            .unwrap_or_else(|| ModelPattern::Exact(route.virtual_model.clone()));
+        let model = pattern.resolve_target(&target.model, request_model);
+        (target.provider_id, model)
+    }
+
+    /// Ordered `(provider_id, upstream_model)` pairs to attempt for a request:
+    /// the balanced targets in selection order, then the route fallback when
+    /// one is configured. Duplicates are dropped.
+    pub fn plan_attempts(
+        &self,
+        route: &Route,
+        request_model: &str,
+        latency: &LatencyTracker,
+    ) -> Vec<(String, String)> {
+        let resolve = |target_model: &str| match self.pattern(&route.id) {
+            Some(pattern) => pattern.resolve_target(target_model, request_model),
+            None => ModelPattern::Exact(String::new()).resolve_target(target_model, request_model),
+        };
+
+        let mut attempts: Vec<(String, String)> = Vec::new();
+        let mut push = |pair: (String, String)| {
+            if !attempts.contains(&pair) {
+                attempts.push(pair);
+            }
+        };
+
+        for target in self.select_targets(route, latency) {
+            let model = resolve(&target.model);
+            push((target.provider_id, model));
+        }
+
+        if route.fallback_provider.is_some() || route.fallback_model.is_some() {
+            let provider = route
+                .fallback_provider
+                .clone()
+                .unwrap_or_else(|| route.target_provider.clone());
+            let model = resolve(
+                route
+                    .fallback_model
+                    .as_deref()
+                    .unwrap_or(&route.target_model),
+            );
+            push((provider, model));
+        }
+
+        attempts
     }
 }
@@ -1,20 +1,33 @@
-use nyro_core::protocol::anthropic::stream::AnthropicResponseFormatter;
+use nyro_core::protocol::anthropic::stream::{
+    AnthropicResponseFormatter, AnthropicResponseParser, AnthropicStreamFormatter, AnthropicStreamParser,
+};
 use nyro_core::protocol::anthropic::decoder::AnthropicDecoder;
 use nyro_core::protocol::anthropic::encoder::AnthropicEncoder;
+use nyro_core::protocol::bedrock::encoder::BedrockEncoder;
+use nyro_core::protocol::bedrock::stream::{BedrockResponseParser, BedrockStreamParser};
+use nyro_core::protocol::gemini::decoder::GeminiDecoder;
 use nyro_core::protocol::gemini::encoder::GeminiEncoder;
-use nyro_core::protocol::gemini::stream::GeminiStreamFormatter;
-use nyro_core::protocol::openai::stream::OpenAIStreamFormatter;
+use nyro_core::protocol::gemini::stream::{GeminiResponseParser, GeminiStreamFormatter};
+use nyro_core::protocol::ollama::decoder::{OllamaDecoder, OllamaGenerateDecoder};
+use nyro_core::protocol::ollama::encoder::OllamaEncoder;
+use nyro_core::protocol::ollama::stream::{OllamaEndpoint, OllamaResponseParser, OllamaStreamFormatter};
+use nyro_core::protocol::openai::decoder::OpenAIDecoder;
+use nyro_core::protocol::openai::stream::{OpenAIResponseFormatter, OpenAIResponseParser, OpenAIStreamFormatter};
 use nyro_core::protocol::openai::encoder::OpenAIEncoder;
 use nyro_core::protocol::openai::responses::decoder::ResponsesDecoder;
+use nyro_core::protocol::openai::responses::encoder::ResponsesEncoder;
 use nyro_core::protocol::openai::responses::formatter::ResponsesResponseFormatter;
+use nyro_core::protocol::openai::responses::parser::{ResponsesResponseParser, ResponsesStreamParser};
 use nyro_core::protocol::semantic::reasoning::normalize_response_reasoning;
 use nyro_core::protocol::semantic::tool_correlation::normalize_request_tool_results;
 use nyro_core::protocol::types::{
-    ContentBlock, InternalMessage, InternalRequest, InternalResponse, MessageContent, ResponseItem, Role,
-    StreamDelta,
+    ContentBlock, ImageSource, InternalMessage, InternalRequest, InternalResponse, MessageContent, ReasoningConfig,
+    ReasoningEffort, ResponseItem, Role, StreamDelta,
     TokenUsage, ToolCall, ToolDef,
 };
-use nyro_core::protocol::{IngressDecoder, Protocol, ResponseFormatter, StreamFormatter};
+use nyro_core::protocol::{
+    IngressDecoder, Protocol, ResponseFormatter, ResponseParser, StreamFormatter, StreamParser,
+};
 use nyro_core::protocol::EgressEncoder;
 
 #[test]
@@ -24,12 +37,15 @@ fn openai_to_anthropic_thinking_blocks() {
         model: "minimax-m2.7".to_string(),
         content: "hello".to_string(),
         reasoning_content: Some("reasoning summary".to_string()),
+        reasoning_signature: None,
+        redacted_reasoning: Vec::new(),
         tool_calls: vec![],
         response_items: None,
         stop_reason: Some("stop".to_string()),
         usage: TokenUsage {
             input_tokens: 10,
             output_tokens: 20,
+            ..Default::default()
         },
     };
 
@@ -52,6 +68,8 @@ fn openai_to_responses_reasoning_and_function_call_items() {
         model: "minimax-m2.7".to_string(),
         content: "done".to_string(),
         reasoning_content: Some("chain".to_string()),
+        reasoning_signature: None,
+        redacted_reasoning: Vec::new(),
         tool_calls: vec![ToolCall {
             id: "call_123".to_string(),
             name: "ls".to_string(),
@@ -103,6 +121,8 @@ fn openai_formatter_sets_tool_calls_finish_reason_when_tool_calls_present() {
         model: "gemini-2.5-flash".to_string(),
         content: String::new(),
         reasoning_content: None,
+        reasoning_signature: None,
+        redacted_reasoning: Vec::new(),
         tool_calls: vec![ToolCall {
             id: "call_1".to_string(),
             name: "bash".to_string(),
@@ -113,6 +133,7 @@ fn openai_formatter_sets_tool_calls_finish_reason_when_tool_calls_present() {
         usage: TokenUsage {
             input_tokens: 44,
             output_tokens: 13,
+            ..Default::default()
         },
     };
 
@@ -174,6 +195,7 @@ fn gemini_tool_result_correlation_success() {
                     arguments: "{\"path\":\"src/main.rs\"}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
@@ -183,6 +205,7 @@ fn gemini_tool_result_correlation_success() {
                 }]),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
         ],
         model: "minimax-m2.7".to_string(),
@@ -192,6 +215,7 @@ fn gemini_tool_result_correlation_success() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::Gemini,
         extra: Default::default(),
     };
@@ -224,6 +248,7 @@ fn gemini_tool_result_id_hint_matches_out_of_order_calls() {
                     },
                 ]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
@@ -233,6 +258,7 @@ fn gemini_tool_result_id_hint_matches_out_of_order_calls() {
                 }]),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
@@ -242,6 +268,7 @@ fn gemini_tool_result_id_hint_matches_out_of_order_calls() {
                 }]),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
         ],
         model: "minimax-m2.7".to_string(),
@@ -251,6 +278,7 @@ fn gemini_tool_result_id_hint_matches_out_of_order_calls() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::Gemini,
         extra: Default::default(),
     };
@@ -267,6 +295,8 @@ fn minimax_reasoning_split_fallback_think_tag() {
         model: "minimax-m2.7".to_string(),
         content: "<think>plan first</think>run ls".to_string(),
         reasoning_content: None,
+        reasoning_signature: None,
+        redacted_reasoning: Vec::new(),
         tool_calls: vec![],
         response_items: None,
         stop_reason: Some("stop".to_string()),
@@ -285,6 +315,8 @@ fn non_reasoning_model_no_regression() {
         model: "plain-model".to_string(),
         content: "hello world".to_string(),
         reasoning_content: None,
+        reasoning_signature: None,
+        redacted_reasoning: Vec::new(),
         tool_calls: vec![],
         response_items: None,
         stop_reason: Some("stop".to_string()),
@@ -370,6 +402,7 @@ fn openai_encoder_injects_synthetic_tool_call_before_orphan_tool_result() {
             content: MessageContent::Text("{\"ok\":true}".to_string()),
             tool_calls: None,
             tool_call_id: Some("call_orphan_1".to_string()),
+            cache_control: None,
         }],
         model: "minimax-m2.7".to_string(),
         stream: false,
@@ -378,6 +411,7 @@ fn openai_encoder_injects_synthetic_tool_call_before_orphan_tool_result() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -414,18 +448,21 @@ fn openai_encoder_injects_adjacent_tool_call_for_non_adjacent_match() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::User,
                 content: MessageContent::Text("intermediate".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("{\"ok\":true}".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_x".to_string()),
+                cache_control: None,
             },
         ],
         model: "minimax-m2.7".to_string(),
@@ -435,6 +472,7 @@ fn openai_encoder_injects_adjacent_tool_call_for_non_adjacent_match() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -481,18 +519,21 @@ fn openai_encoder_drops_intermediate_assistant_text_before_tool_result() {
                     arguments: "{\"command\":\"ls -la\"}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
                 content: MessageContent::Text("extra text".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("{\"stdout\":\"...\"}".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_keep".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -502,6 +543,7 @@ fn openai_encoder_drops_intermediate_assistant_text_before_tool_result() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -546,6 +588,7 @@ fn openai_encoder_remaps_duplicate_tool_call_ids() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
@@ -556,18 +599,21 @@ fn openai_encoder_remaps_duplicate_tool_call_ids() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("{\"ok\":true}".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_dup".to_string()),
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("{\"ok\":true}".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_dup".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -577,6 +623,7 @@ fn openai_encoder_remaps_duplicate_tool_call_ids() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -615,6 +662,7 @@ fn anthropic_encoder_maps_required_tool_choice_to_any() {
             content: MessageContent::Text("hello".to_string()),
             tool_calls: None,
             tool_call_id: None,
+            cache_control: None,
         }],
         model: "MiniMax-M2.7".to_string(),
         stream: false,
@@ -625,8 +673,10 @@ fn anthropic_encoder_maps_required_tool_choice_to_any() {
             name: "exec_command".to_string(),
             description: Some("Execute command".to_string()),
             parameters: serde_json::json!({"type":"object","properties":{"command":{"type":"string"}}}),
+            cache_control: None,
         }]),
         tool_choice: Some(serde_json::json!("required")),
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -650,6 +700,7 @@ fn anthropic_encoder_maps_function_tool_choice_to_tool_name() {
             content: MessageContent::Text("hello".to_string()),
             tool_calls: None,
             tool_call_id: None,
+            cache_control: None,
         }],
         model: "MiniMax-M2.7".to_string(),
         stream: false,
@@ -660,11 +711,13 @@ fn anthropic_encoder_maps_function_tool_choice_to_tool_name() {
             name: "exec_command".to_string(),
             description: Some("Execute command".to_string()),
             parameters: serde_json::json!({"type":"object","properties":{"command":{"type":"string"}}}),
+            cache_control: None,
         }]),
         tool_choice: Some(serde_json::json!({
             "type":"function",
             "function":{"name":"exec_command"}
         })),
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -695,18 +748,21 @@ fn anthropic_encoder_merges_consecutive_roles_and_drops_empty_text() {
                 content: MessageContent::Text("first".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::User,
                 content: MessageContent::Text("second".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
                 content: MessageContent::Text(String::new()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
@@ -717,12 +773,14 @@ fn anthropic_encoder_merges_consecutive_roles_and_drops_empty_text() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("result".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_1".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -732,6 +790,7 @@ fn anthropic_encoder_merges_consecutive_roles_and_drops_empty_text() {
         top_p: None,
         tools: None,
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::ResponsesAPI,
         extra: Default::default(),
     };
@@ -776,6 +835,7 @@ fn anthropic_encoder_normalizes_tool_use_ids_for_tool_and_result() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
@@ -785,6 +845,7 @@ fn anthropic_encoder_normalizes_tool_use_ids_for_tool_and_result() {
                 }]),
                 tool_calls: None,
                 tool_call_id: Some("call_function_abc_1".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -796,8 +857,10 @@ fn anthropic_encoder_normalizes_tool_use_ids_for_tool_and_result() {
             name: "glob".to_string(),
             description: None,
             parameters: serde_json::json!({"type":"object","properties":{}}),
+            cache_control: None,
         }]),
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::Gemini,
         extra: Default::default(),
     };
@@ -863,24 +926,28 @@ fn openai_encoder_remaps_reused_tool_result_id_with_synthetic_adjacent_call() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("ok1".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_same".to_string()),
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
                 content: MessageContent::Text("intermediate".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("ok2".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_same".to_string()),
+                cache_control: None,
             },
         ],
         model: "gpt-4o-mini".to_string(),
@@ -892,8 +959,10 @@ fn openai_encoder_remaps_reused_tool_result_id_with_synthetic_adjacent_call() {
             name: "exec_command".to_string(),
             description: None,
             parameters: serde_json::json!({"type":"object","properties":{}}),
+            cache_control: None,
         }]),
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::OpenAI,
         extra: Default::default(),
     };
@@ -939,18 +1008,21 @@ fn openai_encoder_rewrites_multi_tool_call_history_to_adjacent_pairs() {
                     },
                 ]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("r1".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_a".to_string()),
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("r2".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_b".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -962,8 +1034,10 @@ fn openai_encoder_rewrites_multi_tool_call_history_to_adjacent_pairs() {
             name: "Glob".to_string(),
             description: None,
             parameters: serde_json::json!({"type":"object","properties":{}}),
+            cache_control: None,
         }]),
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::Anthropic,
         extra: Default::default(),
     };
@@ -1007,6 +1081,7 @@ fn openai_encoder_drops_orphan_assistant_tool_calls_without_results() {
                 content: MessageContent::Text("sys".to_string()),
                 tool_calls: None,
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
@@ -1024,6 +1099,7 @@ fn openai_encoder_drops_orphan_assistant_tool_calls_without_results() {
                     },
                 ]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Assistant,
@@ -1034,12 +1110,14 @@ fn openai_encoder_drops_orphan_assistant_tool_calls_without_results() {
                     arguments: "{}".to_string(),
                 }]),
                 tool_call_id: None,
+                cache_control: None,
             },
             InternalMessage {
                 role: Role::Tool,
                 content: MessageContent::Text("{\"ok\":true}".to_string()),
                 tool_calls: None,
                 tool_call_id: Some("call_new".to_string()),
+                cache_control: None,
             },
         ],
         model: "MiniMax-M2.7".to_string(),
@@ -1051,8 +1129,10 @@ fn openai_encoder_drops_orphan_assistant_tool_calls_without_results() {
             name: "glob".to_string(),
             description: None,
             parameters: serde_json::json!({"type":"object","properties":{}}),
+            cache_control: None,
         }]),
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::Gemini,
         extra: Default::default(),
     };
@@ -1187,6 +1267,7 @@ fn gemini_encoder_sanitizes_unsupported_json_schema_fields() {
             content: MessageContent::Text("hello".to_string()),
             tool_calls: None,
             tool_call_id: None,
+            cache_control: None,
         }],
         model: "gemini-2.5-flash".to_string(),
         stream: false,
@@ -1214,8 +1295,10 @@ fn gemini_encoder_sanitizes_unsupported_json_schema_fields() {
                     "entry": {"type":"string"}
                 }
             }),
+            cache_control: None,
         }]),
         tool_choice: None,
+        reasoning: None,
         source_protocol: Protocol::OpenAI,
         extra: Default::default(),
     };
@@ -1239,3 +1322,1005 @@ fn gemini_encoder_sanitizes_unsupported_json_schema_fields() {
     assert!(!rendered.contains("\"ref\""));
     assert!(!rendered.contains("$defs"));
 }
+
+#[test]
+fn ollama_encoder_maps_options_images_and_tool_names() {
+    let req = InternalRequest {
+        messages: vec![
+            InternalMessage {
+                role: Role::User,
+                content: MessageContent::Blocks(vec![
+                    ContentBlock::Text {
+                        text: "what is this?".to_string(),
+                    },
+                    ContentBlock::Image {
+                        source: ImageSource {
+                            media_type: "image/url".to_string(),
+                            data: "data:image/png;base64,iVBORw0KGgo=".to_string(),
+                        },
+                    },
+                ]),
+                tool_calls: None,
+                tool_call_id: None,
+                cache_control: None,
+            },
+            InternalMessage {
+                role: Role::Assistant,
+                content: MessageContent::Text(String::new()),
+                tool_calls: Some(vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "lookup".to_string(),
+                    arguments: "{\"q\":\"png\"}".to_string(),
+                }]),
+                tool_call_id: None,
+                cache_control: None,
+            },
+            InternalMessage {
+                role: Role::Tool,
+                content: MessageContent::Text("a logo".to_string()),
+                tool_calls: None,
+                tool_call_id: Some("call_1".to_string()),
+                cache_control: None,
+            },
+        ],
+        model: "llava".to_string(),
+        stream: true,
+        temperature: Some(0.2),
+        max_tokens: Some(128),
+        top_p: None,
+        tools: None,
+        tool_choice: None,
+        reasoning: Some(ReasoningConfig {
+            enabled: true,
+            ..Default::default()
+        }),
+        source_protocol: Protocol::OpenAI,
+        extra: [
+            ("options".to_string(), serde_json::json!({"num_ctx": 8192})),
+            ("keep_alive".to_string(), serde_json::json!("10m")),
+            ("stop".to_string(), serde_json::json!("END")),
+        ]
+        .into_iter()
+        .collect(),
+    };
+
+    let (body, _) = OllamaEncoder.encode_request(&req).expect("encode ollama body");
+    assert_eq!(OllamaEncoder.egress_path("llava", true), "/api/chat");
+    assert_eq!(body["options"]["num_ctx"], 8192);
+    assert_eq!(body["options"]["num_predict"], 128);
+    assert_eq!(body["options"]["stop"], serde_json::json!(["END"]));
+    assert_eq!(body["keep_alive"], "10m");
+    assert_eq!(body["think"], true);
+
+    let messages = body["messages"].as_array().expect("messages array");
+    assert_eq!(messages[0]["images"], serde_json::json!(["iVBORw0KGgo="]));
+    assert_eq!(messages[1]["tool_calls"][0]["function"]["arguments"], serde_json::json!({"q": "png"}));
+    assert_eq!(messages[2]["role"], "tool");
+    assert_eq!(messages[2]["tool_name"], "lookup");
+}
+
+#[test]
+fn ollama_response_parser_reads_thinking_and_tool_calls() {
+    let resp = OllamaResponseParser
+        .parse_response(serde_json::json!({
+            "model": "qwen3",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "thinking": "need a lookup",
+                "tool_calls": [{"function": {"name": "lookup", "arguments": {"q": "x"}}}]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 12,
+            "eval_count": 3
+        }))
+        .expect("parse ollama response");
+    assert_eq!(resp.reasoning_content.as_deref(), Some("need a lookup"));
+    assert_eq!(resp.tool_calls[0].name, "lookup");
+    assert_eq!(resp.stop_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(resp.usage.input_tokens, 12);
+}
+
+#[test]
+fn ollama_decoder_lifts_options_and_links_tool_results() {
+    let req = OllamaDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-local",
+            "messages": [
+                {"role": "user", "content": "look", "images": ["iVBORw0KGgo="]},
+                {"role": "assistant", "content": "", "tool_calls": [
+                    {"function": {"name": "lookup", "arguments": {"q": "x"}}}
+                ]},
+                {"role": "tool", "content": "found", "tool_name": "lookup"}
+            ],
+            "options": {"temperature": 0.2, "num_predict": 64, "stop": ["END"], "num_ctx": 8192},
+            "format": "json",
+            "keep_alive": "5m"
+        }))
+        .expect("decode ollama chat");
+
+    assert!(req.stream, "ollama streams unless told otherwise");
+    assert_eq!(req.temperature, Some(0.2));
+    assert_eq!(req.max_tokens, Some(64));
+    assert_eq!(req.extra["stop"], serde_json::json!(["END"]));
+    assert_eq!(req.extra["options"], serde_json::json!({"num_ctx": 8192}));
+    assert_eq!(req.extra["response_format"]["type"], "json_object");
+    match &req.messages[0].content {
+        MessageContent::Blocks(blocks) => match &blocks[1] {
+            ContentBlock::Image { source } => assert_eq!(source.media_type, "image/png"),
+            other => panic!("expected image block, got {other:?}"),
+        },
+        other => panic!("expected blocks, got {other:?}"),
+    }
+    let call_id = &req.messages[1].tool_calls.as_ref().unwrap()[0].id;
+    assert_eq!(req.messages[2].tool_call_id.as_ref(), Some(call_id));
+
+    // Native-only fields never leak into an OpenAI upstream body.
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai");
+    assert!(body.get("options").is_none());
+    assert!(body.get("keep_alive").is_none());
+    assert_eq!(body["response_format"]["type"], "json_object");
+    assert_eq!(body["stop"], serde_json::json!(["END"]));
+}
+
+#[test]
+fn ollama_generate_round_trips_as_ndjson_records() {
+    let req = OllamaGenerateDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-local",
+            "system": "be brief",
+            "prompt": "hi",
+            "stream": false
+        }))
+        .expect("decode ollama generate");
+    assert!(!req.stream);
+    assert_eq!(req.messages.len(), 2);
+    assert_eq!(req.messages[0].role, Role::System);
+    assert_eq!(req.source_protocol, Protocol::OllamaGenerate);
+
+    let mut formatter = OllamaStreamFormatter::new(OllamaEndpoint::Generate);
+    let events = formatter.format_deltas(&[
+        StreamDelta::MessageStart { id: "m".into(), model: "claude-local".into() },
+        StreamDelta::TextDelta("hel".into()),
+        StreamDelta::TextDelta("lo".into()),
+        StreamDelta::Usage(TokenUsage { input_tokens: 5, output_tokens: 2, ..Default::default() }),
+        StreamDelta::Done { stop_reason: "end_turn".into() },
+    ]);
+    let records: Vec<serde_json::Value> = events
+        .iter()
+        .map(|ev| serde_json::from_str(&ev.data).expect("json record"))
+        .collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0]["response"], "hel");
+    assert_eq!(records[0]["done"], false);
+    assert_eq!(records[2]["done"], true);
+    assert_eq!(records[2]["done_reason"], "stop");
+    assert_eq!(records[2]["prompt_eval_count"], 5);
+    assert!(formatter.format_done().is_empty());
+
+    let mut chat = OllamaStreamFormatter::new(OllamaEndpoint::Chat);
+    let events = chat.format_deltas(&[
+        StreamDelta::ToolCallStart { index: 0, id: "c".into(), name: "lookup".into() },
+        StreamDelta::ToolCallDelta { index: 0, arguments: "{\"q\":".into() },
+        StreamDelta::ToolCallDelta { index: 0, arguments: "\"x\"}".into() },
+        StreamDelta::Done { stop_reason: "tool_calls".into() },
+    ]);
+    let last: serde_json::Value = serde_json::from_str(&events.last().unwrap().data).unwrap();
+    assert_eq!(last["message"]["tool_calls"][0]["function"]["arguments"]["q"], "x");
+}
+
+#[test]
+fn bedrock_encoder_builds_converse_body() {
+    let text = |role, text: &str| InternalMessage {
+        role,
+        content: MessageContent::Text(text.to_string()),
+        tool_calls: None,
+        tool_call_id: None,
+        cache_control: None,
+    };
+    let req = InternalRequest {
+        messages: vec![
+            text(Role::System, "be brief"),
+            text(Role::User, "weather in Paris?"),
+            InternalMessage {
+                role: Role::Assistant,
+                content: MessageContent::Text(String::new()),
+                tool_calls: Some(vec![ToolCall {
+                    id: "tooluse_1".to_string(),
+                    name: "weather".to_string(),
+                    arguments: "{\"city\":\"Paris\"}".to_string(),
+                }]),
+                tool_call_id: None,
+                cache_control: None,
+            },
+            InternalMessage {
+                role: Role::Tool,
+                content: MessageContent::Text("sunny".to_string()),
+                tool_calls: None,
+                tool_call_id: Some("tooluse_1".to_string()),
+                cache_control: None,
+            },
+            text(Role::User, "and tomorrow?"),
+        ],
+        model: "anthropic.claude-3-5-sonnet-20240620-v1:0".to_string(),
+        stream: false,
+        temperature: Some(0.5),
+        max_tokens: Some(256),
+        top_p: None,
+        tools: Some(vec![ToolDef {
+            name: "weather".to_string(),
+            description: Some("Look up weather".to_string()),
+            parameters: serde_json::json!({"type": "object"}),
+            cache_control: None,
+        }]),
+        tool_choice: Some(serde_json::json!("required")),
+        reasoning: None,
+        source_protocol: Protocol::OpenAI,
+        extra: Default::default(),
+    };
+
+    let (body, _) = BedrockEncoder.encode_request(&req).expect("encode bedrock body");
+    assert_eq!(
+        BedrockEncoder.egress_path(&req.model, true),
+        "/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0/converse-stream"
+    );
+    assert!(body.get("model").is_none());
+    assert_eq!(body["system"], serde_json::json!([{"text": "be brief"}]));
+    assert_eq!(body["inferenceConfig"], serde_json::json!({"maxTokens": 256, "temperature": 0.5}));
+    assert_eq!(body["toolConfig"]["toolChoice"], serde_json::json!({"any": {}}));
+    assert_eq!(body["toolConfig"]["tools"][0]["toolSpec"]["inputSchema"]["json"]["type"], "object");
+
+    // The tool result and the follow-up question merge into one user turn.
+    let messages = body["messages"].as_array().expect("messages array");
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1]["content"][0]["toolUse"]["input"]["city"], "Paris");
+    assert_eq!(messages[2]["role"], "user");
+    assert_eq!(messages[2]["content"][0]["toolResult"]["toolUseId"], "tooluse_1");
+    assert_eq!(messages[2]["content"][0]["toolResult"]["content"], serde_json::json!([{"text": "sunny"}]));
+    assert_eq!(messages[2]["content"][1]["text"], "and tomorrow?");
+}
+
+#[test]
+fn bedrock_stream_parser_reads_converse_stream_events() {
+    let records = [
+        serde_json::json!({"event": "messageStart", "payload": {"role": "assistant"}}),
+        serde_json::json!({"event": "contentBlockDelta", "payload": {"contentBlockIndex": 0, "delta": {"text": "Checking"}}}),
+        serde_json::json!({"event": "contentBlockStart", "payload": {"contentBlockIndex": 1, "start": {"toolUse": {"toolUseId": "tooluse_1", "name": "weather"}}}}),
+        serde_json::json!({"event": "contentBlockDelta", "payload": {"contentBlockIndex": 1, "delta": {"toolUse": {"input": "{\"city\":"}}}}),
+        serde_json::json!({"event": "contentBlockDelta", "payload": {"contentBlockIndex": 1, "delta": {"toolUse": {"input": "\"Paris\"}"}}}}),
+        serde_json::json!({"event": "messageStop", "payload": {"stopReason": "tool_use"}}),
+        serde_json::json!({"event": "metadata", "payload": {"usage": {"inputTokens": 20, "outputTokens": 7}}}),
+    ];
+    let raw: String = records.iter().map(|r| format!("{r}\n")).collect();
+
+    let mut parser = BedrockStreamParser::new();
+    let mut deltas = parser.parse_chunk(&raw).expect("parse bedrock stream");
+    deltas.extend(parser.finish().expect("finish bedrock stream"));
+
+    assert!(matches!(deltas[0], StreamDelta::MessageStart { .. }));
+    assert!(matches!(&deltas[1], StreamDelta::TextDelta(t) if t == "Checking"));
+    assert!(matches!(&deltas[2], StreamDelta::ToolCallStart { index: 0, name, .. } if name == "weather"));
+    let arguments: String = deltas
+        .iter()
+        .filter_map(|d| match d {
+            StreamDelta::ToolCallDelta { arguments, .. } => Some(arguments.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(arguments, "{\"city\":\"Paris\"}");
+    assert!(matches!(&deltas[deltas.len() - 2], StreamDelta::Usage(u) if u.input_tokens == 20 && u.output_tokens == 7));
+    assert!(matches!(deltas.last(), Some(StreamDelta::Done { stop_reason }) if stop_reason == "tool_calls"));
+}
+
+#[test]
+fn responses_encoder_translates_chat_requests() {
+    let text = |role, text: &str| InternalMessage {
+        role,
+        content: MessageContent::Text(text.to_string()),
+        tool_calls: None,
+        tool_call_id: None,
+        cache_control: None,
+    };
+    let req = InternalRequest {
+        messages: vec![
+            text(Role::System, "be brief"),
+            text(Role::User, "weather in Paris?"),
+            InternalMessage {
+                role: Role::Assistant,
+                content: MessageContent::Text(String::new()),
+                tool_calls: Some(vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "weather".to_string(),
+                    arguments: "{\"city\":\"Paris\"}".to_string(),
+                }]),
+                tool_call_id: None,
+                cache_control: None,
+            },
+            InternalMessage {
+                role: Role::Tool,
+                content: MessageContent::Text("sunny".to_string()),
+                tool_calls: None,
+                tool_call_id: Some("call_1".to_string()),
+                cache_control: None,
+            },
+        ],
+        model: "gpt-5".to_string(),
+        stream: false,
+        temperature: None,
+        max_tokens: Some(256),
+        top_p: None,
+        tools: Some(vec![ToolDef {
+            name: "weather".to_string(),
+            description: Some("Look up weather".to_string()),
+            parameters: serde_json::json!({"type": "object"}),
+            cache_control: None,
+        }]),
+        tool_choice: Some(serde_json::json!("required")),
+        reasoning: Some(ReasoningConfig {
+            enabled: true,
+            effort: Some(ReasoningEffort::Low),
+            budget_tokens: None,
+        }),
+        source_protocol: Protocol::OpenAI,
+        extra: Default::default(),
+    };
+    let (body, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+
+    assert_eq!(ResponsesEncoder.egress_path(&req.model, true), "/v1/responses");
+    assert_eq!(body["instructions"], "be brief");
+    assert_eq!(body["max_output_tokens"], 256);
+    assert_eq!(body["reasoning"], serde_json::json!({"effort": "low"}));
+    assert_eq!(body["tool_choice"], "required");
+    assert_eq!(body["tools"][0], serde_json::json!({
+        "type": "function",
+        "name": "weather",
+        "description": "Look up weather",
+        "parameters": {"type": "object"},
+    }));
+    assert!(body.get("reasoning_effort").is_none());
+
+    let input = body["input"].as_array().expect("input array");
+    assert_eq!(input[0]["content"][0], serde_json::json!({"type": "input_text", "text": "weather in Paris?"}));
+    assert_eq!(input[1]["type"], "function_call");
+    assert_eq!(input[1]["call_id"], "call_1");
+    assert_eq!(input[2], serde_json::json!({"type": "function_call_output", "call_id": "call_1", "output": "sunny"}));
+}
+
+#[test]
+fn responses_encoder_passes_native_items_through() {
+    let body = serde_json::json!({
+        "model": "gpt-5",
+        "instructions": "be brief",
+        "previous_response_id": "resp_prev",
+        "input": [
+            {"type": "reasoning", "id": "rs_1", "summary": [], "encrypted_content": "gAAAA"},
+            {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "and now?"}]},
+        ],
+        "tools": [{"type": "web_search"}],
+        "include": ["reasoning.encrypted_content"],
+    });
+    let req = ResponsesDecoder.decode_request(body.clone()).expect("decode responses request");
+    let (out, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+
+    assert_eq!(out["input"], body["input"]);
+    assert_eq!(out["tools"], body["tools"]);
+    assert_eq!(out["instructions"], "be brief");
+    assert_eq!(out["previous_response_id"], "resp_prev");
+    assert_eq!(out["include"], body["include"]);
+}
+
+#[test]
+fn responses_parser_keeps_native_output_items() {
+    let resp = ResponsesResponseParser
+        .parse_response(serde_json::json!({
+            "id": "resp_1",
+            "model": "gpt-5",
+            "status": "completed",
+            "output": [
+                {"type": "reasoning", "id": "rs_1", "summary": [{"type": "summary_text", "text": "Look it up."}], "encrypted_content": "gAAAA"},
+                {"type": "web_search_call", "id": "ws_1", "status": "completed"},
+                {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "weather", "arguments": "{}"},
+            ],
+            "usage": {"input_tokens": 12, "output_tokens": 5},
+        }))
+        .expect("parse responses body");
+
+    assert_eq!(resp.id, "resp_1");
+    assert_eq!(resp.reasoning_content.as_deref(), Some("Look it up."));
+    assert_eq!(resp.tool_calls[0].id, "call_1");
+    assert_eq!(resp.stop_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(resp.usage.input_tokens, 12);
+
+    let out = ResponsesResponseFormatter.format_response(&resp);
+    assert_eq!(out["output"][0]["encrypted_content"], "gAAAA");
+    assert_eq!(out["output"][1]["type"], "web_search_call");
+    assert_eq!(out["output"][2]["call_id"], "call_1");
+}
+
+#[test]
+fn responses_stream_parser_reads_semantic_events() {
+    let events = [
+        serde_json::json!({"type": "response.created", "response": {"id": "resp_1", "model": "gpt-5"}}),
+        serde_json::json!({"type": "response.reasoning_summary_text.delta", "output_index": 0, "delta": "Thinking"}),
+        serde_json::json!({"type": "response.output_text.delta", "output_index": 1, "delta": "Checking"}),
+        serde_json::json!({"type": "response.output_item.added", "output_index": 2, "item": {"type": "function_call", "call_id": "call_1", "name": "weather"}}),
+        serde_json::json!({"type": "response.function_call_arguments.delta", "output_index": 2, "delta": "{\"city\":"}),
+        serde_json::json!({"type": "response.function_call_arguments.delta", "output_index": 2, "delta": "\"Paris\"}"}),
+        serde_json::json!({"type": "response.completed", "response": {"status": "completed", "usage": {"input_tokens": 20, "output_tokens": 7}}}),
+    ];
+    let raw: String = events
+        .iter()
+        .map(|e| format!("event: {}\ndata: {e}\n\n", e["type"].as_str().unwrap()))
+        .collect();
+
+    let mut parser = ResponsesStreamParser::new();
+    let (head, tail) = raw.split_at(raw.len() / 2);
+    let mut deltas = parser.parse_chunk(head).expect("parse responses stream");
+    deltas.extend(parser.parse_chunk(tail).expect("parse responses stream"));
+    deltas.extend(parser.finish().expect("finish responses stream"));
+
+    assert!(matches!(&deltas[0], StreamDelta::MessageStart { id, .. } if id == "resp_1"));
+    assert!(matches!(&deltas[1], StreamDelta::ReasoningDelta(t) if t == "Thinking"));
+    assert!(matches!(&deltas[2], StreamDelta::TextDelta(t) if t == "Checking"));
+    assert!(matches!(&deltas[3], StreamDelta::ToolCallStart { index: 0, id, .. } if id == "call_1"));
+    let arguments: String = deltas
+        .iter()
+        .filter_map(|d| match d {
+            StreamDelta::ToolCallDelta { arguments, .. } => Some(arguments.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(arguments, "{\"city\":\"Paris\"}");
+    assert!(matches!(&deltas[deltas.len() - 2], StreamDelta::Usage(u) if u.input_tokens == 20 && u.output_tokens == 7));
+    assert!(matches!(deltas.last(), Some(StreamDelta::Done { stop_reason }) if stop_reason == "tool_calls"));
+}
+
+const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+
+#[test]
+fn openai_data_uri_image_reaches_gemini_as_inline_data() {
+    let req = OpenAIDecoder
+        .decode_request(serde_json::json!({
+            "model": "gemini-2.0-flash",
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "what is this?"},
+                    // Mislabeled: the bytes are a PNG.
+                    {"type": "image_url", "image_url": {"url": format!("data:image/jpeg;base64,{PNG_1X1}")}},
+                ],
+            }],
+        }))
+        .expect("decode openai request");
+
+    let (body, _) = GeminiEncoder.encode_request(&req).expect("encode gemini body");
+    assert_eq!(
+        body["contents"][0]["parts"][1],
+        serde_json::json!({"inlineData": {"mimeType": "image/png", "data": PNG_1X1}})
+    );
+
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert_eq!(
+        body["messages"][0]["content"][1]["image_url"]["url"],
+        format!("data:image/png;base64,{PNG_1X1}")
+    );
+}
+
+#[test]
+fn anthropic_url_image_maps_to_each_egress_form() {
+    let req = AnthropicDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 64,
+            "messages": [{
+                "role": "user",
+                "content": [{"type": "image", "source": {"type": "url", "url": "https://example.com/cat.webp"}}],
+            }],
+        }))
+        .expect("decode anthropic request");
+
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(
+        body["messages"][0]["content"][0]["source"],
+        serde_json::json!({"type": "url", "url": "https://example.com/cat.webp"})
+    );
+
+    let (body, _) = GeminiEncoder.encode_request(&req).expect("encode gemini body");
+    assert_eq!(
+        body["contents"][0]["parts"][0],
+        serde_json::json!({"fileData": {"mimeType": "image/webp", "fileUri": "https://example.com/cat.webp"}})
+    );
+
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert_eq!(body["messages"][0]["content"][0]["image_url"]["url"], "https://example.com/cat.webp");
+}
+
+const PDF_STUB: &str = "JVBERi0xLjQKJSVFT0YK";
+
+#[test]
+fn openai_file_part_maps_to_each_egress_document_form() {
+    let req = OpenAIDecoder
+        .decode_request(serde_json::json!({
+            "model": "gpt-4o",
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "Review this spec"},
+                    {"type": "file", "file": {"filename": "api_spec v2.pdf", "file_data": format!("data:application/pdf;base64,{PDF_STUB}")}},
+                ],
+            }],
+        }))
+        .expect("decode openai request");
+
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(
+        body["messages"][0]["content"][1],
+        serde_json::json!({
+            "type": "document",
+            "source": {"type": "base64", "media_type": "application/pdf", "data": PDF_STUB},
+            "title": "api_spec v2.pdf",
+        })
+    );
+
+    let (body, _) = GeminiEncoder.encode_request(&req).expect("encode gemini body");
+    assert_eq!(
+        body["contents"][0]["parts"][1],
+        serde_json::json!({"inlineData": {"mimeType": "application/pdf", "data": PDF_STUB}})
+    );
+
+    let (body, _) = BedrockEncoder.encode_request(&req).expect("encode bedrock body");
+    assert_eq!(
+        body["messages"][0]["content"][1],
+        serde_json::json!({"document": {"format": "pdf", "name": "api spec v2", "source": {"bytes": PDF_STUB}}})
+    );
+
+    let (body, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+    assert_eq!(
+        body["input"][0]["content"][1],
+        serde_json::json!({
+            "type": "input_file",
+            "filename": "api_spec v2.pdf",
+            "file_data": format!("data:application/pdf;base64,{PDF_STUB}"),
+        })
+    );
+}
+
+#[test]
+fn document_blocks_decode_from_anthropic_and_gemini() {
+    let req = AnthropicDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 64,
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "document", "source": {"type": "text", "media_type": "text/plain", "data": "hello"}, "title": "notes"},
+                    {"type": "document", "source": {"type": "url", "url": "https://example.com/spec.pdf"}},
+                ],
+            }],
+        }))
+        .expect("decode anthropic request");
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(
+        body["messages"][0]["content"],
+        serde_json::json!([
+            {"type": "document", "source": {"type": "text", "media_type": "text/plain", "data": "hello"}, "title": "notes"},
+            {"type": "document", "source": {"type": "url", "url": "https://example.com/spec.pdf"}},
+        ])
+    );
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert_eq!(body["messages"][0]["content"][0]["file"]["filename"], "notes");
+    assert_eq!(body["messages"][0]["content"][0]["file"]["file_data"], "data:text/plain;base64,aGVsbG8=");
+
+    let req = GeminiDecoder
+        .decode_with_model(
+            serde_json::json!({
+                "contents": [{
+                    "role": "user",
+                    "parts": [{"inlineData": {"mimeType": "application/pdf", "data": PDF_STUB}}],
+                }],
+            }),
+            "gemini-2.5-pro",
+            false,
+        )
+        .expect("decode gemini request");
+    let MessageContent::Blocks(blocks) = &req.messages[0].content else {
+        panic!("expected blocks");
+    };
+    assert!(matches!(
+        &blocks[0],
+        ContentBlock::Document { source, name: None } if source.media_type == "application/pdf"
+    ));
+
+    let err = OpenAIDecoder
+        .decode_request(serde_json::json!({
+            "model": "gpt-4o",
+            "messages": [{"role": "user", "content": [{"type": "file", "file": {"file_id": "file-abc"}}]}],
+        }))
+        .expect_err("file ids cannot be forwarded");
+    assert!(err.to_string().contains("file-abc"));
+}
+
+fn signed_thinking_conversation() -> InternalRequest {
+    AnthropicDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 1024,
+            "messages": [
+                {"role": "user", "content": "What's the weather?"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "Need the forecast.", "signature": "sig-1"},
+                    {"type": "redacted_thinking", "data": "opaque-1"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Paris"}},
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "sunny"},
+                ]},
+            ],
+        }))
+        .expect("decode anthropic request")
+}
+
+#[test]
+fn anthropic_signed_thinking_is_reemitted_verbatim() {
+    let req = signed_thinking_conversation();
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(
+        body["messages"][1]["content"],
+        serde_json::json!([
+            {"type": "thinking", "thinking": "Need the forecast.", "signature": "sig-1"},
+            {"type": "redacted_thinking", "data": "opaque-1"},
+            {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Paris"}},
+        ])
+    );
+
+    let (body, _) = BedrockEncoder.encode_request(&req).expect("encode bedrock body");
+    assert_eq!(
+        body["messages"][1]["content"][0],
+        serde_json::json!({"reasoningContent": {"reasoningText": {"text": "Need the forecast.", "signature": "sig-1"}}})
+    );
+    assert_eq!(body["messages"][1]["content"][1], serde_json::json!({"reasoningContent": {"redactedContent": "opaque-1"}}));
+
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert!(!body.to_string().contains("sig-1"));
+    assert!(!body.to_string().contains("Need the forecast."));
+}
+
+#[test]
+fn responses_egress_maps_thinking_to_encrypted_reasoning_items() {
+    let req = signed_thinking_conversation();
+    let (body, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+    assert_eq!(
+        body["input"][1],
+        serde_json::json!({
+            "type": "reasoning",
+            "summary": [{"type": "summary_text", "text": "Need the forecast."}],
+            "encrypted_content": "sig-1",
+        })
+    );
+    assert_eq!(body["input"][2]["encrypted_content"], "opaque-1");
+    assert_eq!(body["input"][3]["type"], "function_call");
+    assert_eq!(body["include"], serde_json::json!(["reasoning.encrypted_content"]));
+
+    // The upstream's encrypted reasoning comes back to an Anthropic client
+    // as a signed thinking block.
+    let resp = ResponsesResponseParser
+        .parse_response(serde_json::json!({
+            "id": "resp_1",
+            "model": "o4-mini",
+            "status": "completed",
+            "output": [
+                {"type": "reasoning", "id": "rs_1", "summary": [], "encrypted_content": "enc-2"},
+                {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "Sunny."}]},
+            ],
+        }))
+        .expect("parse responses body");
+    let out = AnthropicResponseFormatter.format_response(&resp);
+    assert_eq!(
+        out["content"][0],
+        serde_json::json!({"type": "thinking", "thinking": "", "signature": "enc-2"})
+    );
+}
+
+#[test]
+fn anthropic_stream_keeps_thinking_signatures() {
+    let mut parser = AnthropicStreamParser::new();
+    let deltas = parser
+        .parse_chunk(concat!(
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-sonnet-4\",\"usage\":{\"input_tokens\":5}}}\n\n",
+            "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"thinking\",\"thinking\":\"\"}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Check.\"}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"sig-9\"}}\n\n",
+            "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"redacted_thinking\",\"data\":\"opaque-9\"}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":2,\"delta\":{\"type\":\"text_delta\",\"text\":\"Done.\"}}\n\n",
+        ))
+        .expect("parse anthropic stream");
+    assert!(matches!(&deltas[2], StreamDelta::ReasoningDelta(t) if t == "Check."));
+    assert!(matches!(&deltas[3], StreamDelta::ReasoningSignature(s) if s == "sig-9"));
+    assert!(matches!(&deltas[4], StreamDelta::RedactedReasoning(d) if d == "opaque-9"));
+
+    let mut formatter = AnthropicStreamFormatter::new();
+    let events: Vec<serde_json::Value> = formatter
+        .format_deltas(&deltas)
+        .iter()
+        .map(|ev| serde_json::from_str(&ev.data).unwrap())
+        .collect();
+    let blocks: Vec<&serde_json::Value> = events
+        .iter()
+        .filter(|ev| ev["type"] == "content_block_start")
+        .map(|ev| &ev["content_block"])
+        .collect();
+    assert_eq!(blocks[0]["type"], "thinking");
+    assert_eq!(blocks[1], &serde_json::json!({"type": "redacted_thinking", "data": "opaque-9"}));
+    assert_eq!(blocks[2]["type"], "text");
+    assert!(events.iter().any(|ev| ev["index"] == 0 && ev["delta"]["signature"] == "sig-9"));
+
+    let resp = AnthropicResponseParser
+        .parse_response(serde_json::json!({
+            "id": "msg_2",
+            "model": "claude-sonnet-4",
+            "content": [
+                {"type": "thinking", "thinking": "Plan.", "signature": "sig-10"},
+                {"type": "text", "text": "Hi"},
+            ],
+            "stop_reason": "end_turn",
+        }))
+        .expect("parse anthropic body");
+    assert_eq!(resp.reasoning_content.as_deref(), Some("Plan."));
+    assert_eq!(resp.reasoning_signature.as_deref(), Some("sig-10"));
+}
+
+#[test]
+fn anthropic_thinking_budget_maps_to_each_egress_reasoning_control() {
+    let req = AnthropicDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 8000,
+            "temperature": 0.7,
+            "thinking": {"type": "enabled", "budget_tokens": 10000},
+            "messages": [{"role": "user", "content": "think hard"}],
+        }))
+        .expect("decode anthropic body");
+    assert_eq!(
+        req.reasoning,
+        Some(ReasoningConfig {
+            enabled: true,
+            effort: None,
+            budget_tokens: Some(10000),
+        })
+    );
+
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(body["thinking"], serde_json::json!({"type": "enabled", "budget_tokens": 10000}));
+    assert_eq!(body["max_tokens"], 18000);
+    assert!(body.get("temperature").is_none());
+
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert_eq!(body["reasoning_effort"], "medium");
+
+    let (body, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+    assert_eq!(body["reasoning"], serde_json::json!({"effort": "medium"}));
+
+    let (body, _) = GeminiEncoder.encode_request(&req).expect("encode gemini body");
+    assert_eq!(
+        body["generationConfig"]["thinkingConfig"],
+        serde_json::json!({"thinkingBudget": 10000, "includeThoughts": true})
+    );
+
+    let mut bedrock_req = req.clone();
+    bedrock_req.model = "us.anthropic.claude-sonnet-4-5-20250929-v1:0".to_string();
+    let (body, _) = BedrockEncoder.encode_request(&bedrock_req).expect("encode bedrock body");
+    assert_eq!(
+        body["additionalModelRequestFields"]["thinking"],
+        serde_json::json!({"type": "enabled", "budget_tokens": 10000})
+    );
+    assert_eq!(body["inferenceConfig"]["maxTokens"], 18000);
+    assert!(body["inferenceConfig"].get("temperature").is_none());
+    bedrock_req.model = "amazon.nova-pro-v1:0".to_string();
+    let (body, _) = BedrockEncoder.encode_request(&bedrock_req).expect("encode bedrock body");
+    assert!(body.get("additionalModelRequestFields").is_none());
+
+    let (body, _) = OllamaEncoder.encode_request(&req).expect("encode ollama body");
+    assert_eq!(body["think"], true);
+}
+
+#[test]
+fn reasoning_effort_decodes_from_openai_responses_gemini_and_ollama() {
+    let req = OpenAIDecoder
+        .decode_request(serde_json::json!({
+            "model": "o3",
+            "reasoning_effort": "high",
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .expect("decode openai body");
+    assert_eq!(req.reasoning.as_ref().and_then(|r| r.effort), Some(ReasoningEffort::High));
+    assert!(!req.extra.contains_key("reasoning_effort"));
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(body["thinking"], serde_json::json!({"type": "enabled", "budget_tokens": 32768}));
+    assert_eq!(body["max_tokens"], 32768 + 4096);
+    let (body, _) = GeminiEncoder.encode_request(&req).expect("encode gemini body");
+    assert_eq!(body["generationConfig"]["thinkingConfig"]["thinkingBudget"], 32768);
+
+    let req = ResponsesDecoder
+        .decode_request(serde_json::json!({
+            "model": "gpt-5",
+            "input": "hi",
+            "reasoning": {"effort": "low", "summary": "auto"},
+        }))
+        .expect("decode responses body");
+    assert_eq!(req.reasoning.as_ref().and_then(|r| r.effort), Some(ReasoningEffort::Low));
+    let (body, _) = ResponsesEncoder.encode_request(&req).expect("encode responses body");
+    assert_eq!(body["reasoning"], serde_json::json!({"effort": "low", "summary": "auto"}));
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert_eq!(body["reasoning_effort"], "low");
+    assert!(body.get("reasoning").is_none());
+
+    let req = GeminiDecoder
+        .decode_with_model(
+            serde_json::json!({
+                "contents": [{"role": "user", "parts": [{"text": "hi"}]}],
+                "generationConfig": {"thinkingConfig": {"thinkingBudget": 0}},
+            }),
+            "gemini-2.5-flash",
+            false,
+        )
+        .expect("decode gemini body");
+    assert_eq!(req.reasoning, Some(ReasoningConfig::default()));
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(body["thinking"], serde_json::json!({"type": "disabled"}));
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert!(body.get("reasoning_effort").is_none());
+    let (body, _) = OllamaEncoder.encode_request(&req).expect("encode ollama body");
+    assert_eq!(body["think"], false);
+
+    let req = OllamaDecoder
+        .decode_request(serde_json::json!({
+            "model": "gpt-oss",
+            "think": "medium",
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .expect("decode ollama body");
+    assert!(!req.extra.contains_key("think"));
+    let (body, _) = OllamaEncoder.encode_request(&req).expect("encode ollama body");
+    assert_eq!(body["think"], "medium");
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(body["thinking"]["budget_tokens"], 16384);
+}
+
+#[test]
+fn gemini_thought_parts_parse_as_reasoning() {
+    let resp = GeminiResponseParser
+        .parse_response(serde_json::json!({
+            "candidates": [{
+                "content": {"role": "model", "parts": [
+                    {"text": "Compare both.", "thought": true},
+                    {"text": "42"},
+                ]},
+                "finishReason": "STOP",
+            }],
+        }))
+        .expect("parse gemini body");
+    assert_eq!(resp.reasoning_content.as_deref(), Some("Compare both."));
+    assert_eq!(resp.content, "42");
+}
+
+#[test]
+fn anthropic_cache_breakpoints_reach_anthropic_and_bedrock_egress() {
+    let ephemeral = serde_json::json!({"type": "ephemeral"});
+    let req = AnthropicDecoder
+        .decode_request(serde_json::json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 1024,
+            "system": [
+                {"type": "text", "text": "You are a coding agent."},
+                {"type": "text", "text": "Project rules.", "cache_control": {"type": "ephemeral"}},
+            ],
+            "tools": [{
+                "name": "read_file",
+                "input_schema": {"type": "object"},
+                "cache_control": {"type": "ephemeral"},
+            }],
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Here is the repo."},
+                    {"type": "text", "text": "Fix the bug.", "cache_control": {"type": "ephemeral"}},
+                ]},
+                {"role": "assistant", "content": "On it."},
+                {"role": "user", "content": "Thanks."},
+            ],
+        }))
+        .expect("decode anthropic body");
+    assert_eq!(req.messages[0].cache_control, Some(ephemeral.clone()));
+    assert_eq!(req.messages[1].cache_control, Some(ephemeral.clone()));
+    assert!(req.messages[2].cache_control.is_none());
+
+    let (body, _) = AnthropicEncoder.encode_request(&req).expect("encode anthropic body");
+    assert_eq!(body["system"][0]["text"], "You are a coding agent.\nProject rules.");
+    assert_eq!(body["system"][0]["cache_control"], ephemeral);
+    assert_eq!(body["tools"][0]["cache_control"], ephemeral);
+    let first = body["messages"][0]["content"].as_array().expect("user blocks");
+    assert_eq!(first.last().expect("last block")["cache_control"], ephemeral);
+    assert!(first[0].get("cache_control").is_none());
+    assert_eq!(body["messages"][2]["content"], serde_json::json!([{"type": "text", "text": "Thanks."}]));
+
+    let mut bedrock_req = req.clone();
+    bedrock_req.model = "anthropic.claude-sonnet-4-5-20250929-v1:0".to_string();
+    let (body, _) = BedrockEncoder.encode_request(&bedrock_req).expect("encode bedrock body");
+    let cache_point = serde_json::json!({"cachePoint": {"type": "default"}});
+    assert_eq!(body["system"].as_array().and_then(|s| s.last()), Some(&cache_point));
+    assert_eq!(body["toolConfig"]["tools"][1], cache_point);
+    assert_eq!(body["messages"][0]["content"].as_array().and_then(|c| c.last()), Some(&cache_point));
+
+    let (body, _) = OpenAIEncoder.encode_request(&req).expect("encode openai body");
+    assert!(!body.to_string().contains("cache_control"));
+}
+
+#[test]
+fn cached_prompt_tokens_parse_from_each_upstream() {
+    let resp = AnthropicResponseParser
+        .parse_response(serde_json::json!({
+            "id": "msg_1",
+            "model": "claude-sonnet-4-5",
+            "content": [{"type": "text", "text": "ok"}],
+            "stop_reason": "end_turn",
+            "usage": {
+                "input_tokens": 10,
+                "cache_creation_input_tokens": 200,
+                "cache_read_input_tokens": 1000,
+                "output_tokens": 5,
+            },
+        }))
+        .expect("parse anthropic body");
+    assert_eq!(resp.usage.input_tokens, 1210);
+    assert_eq!(resp.usage.cache_read_tokens, 1000);
+    assert_eq!(resp.usage.cache_creation_tokens, 200);
+    let anthropic = AnthropicResponseFormatter.format_response(&resp);
+    assert_eq!(anthropic["usage"]["input_tokens"], 10);
+    assert_eq!(anthropic["usage"]["cache_read_input_tokens"], 1000);
+    let openai = OpenAIResponseFormatter.format_response(&resp);
+    assert_eq!(openai["usage"]["prompt_tokens"], 1210);
+    assert_eq!(openai["usage"]["prompt_tokens_details"]["cached_tokens"], 1000);
+
+    let resp = OpenAIResponseParser
+        .parse_response(serde_json::json!({
+            "id": "chatcmpl-1",
+            "model": "gpt-4.1",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 2006, "completion_tokens": 3, "prompt_tokens_details": {"cached_tokens": 1920}},
+        }))
+        .expect("parse openai body");
+    assert_eq!((resp.usage.input_tokens, resp.usage.cache_read_tokens), (2006, 1920));
+    let anthropic = AnthropicResponseFormatter.format_response(&resp);
+    assert_eq!(anthropic["usage"]["input_tokens"], 86);
+    assert_eq!(anthropic["usage"]["cache_read_input_tokens"], 1920);
+
+    let resp = GeminiResponseParser
+        .parse_response(serde_json::json!({
+            "candidates": [{"content": {"role": "model", "parts": [{"text": "ok"}]}, "finishReason": "STOP"}],
+            "usageMetadata": {"promptTokenCount": 5000, "candidatesTokenCount": 4, "cachedContentTokenCount": 4096},
+        }))
+        .expect("parse gemini body");
+    assert_eq!((resp.usage.input_tokens, resp.usage.cache_read_tokens), (5000, 4096));
+
+    let resp = ResponsesResponseParser
+        .parse_response(serde_json::json!({
+            "id": "resp_1",
+            "model": "gpt-5",
+            "status": "completed",
+            "output": [],
+            "usage": {"input_tokens": 3000, "output_tokens": 7, "input_tokens_details": {"cached_tokens": 2048}},
+        }))
+        .expect("parse responses body");
+    assert_eq!(resp.usage.cache_read_tokens, 2048);
+
+    let resp = BedrockResponseParser
+        .parse_response(serde_json::json!({
+            "output": {"message": {"role": "assistant", "content": [{"text": "ok"}]}},
+            "stopReason": "end_turn",
+            "usage": {"inputTokens": 12, "outputTokens": 3, "cacheReadInputTokens": 800, "cacheWriteInputTokens": 100},
+        }))
+        .expect("parse bedrock body");
+    assert_eq!(resp.usage.input_tokens, 912);
+    assert_eq!((resp.usage.cache_read_tokens, resp.usage.cache_creation_tokens), (800, 100));
+}
@@ -12,6 +12,9 @@ Covers:
 from __future__ import annotations
 
 import argparse
+import base64
+import hashlib
+import hmac
 import json
 import os
 import socket
@@ -20,13 +23,17 @@ import sys
 import tempfile
 import threading
 import time
+import zlib
 from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
 from pathlib import Path
 from typing import Any
 from urllib.error import HTTPError, URLError
-from urllib.parse import urlsplit
+from urllib.parse import parse_qs, quote, urlsplit
 from urllib.request import Request, urlopen
 
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding, rsa
+
 
 def find_free_port() -> int:
     with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
@@ -85,8 +92,52 @@ def wait_until_ready(url: str, headers: dict[str, str], timeout_sec: float = 30.
     raise TimeoutError(f"server not ready: {url}, last_error={last_error}")
 
 
+# Throwaway service account for the Vertex AI token exchange.
+VERTEX_KEY = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+
+
+def _b64url_decode(part: str) -> bytes:
+    return base64.urlsafe_b64decode(part + "=" * (-len(part) % 4))
+
+
+PIXEL_PNG = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg=="
+
+
+def _one_line_pdf(text: str) -> str:
+    """Base64 of a single-page PDF showing `text`."""
+    stream = f"BT /F1 12 Tf 72 720 Td ({text}) Tj ET".encode()
+    objects = [
+        b"<< /Type /Catalog /Pages 2 0 R >>",
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R"
+        b" /Resources << /Font << /F1 5 0 R >> >> >>",
+        b"<< /Length %d >>\nstream\n" % len(stream) + stream + b"\nendstream",
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+    ]
+    out = bytearray(b"%PDF-1.4\n")
+    offsets = []
+    for number, body in enumerate(objects, start=1):
+        offsets.append(len(out))
+        out += b"%d 0 obj\n" % number + body + b"\nendobj\n"
+    xref = len(out)
+    out += b"xref\n0 %d\n0000000000 65535 f \n" % (len(objects) + 1)
+    out += b"".join(b"%010d 00000 n \n" % offset for offset in offsets)
+    out += b"trailer\n<< /Size %d /Root 1 0 R >>\nstartxref\n%d\n%%%%EOF\n" % (len(objects) + 1, xref)
+    return base64.b64encode(bytes(out)).decode()
+
+
+SPEC_PDF = _one_line_pdf("Nyro gateway spec")
+
+
 class MockProviderHandler(BaseHTTPRequestHandler):
     protocol_version = "HTTP/1.1"
+    flaky_calls = 0
+    last_chat_messages: list[Any] = []
+    last_responses_input: Any = None
+    last_ollama_messages: list[Any] = []
+    last_gemini_contents: list[Any] = []
+    last_gemini_generation_config: Any = None
+    vertex_token_mints = 0
 
     def log_message(self, fmt: str, *args: Any) -> None:  # noqa: D401
         # Keep smoke output clean.
@@ -95,13 +146,18 @@ class MockProviderHandler(BaseHTTPRequestHandler):
     def _read_json_body(self) -> dict[str, Any]:
         length = int(self.headers.get("content-length", "0"))
         raw = self.rfile.read(length) if length else b"{}"
+        self.raw_body = raw
         if not raw:
             return {}
         return json.loads(raw.decode("utf-8"))
 
-    def _write_json(self, status: int, payload: dict[str, Any]) -> None:
+    def _write_json(
+        self, status: int, payload: dict[str, Any], headers: dict[str, str] | None = None
+    ) -> None:
         body = json.dumps(payload).encode("utf-8")
         self.send_response(status)
+        for name, value in (headers or {}).items():
+            self.send_header(name, value)
         self.send_header("content-type", "application/json")
         self.send_header("content-length", str(len(body)))
         self.send_header("connection", "close")
@@ -131,13 +187,293 @@ class MockProviderHandler(BaseHTTPRequestHandler):
         self.wfile.write(raw)
         self.wfile.flush()
 
+    def _write_ndjson(self, records: list[dict[str, Any]]) -> None:
+        raw = "".join(json.dumps(r) + "\n" for r in records).encode("utf-8")
+        self.send_response(200)
+        self.send_header("content-type", "application/x-ndjson")
+        self.send_header("connection", "close")
+        self.send_header("content-length", str(len(raw)))
+        self.end_headers()
+        self.wfile.write(raw)
+        self.wfile.flush()
+
+    def _bedrock_signature_ok(self, path: str) -> bool:
+        """Recompute the SigV4 signature the way Bedrock does."""
+        auth = self.headers.get("authorization", "")
+        if not auth.startswith("AWS4-HMAC-SHA256 "):
+            return False
+        fields = dict(part.strip().split("=", 1) for part in auth[len("AWS4-HMAC-SHA256 "):].split(","))
+        access_key, date, region, service, _ = fields["Credential"].split("/")
+        signed_headers = fields["SignedHeaders"]
+        canonical_headers = "".join(
+            f"{name}:{self.headers.get(name, '').strip()}\n" for name in signed_headers.split(";")
+        )
+        canonical_uri = "/".join(quote(segment, safe="-_.~") for segment in path.split("/"))
+        canonical_request = "\n".join(
+            [
+                "POST",
+                canonical_uri,
+                "",
+                canonical_headers,
+                signed_headers,
+                hashlib.sha256(self.raw_body).hexdigest(),
+            ]
+        )
+        scope = f"{date}/{region}/{service}/aws4_request"
+        string_to_sign = "\n".join(
+            [
+                "AWS4-HMAC-SHA256",
+                self.headers.get("x-amz-date", ""),
+                scope,
+                hashlib.sha256(canonical_request.encode()).hexdigest(),
+            ]
+        )
+        key = b"AWS4upstream-secret"
+        for part in (date, region, service, "aws4_request"):
+            key = hmac.new(key, part.encode(), hashlib.sha256).digest()
+        expected = hmac.new(key, string_to_sign.encode(), hashlib.sha256).hexdigest()
+        return (
+            access_key == "AKIDMOCK"
+            and region == "eu-west-1"
+            and service == "bedrock"
+            and hmac.compare_digest(expected, fields["Signature"])
+        )
+
+    def _write_eventstream(self, events: list[tuple[str, dict[str, Any]]]) -> None:
+        raw = b""
+        for event_type, payload in events:
+            headers = b""
+            for name, value in [
+                (":event-type", event_type),
+                (":content-type", "application/json"),
+                (":message-type", "event"),
+            ]:
+                headers += bytes([len(name)]) + name.encode() + b"\x07"
+                headers += len(value).to_bytes(2, "big") + value.encode()
+            body = json.dumps(payload).encode("utf-8")
+            prelude = (16 + len(headers) + len(body)).to_bytes(4, "big") + len(headers).to_bytes(4, "big")
+            message = prelude + zlib.crc32(prelude).to_bytes(4, "big") + headers + body
+            raw += message + zlib.crc32(message).to_bytes(4, "big")
+
+        self.send_response(200)
+        self.send_header("content-type", "application/vnd.amazon.eventstream")
+        self.send_header("connection", "close")
+        self.send_header("content-length", str(len(raw)))
+        self.end_headers()
+        self.wfile.write(raw)
+        self.wfile.flush()
+
+    def _mint_vertex_token(self) -> None:
+        """JWT-bearer grant: verify the RS256 assertion, hand out a token."""
+        length = int(self.headers.get("content-length", "0"))
+        form = parse_qs(self.rfile.read(length).decode("utf-8"))
+        assertion = form.get("assertion", [""])[0]
+        try:
+            header, claims, signature = assertion.split(".")
+            VERTEX_KEY.public_key().verify(
+                _b64url_decode(signature),
+                f"{header}.{claims}".encode(),
+                padding.PKCS1v15(),
+                hashes.SHA256(),
+            )
+            claims_json = json.loads(_b64url_decode(claims))
+        except Exception as exc:  # noqa: BLE001
+            self._write_json(400, {"error": "invalid_grant", "error_description": str(exc)})
+            return
+        if form.get("grant_type") != ["urn:ietf:params:oauth:grant-type:jwt-bearer"] or claims_json.get(
+            "iss"
+        ) != "nyro-smoke@mock-project.iam.gserviceaccount.com":
+            self._write_json(400, {"error": "invalid_grant"})
+            return
+        MockProviderHandler.vertex_token_mints += 1
+        self._write_json(200, {"access_token": "vertex-access-token", "expires_in": 3599, "token_type": "Bearer"})
+
+    def do_GET(self) -> None:  # noqa: N802
+        # Remote image for media fetching; the generic content type makes the
+        # proxy sniff the real one.
+        if urlsplit(self.path).path == "/media/pixel":
+            raw = base64.b64decode(PIXEL_PNG)
+            self.send_response(200)
+            self.send_header("content-type", "application/octet-stream")
+            self.send_header("content-length", str(len(raw)))
+            self.end_headers()
+            self.wfile.write(raw)
+            return
+        self._write_json(404, {"error": f"unknown path: {self.path}"})
+
     def do_POST(self) -> None:  # noqa: N802
-        body = self._read_json_body()
         path = urlsplit(self.path).path
+        if path == "/token":
+            self._mint_vertex_token()
+            return
+        body = self._read_json_body()
+
+        # Simulated upstream outage, used to exercise route fallback.
+        if body.get("model") == "fail-mock":
+            self._write_json(503, {"error": {"message": "mock upstream unavailable"}})
+            return
+
+        # Rate-limit blip: every other call is a 429, exercising same-provider retry.
+        if body.get("model") == "flaky-mock":
+            MockProviderHandler.flaky_calls += 1
+            if MockProviderHandler.flaky_calls % 2 == 1:
+                self._write_json(
+                    429, {"error": {"message": "mock rate limited"}}, headers={"retry-after": "0"}
+                )
+                return
+
+        # Azure OpenAI: deployment in the path, api-version query, api-key header.
+        if path.startswith("/openai/deployments/") and path.endswith("/chat/completions"):
+            deployment = path.split("/")[3]
+            query = parse_qs(urlsplit(self.path).query)
+            if self.headers.get("api-key") != "upstream-secret" or query.get("api-version") != ["2024-06-01"]:
+                self._write_json(401, {"error": {"message": "mock azure auth failed"}})
+                return
+            self._write_json(
+                200,
+                {
+                    "id": "chatcmpl-azure",
+                    "object": "chat.completion",
+                    "model": deployment,
+                    "choices": [
+                        {
+                            "index": 0,
+                            "message": {"role": "assistant", "content": f"mock-azure:{deployment}"},
+                            "finish_reason": "stop",
+                        }
+                    ],
+                    "usage": {"prompt_tokens": 4, "completion_tokens": 2, "total_tokens": 6},
+                },
+            )
+            return
+
+        # Vertex AI publisher models: OAuth bearer token, model in the path.
+        if path.startswith("/v1/projects/mock-project/locations/us-central1/publishers/"):
+            if self.headers.get("authorization") != "Bearer vertex-access-token":
+                self._write_json(401, {"error": {"code": 401, "status": "UNAUTHENTICATED"}})
+                return
+            publisher, model_method = path.split("/publishers/", 1)[1].split("/models/", 1)
+            model, method = model_method.split(":", 1)
+            if publisher == "google" and method == "generateContent":
+                self._write_json(
+                    200,
+                    {
+                        "candidates": [
+                            {
+                                "content": {"role": "model", "parts": [{"text": f"mock-vertex:{model}"}]},
+                                "finishReason": "STOP",
+                            }
+                        ],
+                        "usageMetadata": {"promptTokenCount": 3, "candidatesTokenCount": 2},
+                    },
+                )
+                return
+            if publisher == "anthropic" and method == "rawPredict":
+                if "model" in body or body.get("anthropic_version") != "vertex-2023-10-16":
+                    self._write_json(400, {"error": {"message": f"bad rawPredict body: {body}"}})
+                    return
+                self._write_json(
+                    200,
+                    {
+                        "id": "msg_vertex",
+                        "type": "message",
+                        "role": "assistant",
+                        "model": model,
+                        "content": [{"type": "text", "text": f"mock-vertex:{model}"}],
+                        "stop_reason": "end_turn",
+                        "usage": {"input_tokens": 3, "output_tokens": 2},
+                    },
+                )
+                return
+            self._write_json(404, {"error": {"message": f"unexpected vertex call {path}"}})
+            return
+
+        # Bedrock Converse: model id in the (encoded) path, SigV4-signed.
+        if path.startswith("/model/") and path.endswith(("/converse", "/converse-stream")):
+            if not self._bedrock_signature_ok(path):
+                self._write_json(403, {"message": "The request signature we calculated does not match"})
+                return
+            model_id = path.split("/")[2].replace("%3A", ":")
+            text = f"mock-bedrock:{model_id}"
+            if path.endswith("/converse-stream"):
+                self._write_eventstream(
+                    [
+                        ("messageStart", {"role": "assistant"}),
+                        ("contentBlockDelta", {"contentBlockIndex": 0, "delta": {"text": text}}),
+                        ("contentBlockStop", {"contentBlockIndex": 0}),
+                        ("messageStop", {"stopReason": "end_turn"}),
+                        ("metadata", {"usage": {"inputTokens": 5, "outputTokens": 3}, "metrics": {"latencyMs": 1}}),
+                    ]
+                )
+                return
+            self._write_json(
+                200,
+                {
+                    "output": {"message": {"role": "assistant", "content": [{"text": text}]}},
+                    "stopReason": "end_turn",
+                    "usage": {"inputTokens": 5, "outputTokens": 3, "totalTokens": 8},
+                },
+            )
+            return
 
         # OpenAI upstream mock
+        # OpenAI Responses API dialect.
+        if path == "/v1/responses":
+            model = str(body.get("model", "mock-responses-model"))
+            if not isinstance(body.get("input"), (list, str)) or "messages" in body:
+                self._write_json(400, {"error": {"message": "mock responses expects input items"}})
+                return
+            MockProviderHandler.last_responses_input = body.get("input")
+            text = f"mock-responses:{model}"
+            reasoning = {
+                "type": "reasoning",
+                "id": "rs_mock",
+                "summary": [{"type": "summary_text", "text": "mock reasoning"}],
+                "encrypted_content": "enc-mock",
+            }
+            message = {
+                "type": "message",
+                "id": "msg_mock",
+                "status": "completed",
+                "role": "assistant",
+                "content": [{"type": "output_text", "text": text, "annotations": []}],
+            }
+            usage = {"input_tokens": 4, "output_tokens": 3, "total_tokens": 7}
+            if body.get("stream"):
+                self._write_sse(
+                    [
+                        ("response.created", {"type": "response.created", "response": {"id": "resp_mock", "model": model}}),
+                        ("response.output_item.added", {"type": "response.output_item.added", "output_index": 0, "item": reasoning}),
+                        (
+                            "response.reasoning_summary_text.delta",
+                            {"type": "response.reasoning_summary_text.delta", "output_index": 0, "delta": "mock reasoning"},
+                        ),
+                        ("response.output_item.done", {"type": "response.output_item.done", "output_index": 0, "item": reasoning}),
+                        ("response.output_text.delta", {"type": "response.output_text.delta", "output_index": 1, "delta": "mock-responses:"}),
+                        ("response.output_text.delta", {"type": "response.output_text.delta", "output_index": 1, "delta": model}),
+                        (
+                            "response.completed",
+                            {"type": "response.completed", "response": {"id": "resp_mock", "status": "completed", "usage": usage}},
+                        ),
+                    ]
+                )
+                return
+            self._write_json(
+                200,
+                {
+                    "id": "resp_mock",
+                    "object": "response",
+                    "status": "completed",
+                    "model": model,
+                    "output": [reasoning, message],
+                    "usage": usage,
+                },
+            )
+            return
+
         if path == "/v1/chat/completions":
             model = str(body.get("model", "mock-openai-model"))
+            MockProviderHandler.last_chat_messages = body.get("messages", [])
             if body.get("stream"):
                 self._write_sse(
                     [
@@ -201,7 +537,53 @@ class MockProviderHandler(BaseHTTPRequestHandler):
             )
             return
 
+        if path == "/v1/embeddings":
+            inputs = body.get("input", [])
+            inputs = [inputs] if isinstance(inputs, str) else inputs
+            self._write_json(
+                200,
+                {
+                    "object": "list",
+                    "data": [
+                        {"object": "embedding", "index": i, "embedding": [0.1 * (i + 1), 0.5]}
+                        for i in range(len(inputs))
+                    ],
+                    "model": body.get("model"),
+                    "usage": {"prompt_tokens": 3 * len(inputs), "total_tokens": 3 * len(inputs)},
+                },
+            )
+            return
+
+        # Ollama native upstream mock
+        if path == "/api/chat":
+            model = str(body.get("model", "llama-mock"))
+            MockProviderHandler.last_ollama_messages = body.get("messages", [])
+            done = {
+                "model": model,
+                "message": {"role": "assistant", "content": ""},
+                "done": True,
+                "done_reason": "stop",
+                "prompt_eval_count": 4,
+                "eval_count": 2,
+            }
+            if body.get("stream"):
+                self._write_ndjson(
+                    [
+                        {"model": model, "message": {"role": "assistant", "content": "mock-ollama"}, "done": False},
+                        {"model": model, "message": {"role": "assistant", "content": "-stream"}, "done": False},
+                        done,
+                    ]
+                )
+                return
+            done["message"]["content"] = "mock-ollama"
+            self._write_json(200, done)
+            return
+
         # Anthropic upstream mock
+        if path == "/v1/messages/count_tokens":
+            self._write_json(200, {"input_tokens": 42})
+            return
+
         if path == "/v1/messages":
             model = str(body.get("model", "claude-mock"))
             if body.get("stream"):
@@ -261,13 +643,25 @@ class MockProviderHandler(BaseHTTPRequestHandler):
                     "model": model,
                     "content": [{"type": "text", "text": "mock-anthropic"}],
                     "stop_reason": "end_turn",
-                    "usage": {"input_tokens": 9, "output_tokens": 6},
+                    "usage": {"input_tokens": 9, "cache_read_input_tokens": 90, "output_tokens": 6},
                 },
             )
             return
 
         # Gemini upstream mock
         if path.startswith("/v1beta/models/"):
+            MockProviderHandler.last_gemini_contents = body.get("contents", [])
+            MockProviderHandler.last_gemini_generation_config = body.get("generationConfig")
+            if path.endswith(":countTokens"):
+                self._write_json(200, {"totalTokens": 7})
+                return
+
+            if path.endswith(":batchEmbedContents"):
+                self._write_json(
+                    200, {"embeddings": [{"values": [0.25, 0.75]} for _ in body.get("requests", [])]}
+                )
+                return
+
             if path.endswith(":streamGenerateContent"):
                 self._write_sse(
                     [
@@ -354,6 +748,9 @@ def run_smoke() -> None:
                 admin_key,
                 "--webui-dir",
                 "./webui/dist",
+                "--fetch-remote-media",
+                "--media-fetch-allow-private",
+                "--document-text-fallback",
             ]
 
             proc = subprocess.Popen(
@@ -393,6 +790,7 @@ def run_smoke() -> None:
                 ("openai", "mock-openai"),
                 ("anthropic", "mock-anthropic"),
                 ("gemini", "mock-gemini"),
+                ("ollama", "mock-ollama"),
             ]:
                 status, resp = http_request(
                     "POST",
@@ -400,7 +798,8 @@ def run_smoke() -> None:
                     payload={
                         "name": name,
                         "protocol": protocol,
-                        "base_url": mock_base,
+                        # Ollama is configured with its /v1 shim URL; native calls strip it.
+                        "base_url": f"{mock_base}/v1" if protocol == "ollama" else mock_base,
                         "api_key": "upstream-secret",
                     },
                     headers=admin_headers,
@@ -408,25 +807,141 @@ def run_smoke() -> None:
                 assert_true(status == 200, f"create provider {protocol} failed: {status} {resp}")
                 provider_ids[protocol] = resp["data"]["id"]
 
+            status, resp = http_request(
+                "POST",
+                f"{admin_base}/api/v1/providers",
+                payload={
+                    "name": "mock-azure",
+                    "vendor": "azure",
+                    "protocol": "openai",
+                    "base_url": mock_base,
+                    "api_key": "upstream-secret",
+                    "api_version": "2024-06-01",
+                },
+                headers=admin_headers,
+            )
+            assert_true(
+                status == 200 and resp["data"]["api_version"] == "2024-06-01",
+                f"create provider azure failed: {status} {resp}",
+            )
+            provider_ids["azure"] = resp["data"]["id"]
+
+            status, resp = http_request(
+                "POST",
+                f"{admin_base}/api/v1/providers",
+                payload={
+                    "name": "mock-responses",
+                    "protocol": "openai",
+                    "base_url": mock_base,
+                    "api_key": "upstream-secret",
+                    "api_dialect": "responses",
+                },
+                headers=admin_headers,
+            )
+            assert_true(
+                status == 200 and resp["data"]["api_dialect"] == "responses",
+                f"create provider responses failed: {status} {resp}",
+            )
+            provider_ids["responses"] = resp["data"]["id"]
+
+            status, resp = http_request(
+                "POST",
+                f"{admin_base}/api/v1/providers",
+                payload={
+                    "name": "mock-bedrock",
+                    "protocol": "bedrock",
+                    "base_url": mock_base,
+                    "api_key": "upstream-secret",
+                    "access_key_id": "AKIDMOCK",
+                    "cloud_region": "eu-west-1",
+                },
+                headers=admin_headers,
+            )
+            assert_true(status == 200, f"create provider bedrock failed: {status} {resp}")
+            provider_ids["bedrock"] = resp["data"]["id"]
+
+            service_account = {
+                "type": "service_account",
+                "project_id": "mock-project",
+                "private_key_id": "mock-key",
+                "private_key": VERTEX_KEY.private_bytes(
+                    serialization.Encoding.PEM,
+                    serialization.PrivateFormat.PKCS8,
+                    serialization.NoEncryption(),
+                ).decode(),
+                "client_email": "nyro-smoke@mock-project.iam.gserviceaccount.com",
+                "token_uri": f"{mock_base}/token",
+            }
+            for protocol in ("gemini", "anthropic"):
+                status, resp = http_request(
+                    "POST",
+                    f"{admin_base}/api/v1/providers",
+                    payload={
+                        "name": f"mock-vertex-{protocol}",
+                        "vendor": "vertex",
+                        "protocol": protocol,
+                        "base_url": mock_base,
+                        "api_key": json.dumps(service_account),
+                        "cloud_region": "us-central1",
+                    },
+                    headers=admin_headers,
+                )
+                assert_true(status == 200, f"create provider vertex {protocol} failed: {status} {resp}")
+                provider_ids[f"vertex-{protocol}"] = resp["data"]["id"]
+
+            # Provider secrets are masked in admin responses; reveal is explicit.
+            status, resp = http_request("GET", f"{admin_base}/api/v1/providers", headers=admin_headers)
+            assert_true(status == 200, f"list providers failed: {status} {resp}")
+            masked = {p["api_key"] for p in resp["data"]}
+            assert_true("upstream-secret" not in masked, f"provider api_key leaked in list: {masked}")
+            status, resp = http_request(
+                "POST",
+                f"{admin_base}/api/v1/providers/{provider_ids['openai']}/reveal-key",
+                headers=admin_headers,
+            )
+            assert_true(
+                status == 200 and resp["data"] == "upstream-secret",
+                f"reveal provider key failed: {status} {resp}",
+            )
+
             # Create routes.
             routes = [
                 ("nyro-chat", "openai", "nyro-chat", provider_ids["openai"], "gpt-mock"),
                 ("nyro-claude", "anthropic", "nyro-claude", provider_ids["anthropic"], "claude-mock"),
                 ("gemini-2.0-flash", "gemini", "gemini-2.0-flash", provider_ids["gemini"], "gemini-2.0-flash"),
+                ("nyro-fallback", "openai", "nyro-fallback", provider_ids["openai"], "fail-mock"),
+                ("nyro-flaky", "openai", "nyro-flaky", provider_ids["openai"], "flaky-mock"),
+                ("nyro-claude-via-openai", "anthropic", "nyro-claude-via-openai", provider_ids["openai"], "gpt-mock"),
+                ("nyro-embed", "gemini", "nyro-embed", provider_ids["openai"], "embed-mock"),
+                ("nyro-ollama", "openai", "nyro-ollama", provider_ids["ollama"], "llama-mock"),
+                ("nyro-gemini", "openai", "nyro-gemini", provider_ids["gemini"], "gemini-mock"),
+                ("nyro-embed-openai", "openai", "nyro-embed-openai", provider_ids["openai"], "embed-mock"),
+                ("nyro-embed-gemini", "openai", "nyro-embed-gemini", provider_ids["gemini"], "text-embedding-mock"),
+                ("nyro-local", "ollama", "nyro-local", provider_ids["anthropic"], "claude-mock"),
+                ("nyro-azure", "openai", "nyro-azure", provider_ids["azure"], "gpt4o-prod"),
+                ("nyro-responses", "openai", "nyro-responses", provider_ids["responses"], "gpt-mock"),
+                ("nyro-responses-claude", "anthropic", "nyro-responses-claude", provider_ids["responses"], "gpt-mock"),
+                ("nyro-bedrock", "anthropic", "nyro-bedrock", provider_ids["bedrock"], "anthropic.claude-mock-v1:0"),
+                ("nyro-vertex", "openai", "nyro-vertex", provider_ids["vertex-gemini"], "gemini-mock"),
+                ("nyro-vertex-claude", "openai", "nyro-vertex-claude", provider_ids["vertex-anthropic"], "claude-mock@1"),
             ]
+            fallbacks = {"nyro-fallback": (provider_ids["anthropic"], "claude-mock")}
             route_ids: list[str] = []
             for name, ingress_protocol, virtual_model, target_provider, target_model in routes:
+                payload = {
+                    "name": name,
+                    "ingress_protocol": ingress_protocol,
+                    "virtual_model": virtual_model,
+                    "target_provider": target_provider,
+                    "target_model": target_model,
+                    "access_control": True,
+                }
+                if name in fallbacks:
+                    payload["fallback_provider"], payload["fallback_model"] = fallbacks[name]
                 status, resp = http_request(
                     "POST",
                     f"{admin_base}/api/v1/routes",
-                    payload={
-                        "name": name,
-                        "ingress_protocol": ingress_protocol,
-                        "virtual_model": virtual_model,
-                        "target_provider": target_provider,
-                        "target_model": target_model,
-                        "access_control": True,
-                    },
+                    payload=payload,
                     headers=admin_headers,
                 )
                 assert_true(status == 200, f"create route {name} failed: {status} {resp}")
@@ -451,6 +966,30 @@ def run_smoke() -> None:
             proxy_key = key_resp["data"]["key"]
             proxy_headers = {"authorization": f"Bearer {proxy_key}"}
 
+            # Model listing: access-controlled routes are only visible to a bound key.
+            status, resp = http_request("GET", f"{proxy_base}/v1/models")
+            assert_true(status == 200 and resp["data"] == [], f"anonymous model list not empty: {status} {resp}")
+            status, resp = http_request("GET", f"{proxy_base}/v1/models", headers=proxy_headers)
+            ids = {m["id"] for m in resp.get("data", [])}
+            assert_true(status == 200 and "nyro-chat" in ids, f"openai model list failed: {status} {resp}")
+            status, resp = http_request(
+                "GET",
+                f"{proxy_base}/v1/models",
+                headers={**proxy_headers, "anthropic-version": "2023-06-01"},
+            )
+            assert_true(
+                status == 200
+                and {m["id"] for m in resp["data"]}
+                == {"nyro-claude", "nyro-claude-via-openai", "nyro-bedrock", "nyro-responses-claude"},
+                f"anthropic model list failed: {status} {resp}",
+            )
+            status, resp = http_request("GET", f"{proxy_base}/v1beta/models", headers=proxy_headers)
+            assert_true(
+                status == 200
+                and {m["name"] for m in resp["models"]} == {"models/gemini-2.0-flash", "models/nyro-embed"},
+                f"gemini model list failed: {status} {resp}",
+            )
+
             # OpenAI non-stream
             status, resp = http_request(
                 "POST",
@@ -475,6 +1014,372 @@ def run_smoke() -> None:
             assert_true("[DONE]" in stream_text_s, "openai stream missing [DONE]")
             assert_true("mock-" in stream_text_s and "stream" in stream_text_s, "openai stream missing text deltas")
 
+            # OpenAI ingress to a native Ollama upstream (/api/chat, NDJSON stream).
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-ollama", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200
+                and resp["choices"][0]["message"]["content"] == "mock-ollama"
+                and resp["usage"]["completion_tokens"] == 2,
+                f"ollama chat failed: {status} {resp}",
+            )
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-ollama", "stream": True, "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            stream_text_s = str(stream_text)
+            assert_true(
+                status == 200 and "mock-ollama" in stream_text_s and "-stream" in stream_text_s and "[DONE]" in stream_text_s,
+                f"ollama stream failed: {status} {stream_text}",
+            )
+
+            # Ollama ingress (/api/chat, /api/generate, /api/tags) to an Anthropic upstream.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/api/chat",
+                payload={"model": "nyro-local", "stream": False, "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp["message"]["content"] == "mock-anthropic" and resp["done"] is True,
+                f"ollama ingress chat failed: {status} {resp}",
+            )
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/api/chat",
+                payload={"model": "nyro-local", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            records = [json.loads(line) for line in str(stream_text).splitlines() if line.strip()]
+            assert_true(
+                status == 200
+                and records
+                and records[-1]["done"] is True
+                and "mock-anthropic" in "".join(r["message"]["content"] for r in records),
+                f"ollama ingress ndjson stream failed: {status} {stream_text}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/api/generate",
+                payload={"model": "nyro-local", "prompt": "hello", "stream": False},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp["response"] == "mock-anthropic",
+                f"ollama ingress generate failed: {status} {resp}",
+            )
+            status, resp = http_request("GET", f"{proxy_base}/api/tags", headers=proxy_headers)
+            assert_true(
+                status == 200 and [m["name"] for m in resp["models"]] == ["nyro-local"],
+                f"ollama tags failed: {status} {resp}",
+            )
+
+            # Azure OpenAI egress: the route's target model names the deployment.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-azure", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp["choices"][0]["message"]["content"] == "mock-azure:gpt4o-prod",
+                f"azure chat failed: {status} {resp}",
+            )
+
+            # Multimodal: a remote image is downloaded for inline-only upstreams
+            # and a mislabeled data URI gets its real media type.
+            image_message = [
+                {
+                    "role": "user",
+                    "content": [
+                        {"type": "text", "text": "describe"},
+                        {"type": "image_url", "image_url": {"url": f"{mock_base}/media/pixel"}},
+                        {"type": "image_url", "image_url": {"url": f"data:image/jpeg;base64,{PIXEL_PNG}"}},
+                    ],
+                }
+            ]
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-ollama", "messages": image_message},
+                headers=proxy_headers,
+            )
+            images = MockProviderHandler.last_ollama_messages[0].get("images")
+            assert_true(status == 200 and images == [PIXEL_PNG, PIXEL_PNG], f"ollama image fetch failed: {status} {resp}")
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-gemini", "messages": image_message},
+                headers=proxy_headers,
+            )
+            parts = MockProviderHandler.last_gemini_contents[0]["parts"]
+            inline = [{"mimeType": "image/png", "data": PIXEL_PNG}] * 2
+            assert_true(
+                status == 200 and [p.get("inlineData") for p in parts[1:]] == inline,
+                f"gemini inline images failed: {status} {resp} {parts}",
+            )
+
+            # Documents: Gemini takes the PDF itself, Ollama gets its text.
+            pdf_message = [
+                {
+                    "role": "user",
+                    "content": [
+                        {"type": "text", "text": "review"},
+                        {"type": "file", "file": {"filename": "spec.pdf", "file_data": f"data:application/pdf;base64,{SPEC_PDF}"}},
+                    ],
+                }
+            ]
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-gemini", "messages": pdf_message},
+                headers=proxy_headers,
+            )
+            parts = MockProviderHandler.last_gemini_contents[0]["parts"]
+            assert_true(
+                status == 200 and parts[1].get("inlineData") == {"mimeType": "application/pdf", "data": SPEC_PDF},
+                f"gemini inline document failed: {status} {resp} {parts}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-ollama", "messages": pdf_message},
+                headers=proxy_headers,
+            )
+            content = MockProviderHandler.last_ollama_messages[0].get("content", "")
+            assert_true(
+                status == 200 and '<document name="spec.pdf">' in content and "Nyro gateway spec" in content,
+                f"ollama document text fallback failed: {status} {resp} {content!r}",
+            )
+
+            # Reasoning effort becomes a thinking budget on Gemini.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={
+                    "model": "nyro-gemini",
+                    "reasoning_effort": "low",
+                    "messages": [{"role": "user", "content": "think a little"}],
+                },
+                headers=proxy_headers,
+            )
+            thinking = (MockProviderHandler.last_gemini_generation_config or {}).get("thinkingConfig")
+            assert_true(
+                status == 200 and thinking == {"thinkingBudget": 4096, "includeThoughts": True},
+                f"reasoning effort not mapped to gemini thinking budget: {status} {resp} {thinking}",
+            )
+
+            # Responses dialect egress: chat clients are translated, Responses
+            # clients get reasoning items back with their encrypted content.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-responses", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp["choices"][0]["message"]["content"] == "mock-responses:gpt-mock",
+                f"responses-dialect chat failed: {status} {resp}",
+            )
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-responses", "stream": True, "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            stream_text_s = str(stream_text)
+            assert_true(
+                status == 200 and "mock-responses:" in stream_text_s and "[DONE]" in stream_text_s,
+                f"responses-dialect chat stream failed: {status} {stream_text}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/responses",
+                payload={"model": "nyro-responses", "input": "hello"},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200
+                and resp["output"][0].get("encrypted_content") == "enc-mock"
+                and resp["output_text"] == "mock-responses:gpt-mock",
+                f"responses passthrough failed: {status} {resp}",
+            )
+
+            # Reasoning round trip for an Anthropic client: the encrypted
+            # reasoning comes back as a signed thinking block and is sent
+            # upstream again as a reasoning item.
+            anth_headers = {**proxy_headers, "anthropic-version": "2023-06-01"}
+            question = {"role": "user", "content": "hello"}
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/messages",
+                payload={"model": "nyro-responses-claude", "max_tokens": 64, "messages": [question]},
+                headers=anth_headers,
+            )
+            thinking = resp.get("content", [{}])[0] if status == 200 else {}
+            assert_true(
+                thinking.get("type") == "thinking" and thinking.get("signature") == "enc-mock",
+                f"responses reasoning not signed for anthropic client: {status} {resp}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/messages",
+                payload={
+                    "model": "nyro-responses-claude",
+                    "max_tokens": 64,
+                    "messages": [question, {"role": "assistant", "content": resp["content"]}, {"role": "user", "content": "more"}],
+                },
+                headers=anth_headers,
+            )
+            sent = MockProviderHandler.last_responses_input or []
+            assert_true(
+                status == 200 and any(i.get("type") == "reasoning" and i.get("encrypted_content") == "enc-mock" for i in sent),
+                f"thinking signature not replayed as encrypted reasoning: {status} {resp} {sent}",
+            )
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/v1/messages",
+                payload={"model": "nyro-responses-claude", "max_tokens": 64, "stream": True, "messages": [question]},
+                headers=anth_headers,
+                timeout=15.0,
+            )
+            assert_true(
+                status == 200 and '"signature":"enc-mock"' in str(stream_text).replace(" ", ""),
+                f"anthropic stream missing signature_delta: {status} {stream_text}",
+            )
+
+            # Stateful Responses API on a Chat Completions provider: stored
+            # responses expand `previous_response_id` and can be read back.
+            status, first = http_request(
+                "POST",
+                f"{proxy_base}/v1/responses",
+                payload={"model": "nyro-chat", "input": "first question"},
+                headers=proxy_headers,
+            )
+            assert_true(status == 200 and first["output_text"] == "mock-openai", f"stored response failed: {status} {first}")
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/responses",
+                payload={"model": "nyro-chat", "input": "second question", "previous_response_id": first["id"]},
+                headers=proxy_headers,
+            )
+            sent = [m.get("content") for m in MockProviderHandler.last_chat_messages]
+            assert_true(
+                status == 200 and sent == ["first question", "mock-openai", "second question"],
+                f"previous_response_id not expanded: {status} {resp} {sent}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/responses",
+                payload={"model": "nyro-chat", "input": "hi", "previous_response_id": "resp_missing"},
+                headers=proxy_headers,
+            )
+            assert_true(status == 404, f"unknown previous_response_id should 404: {status} {resp}")
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/v1/responses",
+                payload={"model": "nyro-chat", "input": "streamed", "stream": True},
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            assert_true(status == 200 and "response.completed" in str(stream_text), f"responses stream failed: {status}")
+            # The mock reuses one completion id, so the streamed answer is now
+            # the one stored under it.
+            status, resp = http_request("GET", f"{proxy_base}/v1/responses/{first['id']}", headers=proxy_headers)
+            assert_true(
+                status == 200 and resp["id"] == first["id"] and resp["output_text"] == "mock-stream",
+                f"get stored response failed: {status} {resp}",
+            )
+            status, resp = http_request("GET", f"{proxy_base}/v1/responses/{first['id']}")
+            assert_true(status == 404, f"stored response visible without its api key: {status} {resp}")
+            status, resp = http_request("DELETE", f"{proxy_base}/v1/responses/{first['id']}", headers=proxy_headers)
+            assert_true(status == 200 and resp["deleted"] is True, f"delete stored response failed: {status} {resp}")
+            status, _ = http_request("GET", f"{proxy_base}/v1/responses/{first['id']}", headers=proxy_headers)
+            assert_true(status == 404, f"deleted response still readable: {status}")
+
+            # Bedrock egress: SigV4-signed Converse, binary event-stream for streaming.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/messages",
+                payload={
+                    "model": "nyro-bedrock",
+                    "max_tokens": 64,
+                    "messages": [{"role": "user", "content": "hello"}],
+                },
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp["content"][0]["text"] == "mock-bedrock:anthropic.claude-mock-v1:0",
+                f"bedrock messages failed: {status} {resp}",
+            )
+            status, stream_text = http_request(
+                "POST",
+                f"{proxy_base}/v1/messages",
+                payload={
+                    "model": "nyro-bedrock",
+                    "max_tokens": 64,
+                    "stream": True,
+                    "messages": [{"role": "user", "content": "hello"}],
+                },
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            stream_text_s = str(stream_text)
+            assert_true(
+                status == 200
+                and "mock-bedrock:anthropic.claude-mock-v1:0" in stream_text_s
+                and "message_stop" in stream_text_s,
+                f"bedrock stream failed: {status} {stream_text}",
+            )
+
+            # Vertex AI egress: one minted token serves both publishers.
+            for model, upstream_model in [("nyro-vertex", "gemini-mock"), ("nyro-vertex-claude", "claude-mock@1")]:
+                status, resp = http_request(
+                    "POST",
+                    f"{proxy_base}/v1/chat/completions",
+                    payload={"model": model, "messages": [{"role": "user", "content": "hello"}]},
+                    headers=proxy_headers,
+                )
+                assert_true(
+                    status == 200 and resp["choices"][0]["message"]["content"] == f"mock-vertex:{upstream_model}",
+                    f"vertex chat {model} failed: {status} {resp}",
+                )
+            assert_true(
+                MockProviderHandler.vertex_token_mints == 1,
+                f"vertex token not cached: {MockProviderHandler.vertex_token_mints} mints",
+            )
+
+            # Multi-target route: two weighted targets on the OpenAI route.
+            for target_model, weight in [("gpt-mock", 3), ("gpt-mock-mirror", 1)]:
+                status, resp = http_request(
+                    "POST",
+                    f"{admin_base}/api/v1/routes/{route_ids[0]}/targets",
+                    payload={"provider_id": provider_ids["openai"], "model": target_model, "weight": weight},
+                    headers=admin_headers,
+                )
+                assert_true(status == 200, f"create route target failed: {status} {resp}")
+            status, resp = http_request(
+                "GET", f"{admin_base}/api/v1/routes/{route_ids[0]}/targets", headers=admin_headers
+            )
+            assert_true(status == 200 and len(resp["data"]) == 2, f"list route targets failed: {status} {resp}")
+            for _ in range(2):
+                status, resp = http_request(
+                    "POST",
+                    f"{proxy_base}/v1/chat/completions",
+                    payload={"model": "nyro-chat", "messages": [{"role": "user", "content": "hello"}]},
+                    headers=proxy_headers,
+                )
+                assert_true(status == 200, f"multi-target request failed: {status} {resp}")
+
             # Anthropic stream
             status, anth_stream = http_request(
                 "POST",
@@ -503,6 +1408,105 @@ def run_smoke() -> None:
             )
             assert_true("mock-anthropic" in anth_text, "anthropic stream missing text delta")
 
+            # count_tokens: passed through to Anthropic upstreams, estimated locally otherwise.
+            count_payload = {"model": "nyro-claude", "messages": [{"role": "user", "content": "hello there"}]}
+            status, resp = http_request(
+                "POST", f"{proxy_base}/v1/messages/count_tokens", payload=count_payload, headers=proxy_headers
+            )
+            assert_true(status == 200 and resp == {"input_tokens": 42}, f"count_tokens passthrough failed: {status} {resp}")
+            count_payload["model"] = "nyro-claude-via-openai"
+            status, resp = http_request(
+                "POST", f"{proxy_base}/v1/messages/count_tokens", payload=count_payload, headers=proxy_headers
+            )
+            assert_true(
+                status == 200 and 0 < resp.get("input_tokens", 0) < 42,
+                f"count_tokens estimate failed: {status} {resp}",
+            )
+
+            # Gemini countTokens: passed through to Gemini upstreams, estimated locally otherwise.
+            gem_count = {"contents": [{"role": "user", "parts": [{"text": "hello there"}]}]}
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1beta/models/gemini-2.0-flash:countTokens",
+                payload=gem_count,
+                headers=proxy_headers,
+            )
+            assert_true(status == 200 and resp == {"totalTokens": 7}, f"gemini countTokens passthrough failed: {status} {resp}")
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1beta/models/nyro-embed:countTokens",
+                payload={"generateContentRequest": gem_count},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and 0 < resp.get("totalTokens", 0) < 7,
+                f"gemini countTokens estimate failed: {status} {resp}",
+            )
+
+            # Gemini embeddings, converted to an OpenAI-compatible upstream.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1beta/models/nyro-embed:embedContent",
+                payload={"content": {"parts": [{"text": "hello"}]}},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and resp == {"embedding": {"values": [0.1, 0.5]}},
+                f"gemini embedContent failed: {status} {resp}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1beta/models/nyro-embed:batchEmbedContents",
+                payload={
+                    "requests": [
+                        {"model": "models/nyro-embed", "content": {"parts": [{"text": "a"}]}},
+                        {"model": "models/nyro-embed", "content": {"parts": [{"text": "b"}]}},
+                    ]
+                },
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200 and [e["values"][0] for e in resp.get("embeddings", [])] == [0.1, 0.2],
+                f"gemini batchEmbedContents failed: {status} {resp}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1beta/models/nyro-embed:predict",
+                payload={},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 400 and resp["error"]["status"] == "INVALID_ARGUMENT",
+                f"unsupported gemini action not rejected: {status} {resp}",
+            )
+
+            # OpenAI embeddings, native and converted to Gemini batchEmbedContents.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/embeddings",
+                payload={"model": "nyro-embed-openai", "input": ["a", "b"], "encoding_format": "float"},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200
+                and [d["embedding"] for d in resp.get("data", [])] == [[0.1, 0.5], [0.2, 0.5]]
+                and resp["model"] == "nyro-embed-openai"
+                and resp["usage"]["prompt_tokens"] == 6,
+                f"openai embeddings failed: {status} {resp}",
+            )
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/embeddings",
+                payload={"model": "nyro-embed-gemini", "input": "hello", "encoding_format": "base64"},
+                headers=proxy_headers,
+            )
+            assert_true(
+                status == 200
+                and resp["data"][0]["embedding"] == "AACAPgAAQD8="
+                and resp["usage"]["prompt_tokens"] > 0,
+                f"openai embeddings via gemini failed: {status} {resp}",
+            )
+
             # Gemini non-stream
             status, gem_resp = http_request(
                 "POST",
@@ -525,6 +1529,67 @@ def run_smoke() -> None:
             assert_true(status == 200, f"gemini stream failed: {status} {gem_stream}")
             assert_true("mock-gemini-stream" in str(gem_stream), "gemini stream missing text delta")
 
+            # Fallback: primary upstream returns 503, request is replayed on the Anthropic provider.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-fallback", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(status == 200, f"fallback non-stream failed: {status} {resp}")
+            content = resp["choices"][0]["message"]["content"]
+            assert_true(content == "mock-anthropic", f"unexpected fallback content: {content}")
+
+            status, fb_stream = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-fallback", "stream": True, "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+                timeout=15.0,
+            )
+            assert_true(status == 200, f"fallback stream failed: {status} {fb_stream}")
+            assert_true("[DONE]" in str(fb_stream), "fallback stream missing [DONE]")
+
+            # Retry: a 429 with Retry-After is retried on the same provider.
+            status, resp = http_request(
+                "POST",
+                f"{proxy_base}/v1/chat/completions",
+                payload={"model": "nyro-flaky", "messages": [{"role": "user", "content": "hello"}]},
+                headers=proxy_headers,
+            )
+            assert_true(status == 200, f"retry after upstream 429 failed: {status} {resp}")
+            assert_true(MockProviderHandler.flaky_calls == 2, "expected one upstream retry")
+
+            # Rate limit: an rpm=1 key gets a 429 in the ingress protocol's error shape.
+            status, key_resp = http_request(
+                "POST",
+                f"{admin_base}/api/v1/api-keys",
+                payload={"name": "smoke-limited", "rpm": 1, "route_ids": route_ids},
+                headers=admin_headers,
+            )
+            assert_true(status == 200, f"create limited api key failed: {status} {key_resp}")
+            limited_headers = {
+                "authorization": f"Bearer {key_resp['data']['key']}",
+                "anthropic-version": "2023-06-01",
+            }
+            anth_payload = {
+                "model": "nyro-claude",
+                "max_tokens": 64,
+                "messages": [{"role": "user", "content": "hello"}],
+            }
+            status, resp = http_request(
+                "POST", f"{proxy_base}/v1/messages", payload=anth_payload, headers=limited_headers
+            )
+            assert_true(status == 200, f"first limited request failed: {status} {resp}")
+            status, resp = http_request(
+                "POST", f"{proxy_base}/v1/messages", payload=anth_payload, headers=limited_headers
+            )
+            assert_true(status == 429, f"expected 429 for rpm=1 key, got {status} {resp}")
+            assert_true(
+                resp.get("type") == "error" and resp["error"]["type"] == "rate_limit_error",
+                f"rate limit error not in anthropic shape: {resp}",
+            )
+
             # Logs should exist after traffic.
             total_logs = 0
             for _ in range(20):
@@ -535,12 +1600,17 @@ def run_smoke() -> None:
                 )
                 assert_true(status == 200, f"query logs failed: {status} {logs_resp}")
                 total_logs = int(logs_resp["data"]["total"])
-                if total_logs >= 3:
+                if total_logs >= 11:
                     break
                 time.sleep(0.3)
-            assert_true(total_logs >= 3, f"expected log entries after traffic, got {total_logs}")
+            assert_true(total_logs >= 11, f"expected log entries after traffic, got {total_logs}")
+
+            # Prompt-cache hits reported by the Anthropic mock reach the stats.
+            status, overview = http_request("GET", f"{admin_base}/api/v1/stats/overview", headers=admin_headers)
+            cached = (overview.get("data") or {}).get("total_cache_read_tokens", 0) if status == 200 else 0
+            assert_true(cached >= 90, f"cached prompt tokens missing from stats: {status} {overview}")
 
-            print("Smoke test passed: admin auth + route API key auth + OpenAI/Anthropic/Gemini flows")
+            print("Smoke test passed: admin auth + route API key auth + OpenAI/Anthropic/Gemini flows + route fallback")
 
     finally:
         if proc is not None and proc.poll() is None:
@@ -1,5 +1,5 @@
 use axum::extract::{Path, Query, Request, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::middleware::{self, Next};
 use axum::response::IntoResponse;
 use axum::routing::{get, put};
@@ -47,23 +47,39 @@ pub fn create_router(gateway: Gateway, admin_key: Option<String>) -> Router {
         .delete(delete_provider_handler);
 
     let routes_item = put(update_route_handler).delete(delete_route_handler);
+    let route_targets_item = put(update_route_target_handler).delete(delete_route_target_handler);
     let api_keys_item = get(get_api_key_handler)
         .put(update_api_key_handler)
         .delete(delete_api_key_handler);
 
     let mut api = Router::new()
         .route("/providers/presets", get(list_provider_presets))
+        .route("/providers/health", get(list_provider_health_handler))
         .route("/providers", get(list_providers).post(create_provider_handler))
         .route("/providers/:id", providers_item)
         .route("/providers/:id/test", get(test_provider_handler))
+        .route(
+            "/providers/:id/reveal-key",
+            axum::routing::post(reveal_provider_key_handler),
+        )
         .route("/providers/:id/test-models", get(test_provider_models_handler))
         .route("/providers/:id/models", get(provider_models_handler))
         .route("/providers/:id/model-capabilities", get(provider_model_capabilities_handler))
+        .route(
+            "/providers/:id/health/reset",
+            axum::routing::post(reset_provider_health_handler),
+        )
         .route("/routes", get(list_routes_handler).post(create_route_handler))
         .route("/routes/:id", routes_item)
+        .route(
+            "/routes/:id/targets",
+            get(list_route_targets_handler).post(create_route_target_handler),
+        )
+        .route("/routes/:id/targets/:target_id", route_targets_item)
         .route("/api-keys", get(list_api_keys_handler).post(create_api_key_handler))
         .route("/api-keys/:id", api_keys_item)
         .route("/logs", get(query_logs_handler))
+        .route("/audit-logs", get(list_audit_logs_handler))
         .route("/stats/overview", get(stats_overview))
         .route("/stats/hourly", get(stats_hourly))
         .route("/stats/models", get(stats_by_model))
@@ -188,6 +204,39 @@ struct ModelCapabilitiesQuery {
     model: String,
 }
 
+async fn reveal_provider_key_handler(
+    State(gw): State<Gateway>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown");
+    let actor = format!("admin-api ({user_agent})");
+    match gw.admin().reveal_provider_key(&id, &actor).await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
+async fn list_provider_health_handler(State(gw): State<Gateway>) -> impl IntoResponse {
+    match gw.admin().list_provider_health().await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
+async fn reset_provider_health_handler(
+    State(gw): State<Gateway>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match gw.admin().reset_provider_health(&id).await {
+        Ok(()) => Json(serde_json::json!({ "ok": true })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
 // ── Routes ──
 
 async fn list_routes_handler(State(gw): State<Gateway>) -> impl IntoResponse {
@@ -228,6 +277,48 @@ async fn delete_route_handler(
     }
 }
 
+async fn list_route_targets_handler(
+    State(gw): State<Gateway>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match gw.admin().list_route_targets(&id).await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
+async fn create_route_target_handler(
+    State(gw): State<Gateway>,
+    Path(id): Path<String>,
+    Json(input): Json<CreateRouteTarget>,
+) -> impl IntoResponse {
+    match gw.admin().create_route_target(&id, input).await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
+async fn update_route_target_handler(
+    State(gw): State<Gateway>,
+    Path((id, target_id)): Path<(String, String)>,
+    Json(input): Json<UpdateRouteTarget>,
+) -> impl IntoResponse {
+    match gw.admin().update_route_target(&id, &target_id, input).await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
+async fn delete_route_target_handler(
+    State(gw): State<Gateway>,
+    Path((id, target_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match gw.admin().delete_route_target(&id, &target_id).await {
+        Ok(()) => Json(serde_json::json!({ "ok": true })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
 // ── API Keys ──
 
 async fn list_api_keys_handler(State(gw): State<Gateway>) -> impl IntoResponse {
@@ -308,6 +399,21 @@ async fn query_logs_handler(
     }
 }
 
+#[derive(Deserialize, Default)]
+struct AuditLogParams {
+    limit: Option<i64>,
+}
+
+async fn list_audit_logs_handler(
+    State(gw): State<Gateway>,
+    Query(params): Query<AuditLogParams>,
+) -> impl IntoResponse {
+    match gw.admin().list_audit_logs(params.limit).await {
+        Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
+        Err(e) => err(e),
+    }
+}
+
 // ── Stats ──
 
 #[derive(Deserialize, Default)]
@@ -396,16 +502,33 @@ async fn set_setting(
 // ── Status ──
 
 async fn get_status(State(gw): State<Gateway>) -> impl IntoResponse {
+    let circuits = gw.admin().list_provider_health().await.unwrap_or_default();
     Json(serde_json::json!({
         "status": "running",
         "proxy_port": gw.config.proxy_port,
+        "circuits": circuits,
     }))
 }
 
 // ── Config Import/Export ──
 
-async fn export_config_handler(State(gw): State<Gateway>) -> impl IntoResponse {
-    match gw.admin().export_config().await {
+#[derive(Deserialize)]
+struct ExportConfigQuery {
+    #[serde(default)]
+    reveal_keys: bool,
+}
+
+async fn export_config_handler(
+    State(gw): State<Gateway>,
+    Query(q): Query<ExportConfigQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown");
+    let actor = format!("admin-api ({user_agent})");
+    match gw.admin().export_config(q.reveal_keys, &actor).await {
         Ok(v) => Json(serde_json::json!({ "data": v })).into_response(),
         Err(e) => err(e),
     }
@@ -44,6 +44,55 @@ struct Args {
 
     #[arg(long, default_value = "./webui/dist", help = "Path to webui static files")]
     webui_dir: String,
+
+    #[arg(
+        long,
+        default_value = "5",
+        help = "Consecutive upstream failures before a provider circuit opens"
+    )]
+    circuit_failure_threshold: u32,
+
+    #[arg(
+        long,
+        default_value = "30",
+        help = "Seconds an open provider circuit waits before admitting a probe"
+    )]
+    circuit_cooldown_secs: u64,
+
+    #[arg(
+        long,
+        default_value = "10",
+        help = "Seconds a request waits for a busy provider's concurrency slot (0 = reject immediately)"
+    )]
+    provider_queue_timeout_secs: u64,
+
+    #[arg(
+        long,
+        help = "Download remote image URLs for upstreams that only accept inline images (Ollama, Bedrock, Gemini)"
+    )]
+    fetch_remote_media: bool,
+
+    #[arg(
+        long,
+        default_value = "20971520",
+        help = "Largest remote image in bytes the proxy will download"
+    )]
+    media_fetch_max_bytes: u64,
+
+    #[arg(long, default_value = "10", help = "Seconds allowed for one remote image download")]
+    media_fetch_timeout_secs: u64,
+
+    #[arg(
+        long,
+        help = "Allow remote media downloads from loopback, private and link-local addresses"
+    )]
+    media_fetch_allow_private: bool,
+
+    #[arg(
+        long,
+        help = "Send the extracted text of PDF attachments to upstreams without native document support"
+    )]
+    document_text_fallback: bool,
 }
 
 #[tokio::main]
@@ -78,6 +127,14 @@ async fn main() -> anyhow::Result<()> {
         proxy_port: args.proxy_port,
         proxy_cors_origins,
         data_dir: PathBuf::from(data_dir),
+        circuit_failure_threshold: args.circuit_failure_threshold,
+        circuit_cooldown_secs: args.circuit_cooldown_secs,
+        provider_queue_timeout_secs: args.provider_queue_timeout_secs,
+        fetch_remote_media: args.fetch_remote_media,
+        media_fetch_max_bytes: args.media_fetch_max_bytes,
+        media_fetch_timeout_secs: args.media_fetch_timeout_secs,
+        media_fetch_allow_private: args.media_fetch_allow_private,
+        document_text_fallback: args.document_text_fallback,
         ..Default::default()
     };
 
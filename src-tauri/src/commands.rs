@@ -49,6 +49,26 @@ pub async fn delete_provider(gw: State<'_, Gateway>, id: String) -> Result<(), S
     gw.admin().delete_provider(&id).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn reveal_provider_key(gw: State<'_, Gateway>, id: String) -> Result<String, String> {
+    gw.admin()
+        .reveal_provider_key(&id, "desktop")
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_provider_health(
+    gw: State<'_, Gateway>,
+) -> Result<Vec<nyro_core::proxy::circuit::CircuitStatus>, String> {
+    gw.admin().list_provider_health().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reset_provider_health(gw: State<'_, Gateway>, id: String) -> Result<(), String> {
+    gw.admin().reset_provider_health(&id).await.map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn test_provider(gw: State<'_, Gateway>, id: String) -> Result<TestResult, String> {
     gw.admin().test_provider(&id).await.map_err(|e| e.to_string())
@@ -114,6 +134,51 @@ pub async fn delete_route(gw: State<'_, Gateway>, id: String) -> Result<(), Stri
     gw.admin().delete_route(&id).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn list_route_targets(
+    gw: State<'_, Gateway>,
+    route_id: String,
+) -> Result<Vec<RouteTarget>, String> {
+    gw.admin().list_route_targets(&route_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_route_target(
+    gw: State<'_, Gateway>,
+    route_id: String,
+    input: CreateRouteTarget,
+) -> Result<RouteTarget, String> {
+    gw.admin()
+        .create_route_target(&route_id, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_route_target(
+    gw: State<'_, Gateway>,
+    route_id: String,
+    id: String,
+    input: UpdateRouteTarget,
+) -> Result<RouteTarget, String> {
+    gw.admin()
+        .update_route_target(&route_id, &id, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_route_target(
+    gw: State<'_, Gateway>,
+    route_id: String,
+    id: String,
+) -> Result<(), String> {
+    gw.admin()
+        .delete_route_target(&route_id, &id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ── API Keys ──
 
 #[tauri::command]
@@ -217,21 +282,39 @@ pub async fn set_setting(
     gw.admin().set_setting(&key, &value).await.map_err(|e| e.to_string())
 }
 
+// ── Audit ──
+
+#[tauri::command]
+pub async fn list_audit_logs(
+    gw: State<'_, Gateway>,
+    limit: Option<i64>,
+) -> Result<Vec<AuditLog>, String> {
+    gw.admin().list_audit_logs(limit).await.map_err(|e| e.to_string())
+}
+
 // ── Status ──
 
 #[tauri::command]
 pub async fn get_gateway_status(gw: State<'_, Gateway>) -> Result<serde_json::Value, String> {
+    let circuits = gw.admin().list_provider_health().await.unwrap_or_default();
     Ok(serde_json::json!({
         "status": "running",
         "proxy_port": gw.config.proxy_port,
+        "circuits": circuits,
     }))
 }
 
 // ── Config Import/Export ──
 
 #[tauri::command]
-pub async fn export_config(gw: State<'_, Gateway>) -> Result<ExportData, String> {
-    gw.admin().export_config().await.map_err(|e| e.to_string())
+pub async fn export_config(
+    gw: State<'_, Gateway>,
+    reveal_keys: Option<bool>,
+) -> Result<ExportData, String> {
+    gw.admin()
+        .export_config(reveal_keys.unwrap_or(false), "desktop")
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
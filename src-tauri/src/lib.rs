@@ -64,6 +64,9 @@ pub fn run() {
             commands::create_provider,
             commands::update_provider,
             commands::delete_provider,
+            commands::reveal_provider_key,
+            commands::list_provider_health,
+            commands::reset_provider_health,
             commands::test_provider,
             commands::test_provider_models,
             commands::get_provider_models,
@@ -72,6 +75,10 @@ pub fn run() {
             commands::create_route,
             commands::update_route,
             commands::delete_route,
+            commands::list_route_targets,
+            commands::create_route_target,
+            commands::update_route_target,
+            commands::delete_route_target,
             commands::list_api_keys,
             commands::get_api_key,
             commands::create_api_key,
@@ -84,6 +91,7 @@ pub fn run() {
             commands::get_stats_by_provider,
             commands::get_setting,
             commands::set_setting,
+            commands::list_audit_logs,
             commands::get_gateway_status,
             commands::export_config,
             commands::import_config,
@@ -53,6 +53,8 @@ function resolveHTTP(cmd: string, args?: Record<string, unknown>): HTTPMapping {
       return { method: "DELETE", url: `${base}/providers/${args?.id}` };
     case "test_provider":
       return { method: "GET", url: `${base}/providers/${args?.id}/test` };
+    case "reveal_provider_key":
+      return { method: "POST", url: `${base}/providers/${args?.id}/reveal-key` };
     case "test_provider_models":
       return { method: "GET", url: `${base}/providers/${args?.id}/test-models` };
     case "get_provider_models":
@@ -93,6 +95,9 @@ function resolveHTTP(cmd: string, args?: Record<string, unknown>): HTTPMapping {
       return { method: "GET", url: `${base}/logs${qs ? "?" + qs : ""}` };
     }
 
+    case "list_audit_logs":
+      return { method: "GET", url: `${base}/audit-logs${args?.limit != null ? `?limit=${args.limit}` : ""}` };
+
     case "get_stats_overview": {
       const hours = args?.hours;
       return {
@@ -128,7 +133,10 @@ function resolveHTTP(cmd: string, args?: Record<string, unknown>): HTTPMapping {
       return { method: "GET", url: `${base}/status` };
 
     case "export_config":
-      return { method: "GET", url: `${base}/config/export` };
+      return {
+        method: "GET",
+        url: `${base}/config/export${args?.reveal_keys ? "?reveal_keys=true" : ""}`,
+      };
     case "import_config":
       return { method: "POST", url: `${base}/config/import`, body: args?.data as Record<string, unknown> };
 
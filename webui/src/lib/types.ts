@@ -11,6 +11,16 @@ export interface Provider {
   models_source?: string | null;
   capabilities_source?: string | null;
   static_models?: string | null;
+  max_concurrency?: number | null;
+  rpm?: number | null;
+  tpm?: number | null;
+  retry_max_attempts?: number | null;
+  retry_base_delay_ms?: number | null;
+  retry_max_delay_ms?: number | null;
+  api_version?: string | null;
+  access_key_id?: string | null;
+  cloud_region?: string | null;
+  api_dialect?: string | null;
   last_test_success?: boolean | null;
   last_test_at?: string | null;
   is_active: boolean;
@@ -21,15 +31,30 @@ export interface Provider {
 export interface Route {
   id: string;
   name: string;
-  ingress_protocol: "openai" | "anthropic" | "gemini";
+  ingress_protocol: "openai" | "anthropic" | "gemini" | "ollama";
   virtual_model: string;
   target_provider: string;
   target_model: string;
+  fallback_provider?: string | null;
+  fallback_model?: string | null;
+  strategy?: "round_robin" | "weighted" | "least_latency";
+  priority?: number;
   access_control: boolean;
   is_active: boolean;
   created_at: string;
 }
 
+export interface RouteTarget {
+  id: string;
+  route_id: string;
+  provider_id: string;
+  model: string;
+  weight: number;
+  priority: number;
+  is_active: boolean;
+  created_at: string;
+}
+
 export interface ApiKey {
   id: string;
   key: string;
@@ -57,6 +82,8 @@ export interface RequestLog {
   duration_ms?: number;
   input_tokens: number;
   output_tokens: number;
+  cache_read_tokens: number;
+  cache_creation_tokens: number;
   is_stream: boolean;
   is_tool_call: boolean;
   error_message?: string;
@@ -67,15 +94,29 @@ export interface LogPage {
   total: number;
 }
 
+export interface CircuitStatus {
+  provider_id: string;
+  provider_name?: string | null;
+  state: "closed" | "open" | "half_open";
+  consecutive_failures: number;
+  total_failures: number;
+  total_successes: number;
+  last_error?: string | null;
+  retry_in_secs?: number | null;
+}
+
 export interface GatewayStatus {
   status: string;
   proxy_port: number;
+  circuits?: CircuitStatus[];
 }
 
 export interface StatsOverview {
   total_requests: number;
   total_input_tokens: number;
   total_output_tokens: number;
+  total_cache_read_tokens: number;
+  total_cache_creation_tokens: number;
   avg_duration_ms: number;
   error_count: number;
 }
@@ -86,6 +127,8 @@ export interface StatsHourly {
   error_count: number;
   total_input_tokens: number;
   total_output_tokens: number;
+  total_cache_read_tokens: number;
+  total_cache_creation_tokens: number;
   avg_duration_ms: number;
 }
 
@@ -94,6 +137,8 @@ export interface ModelStats {
   request_count: number;
   total_input_tokens: number;
   total_output_tokens: number;
+  total_cache_read_tokens: number;
+  total_cache_creation_tokens: number;
   avg_duration_ms: number;
 }
 
@@ -124,7 +169,7 @@ export interface ModelCapabilities {
   output_cost?: number | null;
 }
 
-export type ProviderProtocol = "openai" | "anthropic" | "gemini";
+export type ProviderProtocol = "openai" | "anthropic" | "gemini" | "ollama" | "bedrock";
 
 export interface ProviderChannelPreset {
   id: string;
@@ -163,6 +208,16 @@ export interface CreateProvider {
   capabilities_source?: string;
   static_models?: string;
   api_key: string;
+  max_concurrency?: number;
+  rpm?: number;
+  tpm?: number;
+  retry_max_attempts?: number;
+  retry_base_delay_ms?: number;
+  retry_max_delay_ms?: number;
+  api_version?: string;
+  access_key_id?: string;
+  cloud_region?: string;
+  api_dialect?: string;
 }
 
 export interface UpdateProvider {
@@ -177,12 +232,22 @@ export interface UpdateProvider {
   capabilities_source?: string;
   static_models?: string;
   api_key?: string;
+  max_concurrency?: number;
+  rpm?: number;
+  tpm?: number;
+  retry_max_attempts?: number;
+  retry_base_delay_ms?: number;
+  retry_max_delay_ms?: number;
+  api_version?: string;
+  access_key_id?: string;
+  cloud_region?: string;
+  api_dialect?: string;
   is_active?: boolean;
 }
 
 export interface CreateRoute {
   name: string;
-  ingress_protocol: "openai" | "anthropic" | "gemini";
+  ingress_protocol: "openai" | "anthropic" | "gemini" | "ollama";
   virtual_model: string;
   target_provider: string;
   target_model: string;
@@ -191,7 +256,7 @@ export interface CreateRoute {
 
 export interface UpdateRoute {
   name?: string;
-  ingress_protocol?: "openai" | "anthropic" | "gemini";
+  ingress_protocol?: "openai" | "anthropic" | "gemini" | "ollama";
   virtual_model?: string;
   target_provider?: string;
   target_model?: string;
@@ -231,6 +296,7 @@ export interface LogQuery {
 
 export interface ExportData {
   version: number;
+  keys_masked?: boolean;
   providers: ExportProvider[];
   routes: ExportRoute[];
   settings: [string, string][];
@@ -253,16 +319,29 @@ export interface ExportProvider {
 
 export interface ExportRoute {
   name: string;
-  ingress_protocol: "openai" | "anthropic" | "gemini";
+  ingress_protocol: "openai" | "anthropic" | "gemini" | "ollama";
   virtual_model: string;
   target_provider_name: string;
   target_model: string;
+  fallback_provider_name?: string | null;
+  fallback_model?: string | null;
+  strategy?: string | null;
+  targets?: ExportRouteTarget[];
   access_control: boolean;
   is_active: boolean;
 }
 
+export interface ExportRouteTarget {
+  provider_name: string;
+  model: string;
+  weight: number;
+  priority: number;
+  is_active: boolean;
+}
+
 export interface ImportResult {
   providers_imported: number;
   routes_imported: number;
   settings_imported: number;
+  targets_skipped: number;
 }
@@ -190,7 +190,10 @@ export default function LogsPage() {
                     <td className="px-4 py-2.5 text-right text-xs">
                       {log.duration_ms != null ? `${log.duration_ms.toFixed(0)}ms` : "–"}
                     </td>
-                    <td className="px-4 py-2.5 text-right text-xs">
+                    <td
+                      className="px-4 py-2.5 text-right text-xs"
+                      title={log.cache_read_tokens ? `${log.cache_read_tokens} ${isZh ? "命中缓存" : "cached"}` : undefined}
+                    >
                       {log.input_tokens + log.output_tokens}
                     </td>
                     <td className="px-4 py-2.5 text-center text-xs">
@@ -55,6 +55,8 @@ function protocolUrl(protocol: string) {
   switch (protocol) {
     case "anthropic": return "https://api.anthropic.com";
     case "gemini": return "https://generativelanguage.googleapis.com";
+    case "ollama": return "http://127.0.0.1:11434";
+    case "bedrock": return "https://bedrock-runtime.us-east-1.amazonaws.com";
     default: return "https://api.openai.com";
   }
 }
@@ -78,6 +80,8 @@ const protocolOptions = [
   { label: "OpenAI", value: "openai" },
   { label: "Anthropic", value: "anthropic" },
   { label: "Gemini", value: "gemini" },
+  { label: "Ollama", value: "ollama" },
+  { label: "Bedrock", value: "bedrock" },
 ] as const satisfies ReadonlyArray<{ label: string; value: ProviderProtocol }>;
 
 function availableProtocolsForPreset(
@@ -165,6 +169,10 @@ function defaultModelsEndpoint(baseUrl: string, protocol: ProviderProtocol) {
     return `${normalized}/v1beta/models`;
   }
 
+  if (protocol === "ollama") {
+    return `${normalized.replace(/\/v1$/, "")}/api/tags`;
+  }
+
   return "";
 }
 
@@ -556,6 +564,10 @@ export default function ProvidersPage() {
       capabilities_source: p.capabilities_source ?? "",
       static_models: p.static_models ?? "",
       api_key: p.api_key ?? "",
+      api_version: p.api_version ?? "",
+      access_key_id: p.access_key_id ?? "",
+      cloud_region: p.cloud_region ?? "",
+      api_dialect: p.api_dialect ?? "",
     });
   }
 
@@ -878,8 +890,71 @@ export default function ProvidersPage() {
                   onChange={(e) => setForm({ ...form, base_url: e.target.value })}
                 />
               </div>
+              {form.protocol === "openai" && (
+                <div className="space-y-2">
+                  <FieldLabel
+                    info={isZh ? "Responses 会保留推理条目和内置工具" : "Responses keeps reasoning items and built-in tools"}
+                  >
+                    {isZh ? "接口" : "API"}
+                  </FieldLabel>
+                  <Select
+                    value={form.api_dialect || "chat"}
+                    onValueChange={(value) => setForm({ ...form, api_dialect: value === "chat" ? "" : value })}
+                  >
+                    <SelectTrigger>
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="chat">Chat Completions</SelectItem>
+                      <SelectItem value="responses">Responses</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+              )}
+              {form.vendor === "azure" && (
+                <div className="space-y-2">
+                  <FieldLabel
+                    info={isZh ? "路由的目标模型即 Azure 部署名称" : "A route's target model is the Azure deployment name"}
+                  >
+                    API Version
+                  </FieldLabel>
+                  <Input
+                    placeholder="2024-10-21"
+                    value={form.api_version ?? ""}
+                    onChange={(e) => setForm({ ...form, api_version: e.target.value })}
+                  />
+                </div>
+              )}
+              {form.protocol === "bedrock" && (
+                <div className="space-y-2">
+                  <FieldLabel
+                    info={isZh ? "留空则把 API Key 作为 Bedrock API Key 使用" : "Leave empty to use the API key as a Bedrock API key"}
+                  >
+                    Access Key ID
+                  </FieldLabel>
+                  <Input
+                    placeholder="AKIA..."
+                    value={form.access_key_id ?? ""}
+                    onChange={(e) => setForm({ ...form, access_key_id: e.target.value })}
+                  />
+                </div>
+              )}
+              {(form.protocol === "bedrock" || form.vendor === "vertex") && (
+                <div className="space-y-2">
+                  <FieldLabel>{isZh ? "区域" : "Region"}</FieldLabel>
+                  <Input
+                    placeholder={form.vendor === "vertex" ? "us-central1" : "us-east-1"}
+                    value={form.cloud_region ?? ""}
+                    onChange={(e) => setForm({ ...form, cloud_region: e.target.value })}
+                  />
+                </div>
+              )}
               <div className="space-y-2">
-                <FieldLabel>API Key</FieldLabel>
+                <FieldLabel
+                  info={form.vendor === "vertex" ? (isZh ? "粘贴服务账号 JSON 密钥" : "Paste the service account JSON key") : undefined}
+                >
+                  API Key
+                </FieldLabel>
                 <div className="relative">
                   <Input
                     placeholder="sk-..."
@@ -1144,8 +1219,71 @@ export default function ProvidersPage() {
                         onChange={(e) => setEditForm({ ...editForm, base_url: e.target.value })}
                       />
                     </div>
+                    {editForm.protocol === "openai" && (
+                      <div className="space-y-2">
+                        <FieldLabel
+                          info={isZh ? "Responses 会保留推理条目和内置工具" : "Responses keeps reasoning items and built-in tools"}
+                        >
+                          {isZh ? "接口" : "API"}
+                        </FieldLabel>
+                        <Select
+                          value={editForm.api_dialect || "chat"}
+                          onValueChange={(value) => setEditForm({ ...editForm, api_dialect: value === "chat" ? "" : value })}
+                        >
+                          <SelectTrigger>
+                            <SelectValue />
+                          </SelectTrigger>
+                          <SelectContent>
+                            <SelectItem value="chat">Chat Completions</SelectItem>
+                            <SelectItem value="responses">Responses</SelectItem>
+                          </SelectContent>
+                        </Select>
+                      </div>
+                    )}
+                    {editForm.vendor === "azure" && (
+                      <div className="space-y-2">
+                        <FieldLabel
+                          info={isZh ? "路由的目标模型即 Azure 部署名称" : "A route's target model is the Azure deployment name"}
+                        >
+                          API Version
+                        </FieldLabel>
+                        <Input
+                          placeholder="2024-10-21"
+                          value={editForm.api_version ?? ""}
+                          onChange={(e) => setEditForm({ ...editForm, api_version: e.target.value })}
+                        />
+                      </div>
+                    )}
+                    {editForm.protocol === "bedrock" && (
+                      <div className="space-y-2">
+                        <FieldLabel
+                          info={isZh ? "留空则把 API Key 作为 Bedrock API Key 使用" : "Leave empty to use the API key as a Bedrock API key"}
+                        >
+                          Access Key ID
+                        </FieldLabel>
+                        <Input
+                          placeholder="AKIA..."
+                          value={editForm.access_key_id ?? ""}
+                          onChange={(e) => setEditForm({ ...editForm, access_key_id: e.target.value })}
+                        />
+                      </div>
+                    )}
+                    {(editForm.protocol === "bedrock" || editForm.vendor === "vertex") && (
+                      <div className="space-y-2">
+                        <FieldLabel>{isZh ? "区域" : "Region"}</FieldLabel>
+                        <Input
+                          placeholder={editForm.vendor === "vertex" ? "us-central1" : "us-east-1"}
+                          value={editForm.cloud_region ?? ""}
+                          onChange={(e) => setEditForm({ ...editForm, cloud_region: e.target.value })}
+                        />
+                      </div>
+                    )}
                     <div className="space-y-2">
-                      <FieldLabel>{isZh ? "API Key" : "API Key"}</FieldLabel>
+                      <FieldLabel
+                        info={editForm.vendor === "vertex" ? (isZh ? "粘贴服务账号 JSON 密钥" : "Paste the service account JSON key") : undefined}
+                      >
+                        API Key
+                      </FieldLabel>
                       <div className="relative">
                         <Input
                           placeholder="sk-..."
@@ -1213,6 +1351,10 @@ export default function ProvidersPage() {
                           capabilities_source: editForm.capabilities_source || undefined,
                           static_models: editForm.static_models || undefined,
                           api_key: editForm.api_key || undefined,
+                          api_version: editForm.api_version ?? undefined,
+                          access_key_id: editForm.access_key_id ?? undefined,
+                          cloud_region: editForm.cloud_region ?? undefined,
+                          api_dialect: editForm.api_dialect ?? undefined,
                         };
                         updateMut.mutate({ id: editForm.id, ...input });
                       }}
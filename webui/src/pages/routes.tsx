@@ -25,7 +25,7 @@ const PAGE_SIZE = 6;
 
 type RouteForm = {
   name: string;
-  ingress_protocol: "openai" | "anthropic" | "gemini";
+  ingress_protocol: "openai" | "anthropic" | "gemini" | "ollama";
   virtual_model: string;
   target_provider: string;
   target_model: string;
@@ -75,6 +75,7 @@ function ModelCapabilitiesPanel({ caps, isZh }: { caps: ModelCapabilities; isZh:
 function protocolLabel(value: RouteForm["ingress_protocol"]) {
   if (value === "anthropic") return "Anthropic";
   if (value === "gemini") return "Gemini";
+  if (value === "ollama") return "Ollama";
   return "OpenAI";
 }
 
@@ -356,7 +357,7 @@ export default function RoutesPage() {
               <FieldLabel>{isZh ? "接入协议" : "Ingress Protocol"}</FieldLabel>
               <Select
                 value={createForm.ingress_protocol}
-                onValueChange={(value: "openai" | "anthropic" | "gemini") =>
+                onValueChange={(value: "openai" | "anthropic" | "gemini" | "ollama") =>
                   setCreateForm((prev) => ({ ...prev, ingress_protocol: value }))
                 }
               >
@@ -367,6 +368,7 @@ export default function RoutesPage() {
                   <SelectItem value="openai">OpenAI</SelectItem>
                   <SelectItem value="anthropic">Anthropic</SelectItem>
                   <SelectItem value="gemini">Gemini</SelectItem>
+                  <SelectItem value="ollama">Ollama</SelectItem>
                 </SelectContent>
               </Select>
             </div>
@@ -532,7 +534,7 @@ export default function RoutesPage() {
                       <FieldLabel>{isZh ? "接入协议" : "Ingress Protocol"}</FieldLabel>
                       <Select
                         value={editForm.ingress_protocol}
-                        onValueChange={(value: "openai" | "anthropic" | "gemini") =>
+                        onValueChange={(value: "openai" | "anthropic" | "gemini" | "ollama") =>
                           setEditForm((prev) => (prev ? { ...prev, ingress_protocol: value } : prev))
                         }
                       >
@@ -543,6 +545,7 @@ export default function RoutesPage() {
                           <SelectItem value="openai">OpenAI</SelectItem>
                           <SelectItem value="anthropic">Anthropic</SelectItem>
                           <SelectItem value="gemini">Gemini</SelectItem>
+                          <SelectItem value="ollama">Ollama</SelectItem>
                         </SelectContent>
                       </Select>
                     </div>
@@ -296,8 +296,8 @@ curl ${baseUrl}/v1/messages \\
             {importMut.isSuccess && importMut.data && (
               <p className="text-xs text-green-600">
                 {isZh
-                  ? `已导入：${(importMut.data as ImportResult).providers_imported} 个提供商，${(importMut.data as ImportResult).routes_imported} 条路由，${(importMut.data as ImportResult).settings_imported} 项设置`
-                  : `Imported: ${(importMut.data as ImportResult).providers_imported} providers, ${(importMut.data as ImportResult).routes_imported} routes, ${(importMut.data as ImportResult).settings_imported} settings`}
+                  ? `已导入：${(importMut.data as ImportResult).providers_imported} 个提供商，${(importMut.data as ImportResult).routes_imported} 条路由，${(importMut.data as ImportResult).settings_imported} 项设置${(importMut.data as ImportResult).targets_skipped ? `，跳过 ${(importMut.data as ImportResult).targets_skipped} 个路由目标` : ""}`
+                  : `Imported: ${(importMut.data as ImportResult).providers_imported} providers, ${(importMut.data as ImportResult).routes_imported} routes, ${(importMut.data as ImportResult).settings_imported} settings${(importMut.data as ImportResult).targets_skipped ? `, skipped ${(importMut.data as ImportResult).targets_skipped} route targets` : ""}`}
               </p>
             )}
           </div>
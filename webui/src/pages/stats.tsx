@@ -95,7 +95,15 @@ export default function StatsPage() {
       <div className="grid grid-cols-2 gap-3 lg:grid-cols-4">
         {[
           { label: isZh ? "总请求数" : "Total Requests", value: fmt(overview?.total_requests ?? 0), icon: Activity, color: "text-blue-600" },
-          { label: isZh ? "输入 Token" : "Input Tokens", value: fmt(overview?.total_input_tokens ?? 0), icon: Zap, color: "text-amber-600" },
+          {
+            label: isZh ? "输入 Token" : "Input Tokens",
+            value: fmt(overview?.total_input_tokens ?? 0),
+            note: overview?.total_cache_read_tokens
+              ? `${fmt(overview.total_cache_read_tokens)} ${isZh ? "命中缓存" : "cached"}`
+              : undefined,
+            icon: Zap,
+            color: "text-amber-600",
+          },
           { label: isZh ? "输出 Token" : "Output Tokens", value: fmt(overview?.total_output_tokens ?? 0), icon: Zap, color: "text-green-600" },
           { label: isZh ? "平均延迟" : "Avg Latency", value: `${(overview?.avg_duration_ms ?? 0).toFixed(0)}ms`, icon: Clock, color: "text-purple-600" },
         ].map((c) => (
@@ -107,6 +115,7 @@ export default function StatsPage() {
             <p className="mt-1.5 text-[24px] leading-none font-semibold text-slate-900">
               {c.label === (isZh ? "平均延迟" : "Avg Latency") ? fmtLatency(overview?.avg_duration_ms ?? 0) : c.value}
             </p>
+            {c.note && <p className="mt-1 text-xs text-slate-500">{c.note}</p>}
           </div>
         ))}
       </div>